@@ -0,0 +1,349 @@
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+use aquarius_sdk::anchor_client::solana_sdk::pubkey::Pubkey;
+use aquarius_sdk::anchor_client::solana_sdk::signature::{read_keypair_file, Signer};
+use aquarius_sdk::chain::state::{
+    FeeSchedule, ValueKind, PAUSE_ALL, PAUSE_FCD_UPDATE, PAUSE_SUBMIT, PAUSE_VERIFY,
+};
+use aquarius_sdk::{decode_hex_array, ChainClient};
+
+#[derive(clap::Subcommand)]
+pub enum AdminCommand {
+    /// Sets the minimum number of seconds between submitted data
+    SetPadding { padding: u32 },
+    /// Sets the longest accepted proof, 0 for the program default
+    SetMaxProofLength { max_proof_length: u8 },
+    /// Sets the key allowed to pause and unpause besides the owner
+    SetGuardian {
+        /// Guardian public key, omit to remove the guardian
+        guardian: Option<Pubkey>,
+    },
+    /// Stops instructions, signed by the owner or the guardian
+    Pause(PauseFlags),
+    /// Resumes instructions, signed by the owner or the guardian
+    Unpause(PauseFlags),
+    /// Lists a feed initialized before the feed registry existed
+    RegisterFcd { key: String },
+    /// Sets the decimals, kind and description of a registered feed
+    SetFcdMetadata {
+        key: String,
+        #[clap(long)]
+        decimals: u8,
+        #[clap(long, arg_enum)]
+        kind: Kind,
+        #[clap(long, default_value = "")]
+        description: String,
+    },
+    /// Starts recording the last values of a feed on every update
+    InitFcdHistory {
+        key: String,
+        /// Number of values kept, at most 128
+        #[clap(long)]
+        capacity: u16,
+    },
+    /// Replaces the validators allowed to submit observations of aggregated feeds
+    SetValidators {
+        /// Submissions needed to finalize a round
+        #[clap(long)]
+        quorum: u8,
+        /// Seconds after its first submission during which a round can finalize
+        #[clap(long)]
+        window: u32,
+        validators: Vec<Pubkey>,
+    },
+    /// Replaces the Ethereum addresses of the sidechain validators importing blocks
+    SetEvmValidators {
+        /// Distinct validator signatures needed to import a block
+        #[clap(long)]
+        quorum: u8,
+        /// 20 byte hex addresses
+        #[clap(parse(try_from_str = decode_hex_array::<20>))]
+        validators: Vec<[u8; 20]>,
+    },
+    /// Switches a feed to the median of validator observations
+    InitFcdAggregation { key: String },
+    /// Closes a retired feed, which can't be initialized again for a week
+    CloseFcd {
+        key: String,
+        /// Receiver of the account rent, the signing keypair by default
+        #[clap(long)]
+        recipient: Option<Pubkey>,
+    },
+    /// Sets the fees of verification and reads
+    SetFees {
+        /// Lamports, or tokens of `--mint`, per verification
+        #[clap(long, default_value_t = 0)]
+        verify_fee: u64,
+        /// Lamports, or tokens of `--mint`, per read of a feed
+        #[clap(long, default_value_t = 0)]
+        read_fee: u64,
+        /// SPL mint the fees are paid in, lamports when omitted
+        #[clap(long)]
+        mint: Option<Pubkey>,
+        /// Price of a subscription period, 0 to offer no subscriptions
+        #[clap(long, default_value_t = 0)]
+        subscription_price: u64,
+        /// Seconds a subscription period lasts
+        #[clap(long, default_value_t = 0)]
+        subscription_period: u32,
+        /// Keys calling for free
+        #[clap(long)]
+        exempt: Vec<Pubkey>,
+        /// Keeps the schedule but stops charging it
+        #[clap(long)]
+        disable: bool,
+    },
+    /// Sends collected fees to a recipient
+    WithdrawFees {
+        amount: u64,
+        /// Receives the lamports, or the token account receiving the tokens
+        #[clap(long)]
+        recipient: Pubkey,
+        /// Fee vault the tokens are taken from, for fees in tokens
+        #[clap(long)]
+        vault: Option<Pubkey>,
+    },
+    /// Starts crediting a reward point per signature behind submitted data
+    SetRewards {
+        /// Stops crediting points, keeping what was distributed claimable
+        #[clap(long)]
+        disable: bool,
+    },
+    /// Shares collected lamport fees among the keys with reward points
+    DistributeRewards { amount: u64 },
+    /// Sets the token account holding validator stakes and the slashing rate
+    SetStakeConfig {
+        /// Token account with the stake config address as its authority
+        #[clap(long)]
+        vault: Pubkey,
+        /// Token account receiving slashed stake
+        #[clap(long)]
+        treasury: Pubkey,
+        /// Share of a stake slashed per equivocation, in basis points
+        #[clap(long)]
+        slash_bps: u16,
+        /// Seconds unstaked tokens stay slashable before they can be withdrawn
+        #[clap(long)]
+        unbonding_period: u32,
+    },
+    /// Upgrades the authority and status accounts of an older deployment
+    Migrate,
+    /// Hands the program over to a new owner, who has to co-sign
+    TransferOwnership {
+        /// Keypair of the new owner
+        #[clap(long)]
+        new_owner: PathBuf,
+    },
+}
+
+/// What the value of a feed measures.
+#[derive(Clone, clap::ArgEnum)]
+pub enum Kind {
+    Price,
+    Volume,
+    /// 32 bytes without numeric meaning
+    Raw,
+}
+
+impl From<Kind> for ValueKind {
+    fn from(kind: Kind) -> Self {
+        match kind {
+            Kind::Price => ValueKind::Price,
+            Kind::Volume => ValueKind::Volume,
+            Kind::Raw => ValueKind::Raw,
+        }
+    }
+}
+
+/// Instructions to pause or unpause, all of them when none is given.
+#[derive(clap::Args)]
+pub struct PauseFlags {
+    /// `submit` and `submit_with_hash_scheme`
+    #[clap(long)]
+    submit: bool,
+    /// `update_first_class_data`
+    #[clap(long)]
+    fcd: bool,
+    /// `verify_proof_for_block` and `verify_against_latest`
+    #[clap(long)]
+    verify: bool,
+}
+
+impl PauseFlags {
+    fn bits(&self) -> u8 {
+        let bits = [
+            (self.submit, PAUSE_SUBMIT),
+            (self.fcd, PAUSE_FCD_UPDATE),
+            (self.verify, PAUSE_VERIFY),
+        ]
+        .iter()
+        .filter(|(set, _)| *set)
+        .fold(0, |bits, (_, flag)| bits | flag);
+        match bits {
+            0 => PAUSE_ALL,
+            bits => bits,
+        }
+    }
+}
+
+pub fn run(client: &ChainClient, command: AdminCommand) -> Result<()> {
+    match command {
+        AdminCommand::SetPadding { padding } => {
+            let signature = client.set_padding(padding)?;
+            println!("padding set to {} ({})", padding, signature);
+        }
+        AdminCommand::SetMaxProofLength { max_proof_length } => {
+            let signature = client.set_max_proof_length(max_proof_length)?;
+            println!(
+                "max proof length set to {} ({})",
+                max_proof_length, signature
+            );
+        }
+        AdminCommand::SetGuardian { guardian } => {
+            let guardian = guardian.unwrap_or_default();
+            let signature = client.set_guardian(guardian)?;
+            println!("guardian set to {} ({})", guardian, signature);
+        }
+        AdminCommand::Pause(flags) => {
+            let signature = client.pause(flags.bits())?;
+            println!("paused {} ({})", describe_paused(flags.bits()), signature);
+        }
+        AdminCommand::Unpause(flags) => {
+            let signature = client.unpause(flags.bits())?;
+            println!("unpaused {} ({})", describe_paused(flags.bits()), signature);
+        }
+        AdminCommand::RegisterFcd { key } => {
+            let signature = client.register_first_class_data(&key)?;
+            println!("{} registered ({})", key, signature);
+        }
+        AdminCommand::SetFcdMetadata {
+            key,
+            decimals,
+            kind,
+            description,
+        } => {
+            let signature =
+                client.set_first_class_data_metadata(&key, decimals, kind.into(), &description)?;
+            println!("{} metadata set ({})", key, signature);
+        }
+        AdminCommand::InitFcdHistory { key, capacity } => {
+            let signature = client.initialize_fcd_history(&key, capacity)?;
+            println!(
+                "{} history of {} values initialized ({})",
+                key, capacity, signature
+            );
+        }
+        AdminCommand::SetValidators {
+            quorum,
+            window,
+            validators,
+        } => {
+            let count = validators.len();
+            let signature = client.set_validators(validators, quorum, window)?;
+            println!(
+                "{} validators set with quorum {} ({})",
+                count, quorum, signature
+            );
+        }
+        AdminCommand::SetEvmValidators { quorum, validators } => {
+            let count = validators.len();
+            let signature = client.set_evm_validators(validators, quorum)?;
+            println!(
+                "{} EVM validators set with quorum {} ({})",
+                count, quorum, signature
+            );
+        }
+        AdminCommand::InitFcdAggregation { key } => {
+            let signature = client.initialize_fcd_aggregation(&key)?;
+            println!("{} aggregated from validators ({})", key, signature);
+        }
+        AdminCommand::CloseFcd { key, recipient } => {
+            let recipient = recipient.unwrap_or_else(|| client.program().payer());
+            let signature = client.close_first_class_data(&key, recipient)?;
+            println!("{} closed, rent sent to {} ({})", key, recipient, signature);
+        }
+        AdminCommand::SetFees {
+            verify_fee,
+            read_fee,
+            mint,
+            subscription_price,
+            subscription_period,
+            exempt,
+            disable,
+        } => {
+            let schedule = FeeSchedule {
+                mint: mint.unwrap_or_default(),
+                verify_fee,
+                read_fee,
+                subscription_price,
+                subscription_period,
+            };
+            let signature = client.set_fee_schedule(schedule, exempt, !disable)?;
+            let state = if disable { "disabled" } else { "enabled" };
+            println!("fees set and {} ({})", state, signature);
+        }
+        AdminCommand::WithdrawFees {
+            amount,
+            recipient,
+            vault,
+        } => {
+            let signature = client.withdraw_fees(amount, &recipient, vault.as_ref())?;
+            println!("{} fees sent to {} ({})", amount, recipient, signature);
+        }
+        AdminCommand::SetRewards { disable } => {
+            let signature = client.set_rewards_enabled(!disable)?;
+            let state = if disable { "disabled" } else { "enabled" };
+            println!("rewards {} ({})", state, signature);
+        }
+        AdminCommand::DistributeRewards { amount } => {
+            let signature = client.distribute_rewards(amount)?;
+            println!("{} lamports distributed ({})", amount, signature);
+        }
+        AdminCommand::SetStakeConfig {
+            vault,
+            treasury,
+            slash_bps,
+            unbonding_period,
+        } => {
+            let signature =
+                client.set_stake_config(&vault, &treasury, slash_bps, unbonding_period)?;
+            println!(
+                "stakes held in {}, {} bps slashed ({})",
+                vault, slash_bps, signature
+            );
+        }
+        AdminCommand::Migrate => {
+            let signature = client.migrate()?;
+            println!("accounts migrated ({})", signature);
+        }
+        AdminCommand::TransferOwnership { new_owner } => {
+            let new_owner = read_keypair_file(&new_owner).map_err(|err| anyhow!("{}", err))?;
+            let signature = client.transfer_ownership(&new_owner)?;
+            println!(
+                "ownership transferred to {} ({})",
+                new_owner.pubkey(),
+                signature
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Names of the instructions stopped by `PAUSE_*` flags.
+pub fn describe_paused(paused: u8) -> String {
+    let names: Vec<&str> = [
+        (PAUSE_SUBMIT, "submit"),
+        (PAUSE_FCD_UPDATE, "fcd"),
+        (PAUSE_VERIFY, "verify"),
+    ]
+    .iter()
+    .filter(|(flag, _)| paused & flag != 0)
+    .map(|(_, name)| *name)
+    .collect();
+    if names.is_empty() {
+        "nothing".to_string()
+    } else {
+        names.join(", ")
+    }
+}
@@ -0,0 +1,148 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use aquarius_sdk::chain::state::Block;
+use aquarius_sdk::sorted_merkle_tree::{
+    encode_key, extract_timestamp, hash_message, HashScheme, Proof,
+};
+use aquarius_sdk::{
+    block_address, decode_hex, decode_hex_bytes, encode_hex, encode_value, ChainClient,
+};
+
+#[derive(clap::Subcommand)]
+pub enum BlockCommand {
+    /// Shows a submitted block
+    Show { block_id: u32 },
+    /// Checks that a key/value pair is part of a submitted block
+    Verify {
+        block_id: u32,
+        /// Feed key, or its 32 byte hex encoding
+        #[clap(long)]
+        key: String,
+        /// Decimal value, or its 32 byte hex encoding
+        #[clap(long)]
+        value: String,
+        /// JSON array or whitespace separated list of hex proof elements
+        #[clap(long)]
+        proof_file: PathBuf,
+        /// Run `verify_proof_for_block` on chain instead of verifying locally
+        #[clap(long)]
+        on_chain: bool,
+    },
+    /// Checks that an arbitrary message is part of a submitted block
+    VerifyMessage {
+        block_id: u32,
+        /// Hex encoded message bytes
+        message: String,
+        /// JSON array or whitespace separated list of hex proof elements
+        #[clap(long)]
+        proof_file: PathBuf,
+        /// Run `verify_message_inclusion` on chain instead of verifying locally
+        #[clap(long)]
+        on_chain: bool,
+    },
+}
+
+pub fn run(client: &ChainClient, command: BlockCommand) -> Result<()> {
+    match command {
+        BlockCommand::Show { block_id } => {
+            let (address, _) = block_address(&client.program_id(), block_id);
+            let block = client.block(block_id)?;
+
+            println!("block id:  {}", block.block_id);
+            println!("address:   {}", address);
+            println!("root:      {}", encode_hex(&block.root));
+            println!("timestamp: {}", block.timestamp);
+            match HashScheme::from_u8(block.hash_scheme) {
+                Some(scheme) => println!("scheme:    {:?}", scheme),
+                None => println!("scheme:    unknown ({})", block.hash_scheme),
+            }
+        }
+        BlockCommand::Verify {
+            block_id,
+            key,
+            value,
+            proof_file,
+            on_chain,
+        } => {
+            let key_bytes = parse_key(&key)?;
+            let value = encode_value(&value, &key)?;
+            let proof = read_proof_file(&proof_file)?;
+
+            let valid = if on_chain {
+                client.verify_proof_for_block(block_id, proof, key_bytes, value)?
+            } else {
+                let (block, scheme) = block_with_scheme(client, block_id)?;
+                Proof {
+                    key: key_bytes,
+                    value,
+                    proof,
+                    scheme,
+                }
+                .verify(&block.root)
+            };
+
+            if !valid {
+                bail!("proof does not match the root of block {}", block_id);
+            }
+            println!("proof is valid for block {}", block_id);
+        }
+        BlockCommand::VerifyMessage {
+            block_id,
+            message,
+            proof_file,
+            on_chain,
+        } => {
+            let message = decode_hex_bytes(&message)?;
+            let proof = read_proof_file(&proof_file)?;
+
+            let valid = if on_chain {
+                client.verify_message_inclusion(block_id, message, proof)?
+            } else {
+                let (block, scheme) = block_with_scheme(client, block_id)?;
+                scheme.verify_squashed_root(&block.root, &proof, hash_message(&message))
+            };
+
+            if !valid {
+                bail!("message is not part of block {}", block_id);
+            }
+            println!("message is part of block {}", block_id);
+        }
+    }
+    Ok(())
+}
+
+fn block_with_scheme(client: &ChainClient, block_id: u32) -> Result<(Block, HashScheme)> {
+    let block = client.block(block_id)?;
+    let scheme = match HashScheme::from_u8(block.hash_scheme) {
+        Some(scheme) => scheme,
+        None => bail!("unknown hash scheme {}", block.hash_scheme),
+    };
+    println!("root timestamp: {}", extract_timestamp(&block.root));
+    Ok((block, scheme))
+}
+
+fn parse_key(key: &str) -> Result<[u8; 32]> {
+    if key.starts_with("0x") {
+        Ok(decode_hex(key)?)
+    } else {
+        Ok(encode_key(key)?)
+    }
+}
+
+fn read_proof_file(path: &Path) -> Result<Vec<[u8; 32]>> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+
+    let elements: Vec<String> = if content.trim_start().starts_with('[') {
+        serde_json::from_str(&content).with_context(|| format!("parsing {}", path.display()))?
+    } else {
+        content.split_whitespace().map(str::to_string).collect()
+    };
+
+    elements
+        .iter()
+        .map(|element| Ok(decode_hex(element)?))
+        .collect()
+}
@@ -0,0 +1,175 @@
+use anyhow::{anyhow, Result};
+use aquarius_sdk::chain::cross_rate::{CrossRateOperation, CROSS_RATE_DECIMALS};
+use aquarius_sdk::chain::state::ValueKind;
+use aquarius_sdk::{
+    decode_value, encode_hex, encode_value, fcd_address, format_decimal, ChainClient, U256,
+};
+
+#[derive(clap::Subcommand)]
+pub enum FcdCommand {
+    /// Shows the current value of a feed
+    Show {
+        key: String,
+        /// Fails unless the value is known within this many basis points
+        #[clap(long)]
+        max_confidence_bps: Option<u32>,
+    },
+    /// Lists all feeds stored by the program
+    List,
+    /// Lists the feeds of the feed registry
+    Registry,
+    /// Lists the recorded values of a feed, oldest first
+    History { key: String },
+    /// Submits an observation of an aggregated feed, signed as a validator
+    Submit {
+        key: String,
+        value: String,
+        /// Data timestamp of the value
+        #[clap(long)]
+        timestamp: u32,
+    },
+    /// Shows the time weighted average of a feed with a history
+    Twap {
+        key: String,
+        /// Length of the window in seconds, up to now
+        #[clap(long, default_value = "3600")]
+        window: u32,
+    },
+    /// Shows the rate derived from two feeds, e.g. SOL-ETH from SOL-USD and ETH-USD
+    CrossRate {
+        base: String,
+        /// Required unless inverting the base feed
+        quote: Option<String>,
+        #[clap(long, arg_enum, default_value = "divide")]
+        operation: Operation,
+    },
+}
+
+#[derive(Clone, clap::ArgEnum)]
+pub enum Operation {
+    /// base / quote
+    Divide,
+    /// base * quote
+    Multiply,
+    /// 1 / base
+    Invert,
+}
+
+impl From<Operation> for CrossRateOperation {
+    fn from(operation: Operation) -> Self {
+        match operation {
+            Operation::Divide => CrossRateOperation::Divide,
+            Operation::Multiply => CrossRateOperation::Multiply,
+            Operation::Invert => CrossRateOperation::Invert,
+        }
+    }
+}
+
+pub fn run(client: &ChainClient, command: FcdCommand) -> Result<()> {
+    match command {
+        FcdCommand::Show {
+            key,
+            max_confidence_bps,
+        } => {
+            let (address, _) = fcd_address(&client.program_id(), &key)?;
+            let feed = match max_confidence_bps {
+                Some(max_ratio_bps) => client.confident_feed(&key, max_ratio_bps)?,
+                None => client.feed(&key)?,
+            };
+
+            println!("key:         {}", feed.key);
+            println!("address:     {}", address);
+            println!("value:       {}", feed);
+            println!("raw value:   {}", encode_hex(&feed.value));
+            if let Some(confidence) = feed.confidence_number() {
+                println!(
+                    "confidence:  ±{}",
+                    format_decimal(confidence, feed.decimals.into())
+                );
+            }
+            println!("timestamp:   {}", feed.timestamp);
+            println!("kind:        {:?}", feed.kind);
+            println!("decimals:    {}", feed.decimals);
+            println!("description: {}", feed.description);
+        }
+        FcdCommand::List => {
+            let mut fcds = client.fcds()?;
+            fcds.sort_by(|(_, a), (_, b)| a.key.cmp(&b.key));
+
+            for (address, fcd) in fcds {
+                println!(
+                    "{:<24} {:>32} {:>12} {}",
+                    fcd.key,
+                    decode_value(&fcd.value, &fcd.key),
+                    fcd.timestamp,
+                    address
+                );
+            }
+        }
+        FcdCommand::Registry => {
+            for feed in client.feeds()? {
+                let kind = ValueKind::from_u8(feed.metadata.kind).map_or_else(
+                    || feed.metadata.kind.to_string(),
+                    |kind| format!("{:?}", kind),
+                );
+                println!(
+                    "{:<24} {:<6} {:>2} decimals {} {}",
+                    feed.key, kind, feed.metadata.decimals, feed.fcd, feed.metadata.description
+                );
+            }
+        }
+        FcdCommand::History { key } => {
+            let history = client
+                .fcd_history(&key)?
+                .ok_or_else(|| anyhow!("{} has no history", key))?;
+            for observation in history.ordered() {
+                println!(
+                    "{:>12} {}",
+                    observation.timestamp,
+                    decode_value(&observation.value, &key)
+                );
+            }
+        }
+        FcdCommand::Submit {
+            key,
+            value,
+            timestamp,
+        } => {
+            let signature =
+                client.submit_fcd_observation(&key, encode_value(&value, &key)?, timestamp)?;
+            println!("{} observation submitted ({})", key, signature);
+        }
+        FcdCommand::Twap { key, window } => {
+            let feed = client.feed(&key)?;
+            let twap = client.twap(&key, window)?;
+            println!(
+                "{} over {}s: {}",
+                key,
+                window,
+                format_decimal(twap, feed.decimals.into())
+            );
+        }
+        FcdCommand::CrossRate {
+            base,
+            quote,
+            operation,
+        } => {
+            let operation = CrossRateOperation::from(operation);
+            let quote = match (quote, operation) {
+                (Some(quote), _) => quote,
+                (None, CrossRateOperation::Invert) => base.clone(),
+                (None, _) => return Err(anyhow!("a quote feed is required")),
+            };
+            let rate = client.cross_rate(&base, &quote, operation)?;
+            println!(
+                "{} (timestamp {})",
+                format_decimal(
+                    U256::from_big_endian(&rate.value),
+                    CROSS_RATE_DECIMALS.into()
+                ),
+                rate.timestamp
+            );
+        }
+    }
+    Ok(())
+}
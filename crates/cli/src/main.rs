@@ -0,0 +1,148 @@
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use anyhow::{Context, Result};
+use aquarius_sdk::anchor_client::anchor_lang::prelude::Pubkey;
+use aquarius_sdk::anchor_client::solana_sdk::signature::{read_keypair_file, Keypair};
+use aquarius_sdk::anchor_client::Cluster;
+use aquarius_sdk::chain::state::EvmBlockHeader;
+use aquarius_sdk::{decode_hex_array, decode_hex_bytes, ChainClient};
+use clap::Parser;
+
+mod admin;
+mod block;
+mod fcd;
+mod stake;
+mod status;
+
+#[derive(Parser)]
+#[clap(
+    name = "aquarius",
+    version,
+    about = "Inspect and administer the Umbrella chain program"
+)]
+struct Opts {
+    /// Cluster name (localnet, devnet, mainnet) or RPC url
+    #[clap(long, short = 'u', global = true, default_value = "localnet")]
+    url: Cluster,
+
+    #[clap(long, global = true, default_value_t = aquarius_sdk::chain::ID)]
+    program_id: Pubkey,
+
+    /// Keypair paying for transactions, defaults to ~/.config/solana/id.json
+    #[clap(long, short = 'k', global = true)]
+    keypair: Option<PathBuf>,
+
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(clap::Subcommand)]
+enum Command {
+    /// Submitted blocks
+    #[clap(subcommand)]
+    Block(block::BlockCommand),
+    /// First class data feeds
+    #[clap(subcommand)]
+    Fcd(fcd::FcdCommand),
+    /// Program status and owner
+    Status,
+    /// Owner only instructions
+    #[clap(subcommand)]
+    Admin(admin::AdminCommand),
+    /// Prepays calls of a consumer free of per call fees
+    Subscribe {
+        /// Key paying fees, usually a program's signing address
+        consumer: Pubkey,
+        /// Number of subscription periods paid for
+        #[clap(long, default_value_t = 1)]
+        periods: u32,
+    },
+    /// Claims the rewards distributed to the keypair
+    ClaimRewards,
+    /// Imports a sidechain block signed by a quorum of the EVM validators
+    ImportEvmBlock {
+        /// ABI-encoded `blockId, root, timestamp` as hex
+        payload: String,
+        /// 65 byte hex `eth_sign` signature of a validator, repeated for each
+        #[clap(long = "signature", parse(try_from_str = decode_hex_array::<65>))]
+        signatures: Vec<[u8; 65]>,
+    },
+    /// Validator stakes and reports of equivocating validators
+    #[clap(subcommand)]
+    Stake(stake::StakeCommand),
+}
+
+fn main() -> Result<()> {
+    let opts = Opts::parse();
+    let keypair_path = opts.keypair.clone().unwrap_or_else(default_keypair_path);
+
+    match opts.command {
+        command @ (Command::Admin(_)
+        | Command::Subscribe { .. }
+        | Command::ClaimRewards
+        | Command::ImportEvmBlock { .. }
+        | Command::Stake(_)) => {
+            let payer = read_keypair_file(&keypair_path)
+                .map_err(|err| anyhow::anyhow!("{}", err))
+                .with_context(|| format!("reading keypair {}", keypair_path.display()))?;
+            let payer = Rc::new(payer);
+            let client = ChainClient::new(opts.url, payer.clone(), opts.program_id);
+            match command {
+                Command::Admin(command) => admin::run(&client, command),
+                Command::Stake(command) => stake::run(&client, &payer, command),
+                Command::Subscribe { consumer, periods } => {
+                    let signature = client.subscribe(&consumer, periods)?;
+                    let paid_until = client
+                        .subscription(&consumer)?
+                        .map(|subscription| subscription.paid_until)
+                        .unwrap_or_default();
+                    println!(
+                        "{} subscribed until {} ({})",
+                        consumer, paid_until, signature
+                    );
+                    Ok(())
+                }
+                Command::ClaimRewards => {
+                    let payer = client.program().payer();
+                    let owed = client
+                        .reward_vault()?
+                        .map(|reward_vault| reward_vault.owed(&payer))
+                        .unwrap_or_default();
+                    let signature = client.claim_rewards()?;
+                    println!("{} lamports claimed by {} ({})", owed, payer, signature);
+                    Ok(())
+                }
+                Command::ImportEvmBlock {
+                    payload,
+                    signatures,
+                } => {
+                    let payload = decode_hex_bytes(&payload)?;
+                    let block_id = EvmBlockHeader::decode(&payload)
+                        .map(|header| header.block_id)
+                        .unwrap_or_default();
+                    let signature = client.import_evm_block(&payload, &signatures)?;
+                    println!("block {} imported ({})", block_id, signature);
+                    Ok(())
+                }
+                _ => unreachable!(),
+            }
+        }
+        command => {
+            // reads never sign, so any payer will do when no keypair is set up
+            let payer = read_keypair_file(&keypair_path).unwrap_or_else(|_| Keypair::new());
+            let client = ChainClient::new(opts.url, Rc::new(payer), opts.program_id);
+            match command {
+                Command::Block(command) => block::run(&client, command),
+                Command::Fcd(command) => fcd::run(&client, command),
+                Command::Status => status::run(&client),
+                _ => unreachable!(),
+            }
+        }
+    }
+}
+
+fn default_keypair_path() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    PathBuf::from(home).join(".config/solana/id.json")
+}
@@ -0,0 +1,100 @@
+use anyhow::Result;
+use aquarius_sdk::anchor_client::anchor_lang::prelude::Pubkey;
+use aquarius_sdk::anchor_client::solana_sdk::signature::{Keypair, Signature};
+use aquarius_sdk::{decode_hex, encode_hex, sign_attestation, Attestation, ChainClient};
+
+#[derive(clap::Subcommand)]
+pub enum StakeCommand {
+    /// Stakes tokens of the keypair, a registered validator
+    Deposit {
+        amount: u64,
+        /// Token account the stake is taken from
+        #[clap(long)]
+        source: Pubkey,
+    },
+    /// Starts unbonding staked tokens, slashable until the unbonding period passed
+    Unstake { amount: u64 },
+    /// Sends unbonded tokens to a token account of the keypair
+    Withdraw {
+        #[clap(long)]
+        destination: Pubkey,
+    },
+    /// Signs a block root with the keypair, as evidence of what it attested
+    Attest {
+        block_id: u32,
+        /// 32 byte hex root
+        root: String,
+    },
+    /// Slashes a validator that attested two different roots of a block
+    ReportEquivocation {
+        validator: Pubkey,
+        block_id: u32,
+        /// First attested root and its signature, as `<root>:<signature>`
+        #[clap(long, parse(try_from_str = parse_attestation))]
+        first: Attestation,
+        /// Second attested root and its signature, as `<root>:<signature>`
+        #[clap(long, parse(try_from_str = parse_attestation))]
+        second: Attestation,
+    },
+}
+
+pub fn run(client: &ChainClient, payer: &Keypair, command: StakeCommand) -> Result<()> {
+    let validator = client.program().payer();
+    match command {
+        StakeCommand::Deposit { amount, source } => {
+            let signature = client.stake(&source, amount)?;
+            let staked = client
+                .validator_stake(&validator)?
+                .map(|stake| stake.amount)
+                .unwrap_or_default();
+            println!("{} staked by {} ({})", staked, validator, signature);
+        }
+        StakeCommand::Unstake { amount } => {
+            let signature = client.request_unstake(amount)?;
+            let unbonding_until = client
+                .validator_stake(&validator)?
+                .map(|stake| stake.unbonding_until)
+                .unwrap_or_default();
+            println!(
+                "{} unbonding until {} ({})",
+                amount, unbonding_until, signature
+            );
+        }
+        StakeCommand::Withdraw { destination } => {
+            let unbonding = client
+                .validator_stake(&validator)?
+                .map(|stake| stake.unbonding)
+                .unwrap_or_default();
+            let signature = client.withdraw_stake(&destination)?;
+            println!("{} sent to {} ({})", unbonding, destination, signature);
+        }
+        StakeCommand::Attest { block_id, root } => {
+            let root = decode_hex(&root)?;
+            let signature = sign_attestation(payer, block_id, &root);
+            println!("{}:{}", encode_hex(&root), signature);
+        }
+        StakeCommand::ReportEquivocation {
+            validator,
+            block_id,
+            first,
+            second,
+        } => {
+            let signature = client.report_equivocation(&validator, block_id, &first, &second)?;
+            println!(
+                "{} slashed for block {} ({})",
+                validator, block_id, signature
+            );
+        }
+    }
+    Ok(())
+}
+
+fn parse_attestation(value: &str) -> Result<Attestation> {
+    let (root, signature) = value
+        .split_once(':')
+        .ok_or_else(|| anyhow::anyhow!("expected <root>:<signature>"))?;
+    Ok(Attestation {
+        root: decode_hex(root)?,
+        signature: signature.parse::<Signature>()?,
+    })
+}
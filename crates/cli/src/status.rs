@@ -0,0 +1,81 @@
+use anyhow::Result;
+use aquarius_sdk::ChainClient;
+
+use crate::admin::describe_paused;
+
+pub fn run(client: &ChainClient) -> Result<()> {
+    let authority = client.authority()?;
+    let status = client.status()?;
+
+    println!("program id:          {}", client.program_id());
+    println!("owner:               {}", authority.owner);
+    println!("guardian:            {}", authority.guardian);
+    println!("padding:             {}", status.padding);
+    println!("last id:             {}", status.last_id);
+    println!("last data timestamp: {}", status.last_data_timestamp);
+    println!("next block id:       {}", status.next_block_id);
+    println!("max proof length:    {}", status.max_proof_length());
+    println!("paused:              {}", describe_paused(status.paused));
+    println!("version:             {}", status.version);
+    if let Some(validator_set) = client.validator_set()? {
+        println!(
+            "validators:          {} (quorum {}, window {}s)",
+            validator_set.validators.len(),
+            validator_set.quorum,
+            validator_set.window
+        );
+    }
+    if let Some(evm_validator_set) = client.evm_validator_set()? {
+        println!(
+            "evm validators:      {} (quorum {})",
+            evm_validator_set.validators.len(),
+            evm_validator_set.quorum
+        );
+    }
+    if let Some(fee_config) = client.fee_config()? {
+        let schedule = &fee_config.schedule;
+        let unit = if schedule.in_tokens() {
+            schedule.mint.to_string()
+        } else {
+            "lamports".to_string()
+        };
+        println!(
+            "fees:                {} (verify {}, read {} {})",
+            if status.fees_enabled {
+                "enabled"
+            } else {
+                "disabled"
+            },
+            schedule.verify_fee,
+            schedule.read_fee,
+            unit
+        );
+        if schedule.subscription_price > 0 {
+            println!(
+                "subscription:        {} {} per {}s",
+                schedule.subscription_price, unit, schedule.subscription_period
+            );
+        }
+        println!("fee exempt:          {}", fee_config.exempt.len());
+    }
+    if let Some(reward_vault) = client.reward_vault()? {
+        println!(
+            "rewards:             {} ({} keys, {} points, {} lamports undistributed)",
+            if status.rewards_enabled {
+                "enabled"
+            } else {
+                "disabled"
+            },
+            reward_vault.entries.len(),
+            reward_vault.total_points,
+            reward_vault.undistributed
+        );
+    }
+    if let Some(stake_config) = client.stake_config()? {
+        println!(
+            "staking:             {} ({} bps slashed, {}s unbonding)",
+            stake_config.mint, stake_config.slash_bps, stake_config.unbonding_period
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use aquarius_sdk::anchor_client::anchor_lang::prelude::Pubkey;
+use aquarius_sdk::anchor_client::anchor_lang::{AnchorDeserialize, Discriminator};
+use aquarius_sdk::chain::events::*;
+
+const PROGRAM_DATA: &str = "Program data: ";
+
+/// Events of the chain program the indexer understands.
+#[derive(Debug)]
+pub enum ChainEvent {
+    Initialization(LogInitialization),
+    BlockSubmitted(LogBlockSubmitted),
+    FcdInitialized(LogFCDInitialized),
+    FcdUpdated(LogFCDUpdated),
+    PaddingSet(LogPaddingSet),
+    OwnershipChanged(LogOwnershipChanged),
+}
+
+impl ChainEvent {
+    /// Decodes the payload of a `Program data:` log line.
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        if data.len() < 8 {
+            return None;
+        }
+        let (discriminator, mut payload) = data.split_at(8);
+
+        fn decode<T: AnchorDeserialize>(payload: &mut &[u8]) -> Option<T> {
+            T::deserialize(payload).ok()
+        }
+
+        let event = match discriminator {
+            d if d == LogInitialization::discriminator() => {
+                Self::Initialization(decode(&mut payload)?)
+            }
+            d if d == LogBlockSubmitted::discriminator() => {
+                Self::BlockSubmitted(decode(&mut payload)?)
+            }
+            d if d == LogFCDInitialized::discriminator() => {
+                Self::FcdInitialized(decode(&mut payload)?)
+            }
+            d if d == LogFCDUpdated::discriminator() => Self::FcdUpdated(decode(&mut payload)?),
+            d if d == LogPaddingSet::discriminator() => Self::PaddingSet(decode(&mut payload)?),
+            d if d == LogOwnershipChanged::discriminator() => {
+                Self::OwnershipChanged(decode(&mut payload)?)
+            }
+            _ => return None,
+        };
+        Some(event)
+    }
+}
+
+/// Extracts the events emitted by `program_id` from the logs of a single
+/// transaction, including events emitted during a CPI into the program.
+pub fn parse_logs(program_id: &Pubkey, logs: &[String]) -> Vec<ChainEvent> {
+    let program_id = program_id.to_string();
+    let invoke = format!("Program {} invoke", program_id);
+    let mut stack: Vec<bool> = vec![];
+    let mut events = vec![];
+
+    for log in logs {
+        if log.starts_with("Program ") && log.contains(" invoke [") {
+            stack.push(log.starts_with(&invoke));
+        } else if log.starts_with("Program ")
+            && (log.ends_with(" success") || log.contains(" failed"))
+            && !log.starts_with("Program log:")
+        {
+            stack.pop();
+        } else if let Some(data) = log.strip_prefix(PROGRAM_DATA) {
+            if stack.last() != Some(&true) {
+                continue;
+            }
+            if let Some(event) = base64::decode(data)
+                .ok()
+                .and_then(|data| ChainEvent::decode(&data))
+            {
+                events.push(event);
+            }
+        }
+    }
+
+    events
+}
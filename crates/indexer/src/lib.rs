@@ -0,0 +1,14 @@
+//! Rebuilds the history of blocks and first class data from the events the
+//! `chain` program writes to transaction logs, and stores it in SQLite.
+//!
+//! Logs are read from an RPC node or from a JSON lines file, see `source`.
+
+pub use events::*;
+pub use server::*;
+pub use source::*;
+pub use store::*;
+
+pub mod events;
+pub mod server;
+pub mod source;
+pub mod store;
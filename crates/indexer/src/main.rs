@@ -0,0 +1,110 @@
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use aquarius_indexer::*;
+use aquarius_sdk::anchor_client::anchor_lang::prelude::Pubkey;
+use aquarius_sdk::anchor_client::Cluster;
+use clap::Parser;
+
+#[derive(Parser)]
+#[clap(
+    name = "indexer",
+    version,
+    about = "Indexes chain program events for historical queries"
+)]
+struct Opts {
+    /// SQLite database file
+    #[clap(long, global = true, default_value = "aquarius.sqlite")]
+    db: PathBuf,
+
+    #[clap(long, global = true, default_value_t = aquarius_sdk::chain::ID)]
+    program_id: Pubkey,
+
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(clap::Subcommand)]
+enum Command {
+    /// Indexes new transactions from an RPC node
+    Sync {
+        /// Cluster name (localnet, devnet, mainnet) or RPC url
+        #[clap(long, short = 'u', default_value = "localnet")]
+        url: Cluster,
+        /// Keep polling every given number of seconds
+        #[clap(long)]
+        follow: Option<u64>,
+    },
+    /// Indexes a JSON lines file of transaction logs
+    Import { file: PathBuf },
+    /// Root of an indexed block
+    Block { block_id: u32 },
+    /// Value of a feed at a data timestamp, the latest value without one
+    Fcd {
+        key: String,
+        #[clap(long)]
+        at: Option<u32>,
+    },
+    /// Values of a feed between two data timestamps
+    History {
+        key: String,
+        #[clap(long, default_value_t = 0)]
+        from: u32,
+        #[clap(long, default_value_t = u32::MAX)]
+        to: u32,
+    },
+    /// Serves the queries above over HTTP
+    Serve {
+        #[clap(long, default_value = "127.0.0.1:8080")]
+        address: SocketAddr,
+    },
+}
+
+fn main() -> Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    let opts = Opts::parse();
+    let store = Store::open(&opts.db).with_context(|| format!("opening {}", opts.db.display()))?;
+
+    match opts.command {
+        Command::Sync { url, follow } => {
+            let source = RpcSource::new(url.url(), opts.program_id);
+            loop {
+                let count = source.sync(&store)?;
+                log::info!("indexed {} events", count);
+                match follow {
+                    Some(interval) => thread::sleep(Duration::from_secs(interval)),
+                    None => break,
+                }
+            }
+        }
+        Command::Import { file } => {
+            let count = import_file(&store, &opts.program_id, &file)?;
+            log::info!("indexed {} events", count);
+        }
+        Command::Block { block_id } => print(query(&store, &format!("/blocks/{}", block_id))?),
+        Command::Fcd { key, at } => {
+            let at = at.map(|at| format!("?at={}", at)).unwrap_or_default();
+            print(query(&store, &format!("/fcds/{}{}", key, at))?)
+        }
+        Command::History { key, from, to } => print(query(
+            &store,
+            &format!("/fcds/{}/history?from={}&to={}", key, from, to),
+        )?),
+        Command::Serve { address } => {
+            log::info!("serving queries on {}", address);
+            serve(&store, address)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn print(value: Option<serde_json::Value>) {
+    match value {
+        Some(value) => println!("{:#}", value),
+        None => println!("not found"),
+    }
+}
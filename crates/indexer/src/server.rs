@@ -0,0 +1,87 @@
+use std::net::SocketAddr;
+
+use anyhow::{anyhow, Result};
+use aquarius_sdk::{decode_value, encode_hex};
+use serde_json::{json, Value};
+
+use crate::store::{BlockRecord, FcdRecord, Store};
+
+/// Answers a query path against the store, `None` meaning not found.
+///
+/// - `/blocks/{block_id}`: root of a block
+/// - `/fcds`: indexed keys
+/// - `/fcds/{key}?at={timestamp}`: value in effect at a time, latest without `at`
+/// - `/fcds/{key}/history?from={timestamp}&to={timestamp}`
+pub fn query(store: &Store, path: &str) -> Result<Option<Value>> {
+    let (path, query) = path.split_once('?').unwrap_or((path, ""));
+    let param = |name: &str| -> Result<Option<u32>> {
+        query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.parse().map_err(|_| anyhow!("invalid {}", name)))
+            .transpose()
+    };
+
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let value = match segments.as_slice() {
+        ["blocks", block_id] => store.block(block_id.parse()?)?.map(block_json),
+        ["fcds"] => Some(json!(store.keys()?)),
+        ["fcds", key] => store
+            .fcd_at(key, param("at")?.unwrap_or(u32::MAX))?
+            .map(fcd_json),
+        ["fcds", key, "history"] => Some(Value::Array(
+            store
+                .fcd_history(
+                    key,
+                    param("from")?.unwrap_or(0),
+                    param("to")?.unwrap_or(u32::MAX),
+                )?
+                .into_iter()
+                .map(fcd_json)
+                .collect(),
+        )),
+        _ => None,
+    };
+    Ok(value)
+}
+
+/// Serves `query` over HTTP until the process exits.
+pub fn serve(store: &Store, address: SocketAddr) -> Result<()> {
+    let server = tiny_http::Server::http(address).map_err(|err| anyhow!("{}", err))?;
+
+    for request in server.incoming_requests() {
+        let response = match query(store, request.url()) {
+            Ok(Some(value)) => tiny_http::Response::from_string(value.to_string()),
+            Ok(None) => tiny_http::Response::from_string("not found").with_status_code(404),
+            Err(err) => tiny_http::Response::from_string(err.to_string()).with_status_code(400),
+        };
+        let header = tiny_http::Header::from_bytes("Content-Type", "application/json").unwrap();
+        if let Err(err) = request.respond(response.with_header(header)) {
+            log::warn!("failed to respond: {}", err);
+        }
+    }
+
+    Ok(())
+}
+
+fn block_json(block: BlockRecord) -> Value {
+    json!({
+        "blockId": block.block_id,
+        "root": encode_hex(&block.root),
+        "timestamp": block.timestamp,
+        "signature": block.signature,
+        "slot": block.slot,
+    })
+}
+
+fn fcd_json(fcd: FcdRecord) -> Value {
+    json!({
+        "key": fcd.key,
+        "value": decode_value(&fcd.value, &fcd.key),
+        "rawValue": encode_hex(&fcd.value),
+        "timestamp": fcd.timestamp,
+        "signature": fcd.signature,
+        "slot": fcd.slot,
+    })
+}
@@ -0,0 +1,144 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use aquarius_sdk::anchor_client::anchor_lang::prelude::Pubkey;
+use aquarius_sdk::anchor_client::solana_client::rpc_client::{
+    GetConfirmedSignaturesForAddress2Config, RpcClient,
+};
+use aquarius_sdk::anchor_client::solana_client::rpc_config::RpcTransactionConfig;
+use aquarius_sdk::anchor_client::solana_sdk::commitment_config::CommitmentConfig;
+use aquarius_sdk::anchor_client::solana_sdk::signature::Signature;
+use serde::{Deserialize, Serialize};
+use solana_transaction_status::UiTransactionEncoding;
+
+use crate::events::parse_logs;
+use crate::store::Store;
+
+/// Logs of one transaction, in the shape of a `logsSubscribe` notification
+/// plus the slot.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TransactionLogs {
+    pub signature: String,
+    #[serde(default)]
+    pub slot: u64,
+    #[serde(default)]
+    pub err: Option<serde_json::Value>,
+    pub logs: Vec<String>,
+}
+
+/// Stores the events of a successful transaction and returns how many there were.
+pub fn index_transaction(
+    store: &Store,
+    program_id: &Pubkey,
+    tx: &TransactionLogs,
+) -> Result<usize> {
+    if tx.err.as_ref().is_some_and(|err| !err.is_null()) {
+        return Ok(0);
+    }
+
+    let events = parse_logs(program_id, &tx.logs);
+    for event in &events {
+        store.insert(event, &tx.signature, tx.slot)?;
+    }
+    Ok(events.len())
+}
+
+/// Indexes a file with one `TransactionLogs` JSON object per line.
+pub fn import_file(store: &Store, program_id: &Pubkey, path: &Path) -> Result<usize> {
+    let file = File::open(path).with_context(|| format!("opening {}", path.display()))?;
+    let mut count = 0;
+
+    for (number, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let tx: TransactionLogs = serde_json::from_str(&line)
+            .with_context(|| format!("{}:{}", path.display(), number + 1))?;
+        count += index_transaction(store, program_id, &tx)?;
+    }
+
+    Ok(count)
+}
+
+/// Reads the program's transaction history from an RPC node.
+pub struct RpcSource {
+    rpc: RpcClient,
+    program_id: Pubkey,
+}
+
+/// Signatures requested per `getSignaturesForAddress` call.
+const SIGNATURES_PAGE: usize = 1000;
+
+impl RpcSource {
+    pub fn new(url: &str, program_id: Pubkey) -> Self {
+        Self {
+            rpc: RpcClient::new_with_commitment(url.to_string(), CommitmentConfig::finalized()),
+            program_id,
+        }
+    }
+
+    /// Indexes every transaction since the stored cursor, oldest first, and
+    /// returns the number of events stored.
+    pub fn sync(&self, store: &Store) -> Result<usize> {
+        let until = store
+            .cursor()?
+            .map(|signature| Signature::from_str(&signature))
+            .transpose()?;
+
+        let mut signatures = vec![];
+        let mut before = None;
+        loop {
+            let page = self.rpc.get_signatures_for_address_with_config(
+                &self.program_id,
+                GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    until,
+                    limit: Some(SIGNATURES_PAGE),
+                    commitment: Some(CommitmentConfig::finalized()),
+                },
+            )?;
+            match page.last() {
+                Some(last) => before = Some(Signature::from_str(&last.signature)?),
+                None => break,
+            }
+            signatures.extend(page);
+        }
+
+        let mut count = 0;
+        for status in signatures.iter().rev() {
+            if status.err.is_none() {
+                let signature = Signature::from_str(&status.signature)?;
+                let tx = self.rpc.get_transaction_with_config(
+                    &signature,
+                    RpcTransactionConfig {
+                        encoding: Some(UiTransactionEncoding::Json),
+                        commitment: Some(CommitmentConfig::finalized()),
+                    },
+                )?;
+                let logs = tx
+                    .transaction
+                    .meta
+                    .and_then(|meta| meta.log_messages)
+                    .unwrap_or_default();
+
+                count += index_transaction(
+                    store,
+                    &self.program_id,
+                    &TransactionLogs {
+                        signature: status.signature.clone(),
+                        slot: tx.slot,
+                        err: None,
+                        logs,
+                    },
+                )?;
+            }
+            store.set_cursor(&status.signature)?;
+        }
+
+        Ok(count)
+    }
+}
@@ -0,0 +1,213 @@
+use std::path::Path;
+
+use anyhow::Result;
+use aquarius_sdk::{decode_hex, encode_hex};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::events::ChainEvent;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockRecord {
+    pub block_id: u32,
+    pub root: [u8; 32],
+    pub timestamp: u32,
+    pub signature: String,
+    pub slot: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FcdRecord {
+    pub key: String,
+    pub value: [u8; 32],
+    pub timestamp: u32,
+    pub signature: String,
+    pub slot: u64,
+}
+
+/// SQLite database with the indexed history.
+pub struct Store {
+    connection: Connection,
+}
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS blocks (
+    block_id INTEGER PRIMARY KEY,
+    root TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    signature TEXT NOT NULL,
+    slot INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS fcd_values (
+    key TEXT NOT NULL,
+    value TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    signature TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    PRIMARY KEY (key, timestamp)
+);
+CREATE TABLE IF NOT EXISTS cursor (
+    id INTEGER PRIMARY KEY CHECK (id = 0),
+    signature TEXT NOT NULL
+);
+";
+
+impl Store {
+    pub fn open(path: &Path) -> Result<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    pub fn in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(connection: Connection) -> Result<Self> {
+        connection.execute_batch(SCHEMA)?;
+        Ok(Self { connection })
+    }
+
+    /// Stores an event; replaying the same event is a no-op.
+    pub fn insert(&self, event: &ChainEvent, signature: &str, slot: u64) -> Result<()> {
+        match event {
+            ChainEvent::BlockSubmitted(block) => {
+                self.connection.execute(
+                    "INSERT OR IGNORE INTO blocks (block_id, root, timestamp, signature, slot)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![
+                        block.block_id,
+                        encode_hex(&block.root),
+                        block.timestamp,
+                        signature,
+                        slot
+                    ],
+                )?;
+            }
+            ChainEvent::FcdInitialized(fcd) => {
+                self.insert_fcd(&fcd.key, &fcd.value, fcd.timestamp, signature, slot)?
+            }
+            ChainEvent::FcdUpdated(fcd) => {
+                self.insert_fcd(&fcd.key, &fcd.value, fcd.timestamp, signature, slot)?
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn insert_fcd(
+        &self,
+        key: &str,
+        value: &[u8; 32],
+        timestamp: u32,
+        signature: &str,
+        slot: u64,
+    ) -> Result<()> {
+        self.connection.execute(
+            "INSERT OR IGNORE INTO fcd_values (key, value, timestamp, signature, slot)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![key, encode_hex(value), timestamp, signature, slot],
+        )?;
+        Ok(())
+    }
+
+    /// Last transaction processed by `RpcSource`.
+    pub fn cursor(&self) -> Result<Option<String>> {
+        Ok(self
+            .connection
+            .query_row("SELECT signature FROM cursor WHERE id = 0", [], |row| {
+                row.get(0)
+            })
+            .optional()?)
+    }
+
+    pub fn set_cursor(&self, signature: &str) -> Result<()> {
+        self.connection.execute(
+            "INSERT INTO cursor (id, signature) VALUES (0, ?1)
+             ON CONFLICT (id) DO UPDATE SET signature = excluded.signature",
+            params![signature],
+        )?;
+        Ok(())
+    }
+
+    pub fn block(&self, block_id: u32) -> Result<Option<BlockRecord>> {
+        self.connection
+            .query_row(
+                "SELECT block_id, root, timestamp, signature, slot FROM blocks WHERE block_id = ?1",
+                params![block_id],
+                |row| {
+                    Ok((
+                        row.get(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get(2)?,
+                        row.get(3)?,
+                        row.get(4)?,
+                    ))
+                },
+            )
+            .optional()?
+            .map(|(block_id, root, timestamp, signature, slot)| {
+                Ok(BlockRecord {
+                    block_id,
+                    root: decode_hex(&root)?,
+                    timestamp,
+                    signature,
+                    slot,
+                })
+            })
+            .transpose()
+    }
+
+    /// Value of `key` in effect at `timestamp`: the latest one whose data
+    /// timestamp is not after it.
+    pub fn fcd_at(&self, key: &str, timestamp: u32) -> Result<Option<FcdRecord>> {
+        Ok(self
+            .query_fcds(
+                "SELECT key, value, timestamp, signature, slot FROM fcd_values
+                 WHERE key = ?1 AND timestamp <= ?2
+                 ORDER BY timestamp DESC LIMIT 1",
+                params![key, timestamp],
+            )?
+            .pop())
+    }
+
+    /// Values of `key` with data timestamps in `from..=to`, oldest first.
+    pub fn fcd_history(&self, key: &str, from: u32, to: u32) -> Result<Vec<FcdRecord>> {
+        self.query_fcds(
+            "SELECT key, value, timestamp, signature, slot FROM fcd_values
+             WHERE key = ?1 AND timestamp >= ?2 AND timestamp <= ?3
+             ORDER BY timestamp",
+            params![key, from, to],
+        )
+    }
+
+    fn query_fcds(&self, sql: &str, params: impl rusqlite::Params) -> Result<Vec<FcdRecord>> {
+        let mut statement = self.connection.prepare(sql)?;
+        let rows = statement.query_map(params, |row| {
+            Ok((
+                row.get(0)?,
+                row.get::<_, String>(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+            ))
+        })?;
+
+        rows.map(|row| {
+            let (key, value, timestamp, signature, slot) = row?;
+            Ok(FcdRecord {
+                key,
+                value: decode_hex(&value)?,
+                timestamp,
+                signature,
+                slot,
+            })
+        })
+        .collect()
+    }
+
+    pub fn keys(&self) -> Result<Vec<String>> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT DISTINCT key FROM fcd_values ORDER BY key")?;
+        let keys = statement.query_map([], |row| row.get(0))?;
+        Ok(keys.collect::<rusqlite::Result<_>>()?)
+    }
+}
@@ -0,0 +1,191 @@
+use std::fs;
+
+use aquarius_indexer::*;
+use aquarius_sdk::anchor_client::anchor_lang::prelude::Pubkey;
+use aquarius_sdk::anchor_client::anchor_lang::Event;
+use aquarius_sdk::chain::events::*;
+use aquarius_sdk::encode_value;
+
+fn program_id() -> Pubkey {
+    aquarius_sdk::chain::ID
+}
+
+fn data_log(event: &impl Event) -> String {
+    format!("Program data: {}", base64::encode(event.data()))
+}
+
+fn invocation(program_id: &Pubkey, inner: Vec<String>) -> Vec<String> {
+    let mut logs = vec![format!("Program {} invoke [1]", program_id)];
+    logs.extend(inner);
+    logs.push(format!(
+        "Program {} consumed 5000 of 200000 compute units",
+        program_id
+    ));
+    logs.push(format!("Program {} success", program_id));
+    logs
+}
+
+fn fcd_update(key: &str, value: &str, timestamp: u32) -> LogFCDUpdated {
+    LogFCDUpdated {
+        key: key.to_string(),
+        value: encode_value(value, key).unwrap(),
+        timestamp,
+        owner: Pubkey::new_unique(),
+    }
+}
+
+fn tx(signature: &str, logs: Vec<String>) -> TransactionLogs {
+    TransactionLogs {
+        signature: signature.to_string(),
+        slot: 100,
+        err: None,
+        logs,
+    }
+}
+
+#[test]
+fn parses_events_of_the_program_only() {
+    let other = Pubkey::new_unique();
+    let mut logs = invocation(
+        &program_id(),
+        vec![
+            "Program log: Instruction: UpdateFirstClassData".to_string(),
+            "Program log: LogFCDUpdated: \"ETH-USD\"".to_string(),
+            data_log(&fcd_update("ETH-USD", "3001.23", 1651640200)),
+        ],
+    );
+    logs.extend(invocation(
+        &other,
+        vec![data_log(&fcd_update("FAKE-USD", "1", 1651640200))],
+    ));
+
+    let events = parse_logs(&program_id(), &logs);
+    assert_eq!(events.len(), 1);
+    match &events[0] {
+        ChainEvent::FcdUpdated(event) => assert_eq!(event.key, "ETH-USD"),
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn parses_events_emitted_through_cpi() {
+    let caller = Pubkey::new_unique();
+    let logs = invocation(
+        &caller,
+        vec![
+            format!("Program {} invoke [2]", program_id()),
+            data_log(&LogPaddingSet {
+                padding: 10,
+                owner: caller,
+            }),
+            format!("Program {} success", program_id()),
+            data_log(&fcd_update("FAKE-USD", "1", 1651640200)),
+        ],
+    );
+
+    let events = parse_logs(&program_id(), &logs);
+    assert_eq!(events.len(), 1);
+    assert!(matches!(events[0], ChainEvent::PaddingSet(_)));
+}
+
+#[test]
+fn answers_fcd_value_at_time() {
+    let store = Store::in_memory().unwrap();
+    for (i, (value, timestamp)) in [("3000", 1000), ("3100", 2000), ("3200", 3000)]
+        .iter()
+        .enumerate()
+    {
+        let logs = invocation(
+            &program_id(),
+            vec![data_log(&fcd_update("ETH-USD", value, *timestamp))],
+        );
+        let count =
+            index_transaction(&store, &program_id(), &tx(&format!("sig{}", i), logs)).unwrap();
+        assert_eq!(count, 1);
+    }
+
+    assert_eq!(store.fcd_at("ETH-USD", 999).unwrap(), None);
+    let value = |at| store.fcd_at("ETH-USD", at).unwrap().unwrap().value;
+    assert_eq!(value(1000), encode_value("3000", "ETH-USD").unwrap());
+    assert_eq!(value(2999), encode_value("3100", "ETH-USD").unwrap());
+    assert_eq!(value(u32::MAX), encode_value("3200", "ETH-USD").unwrap());
+
+    let history = store.fcd_history("ETH-USD", 1500, 3000).unwrap();
+    assert_eq!(
+        history.iter().map(|fcd| fcd.timestamp).collect::<Vec<_>>(),
+        vec![2000, 3000]
+    );
+    assert_eq!(store.keys().unwrap(), vec!["ETH-USD"]);
+}
+
+#[test]
+fn answers_block_roots_and_ignores_replays_and_failures() {
+    let store = Store::in_memory().unwrap();
+    let block = |block_id, root_byte| LogBlockSubmitted {
+        block_id,
+        root: [root_byte; 32],
+        timestamp: 1651640200 + block_id,
+        owner: Pubkey::new_unique(),
+    };
+
+    let logs = invocation(&program_id(), vec![data_log(&block(1, 0xaa))]);
+    index_transaction(&store, &program_id(), &tx("first", logs.clone())).unwrap();
+    index_transaction(&store, &program_id(), &tx("replay", logs)).unwrap();
+
+    let mut failed = tx(
+        "failed",
+        invocation(&program_id(), vec![data_log(&block(2, 0xbb))]),
+    );
+    failed.err = Some(serde_json::json!({"InstructionError": [0, {"Custom": 6002}]}));
+    assert_eq!(
+        index_transaction(&store, &program_id(), &failed).unwrap(),
+        0
+    );
+
+    let stored = store.block(1).unwrap().unwrap();
+    assert_eq!(stored.root, [0xaa; 32]);
+    assert_eq!(stored.signature, "first");
+    assert_eq!(store.block(2).unwrap(), None);
+}
+
+#[test]
+fn imports_log_files_and_serves_queries() {
+    let path = std::env::temp_dir().join(format!("aquarius-indexer-{}.jsonl", std::process::id()));
+    let lines = [
+        tx(
+            "a",
+            invocation(
+                &program_id(),
+                vec![data_log(&fcd_update("BTC-USD", "39000.5", 1000))],
+            ),
+        ),
+        tx(
+            "b",
+            invocation(
+                &program_id(),
+                vec![data_log(&fcd_update("BTC-USD", "40000", 2000))],
+            ),
+        ),
+    ]
+    .iter()
+    .map(|tx| serde_json::to_string(tx).unwrap())
+    .collect::<Vec<_>>()
+    .join("\n");
+    fs::write(&path, lines).unwrap();
+
+    let store = Store::in_memory().unwrap();
+    assert_eq!(import_file(&store, &program_id(), &path).unwrap(), 2);
+    fs::remove_file(&path).unwrap();
+
+    let value = query(&store, "/fcds/BTC-USD?at=1500").unwrap().unwrap();
+    assert_eq!(value["value"], "39000.5");
+    assert_eq!(value["signature"], "a");
+    let latest = query(&store, "/fcds/BTC-USD").unwrap().unwrap();
+    assert_eq!(latest["value"], "40000");
+    let history = query(&store, "/fcds/BTC-USD/history?from=0&to=5000")
+        .unwrap()
+        .unwrap();
+    assert_eq!(history.as_array().unwrap().len(), 2);
+    assert_eq!(query(&store, "/blocks/7").unwrap(), None);
+    assert!(query(&store, "/fcds/BTC-USD?at=soon").is_err());
+}
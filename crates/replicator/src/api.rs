@@ -0,0 +1,110 @@
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use aquarius_sdk::{decode_hex, encode_value};
+use serde::Deserialize;
+
+/// Block as returned by the Umbrella API.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SidechainBlock {
+    pub block_id: u32,
+    /// Root with the data timestamp squashed into it.
+    pub root: String,
+    pub data_timestamp: u32,
+    pub status: String,
+}
+
+impl SidechainBlock {
+    pub fn is_finalized(&self) -> bool {
+        self.status == "finalized"
+    }
+
+    pub fn root_bytes(&self) -> Result<[u8; 32]> {
+        Ok(decode_hex(&self.root)?)
+    }
+}
+
+/// First class data value as returned by the Umbrella API.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SidechainFcd {
+    pub key: String,
+    /// Number, decimal string, or hex string for `FIXED_` keys.
+    pub value: serde_json::Value,
+    pub data_timestamp: u32,
+}
+
+impl SidechainFcd {
+    pub fn value_bytes(&self) -> Result<[u8; 32]> {
+        let value = match &self.value {
+            serde_json::Value::String(value) => value.clone(),
+            value => value.to_string(),
+        };
+        Ok(encode_value(&value, &self.key)?)
+    }
+}
+
+pub trait UmbrellaApi {
+    /// Most recent finalized block.
+    fn latest_block(&self) -> Result<SidechainBlock>;
+
+    /// Latest value of every first class data feed.
+    fn first_class_data(&self) -> Result<Vec<SidechainFcd>>;
+}
+
+/// `UmbrellaApi` over HTTP.
+///
+/// Expects `GET {url}/blocks?offset=0&limit=N` to return a JSON array of
+/// blocks, newest first, and `GET {url}/fcds` a JSON array of feeds.
+pub struct HttpApi {
+    url: String,
+    api_key: Option<String>,
+    client: reqwest::blocking::Client,
+}
+
+/// How many recent blocks are scanned for a finalized one.
+const BLOCKS_PAGE: usize = 10;
+
+impl HttpApi {
+    pub fn new(url: &str, api_key: Option<String>) -> Result<Self> {
+        Ok(Self {
+            url: url.trim_end_matches('/').to_string(),
+            api_key,
+            client: reqwest::blocking::Client::builder()
+                .timeout(Duration::from_secs(30))
+                .build()?,
+        })
+    }
+
+    fn get<T: serde::de::DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let url = format!("{}{}", self.url, path);
+        let mut request = self.client.get(&url);
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        request
+            .send()
+            .and_then(|response| response.error_for_status())
+            .and_then(|response| response.json())
+            .with_context(|| format!("GET {}", url))
+    }
+}
+
+impl UmbrellaApi for HttpApi {
+    fn latest_block(&self) -> Result<SidechainBlock> {
+        let blocks: Vec<SidechainBlock> =
+            self.get(&format!("/blocks?offset=0&limit={}", BLOCKS_PAGE))?;
+
+        blocks
+            .into_iter()
+            .filter(SidechainBlock::is_finalized)
+            .max_by_key(|block| block.block_id)
+            .context("no finalized block in the latest page")
+    }
+
+    fn first_class_data(&self) -> Result<Vec<SidechainFcd>> {
+        self.get("/fcds")
+    }
+}
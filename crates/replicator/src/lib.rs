@@ -0,0 +1,17 @@
+//! Mirrors finalized Umbrella sidechain blocks and first class data to the
+//! `chain` program.
+//!
+//! The sidechain is read through `UmbrellaApi` and Solana is written through
+//! `ChainTarget`, so both ends can be replaced by mocks.
+
+pub use api::*;
+pub use metrics::*;
+pub use replicator::*;
+pub use retry::*;
+pub use target::*;
+
+pub mod api;
+pub mod metrics;
+pub mod replicator;
+pub mod retry;
+pub mod target;
@@ -0,0 +1,101 @@
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use aquarius_replicator::*;
+use aquarius_sdk::anchor_client::anchor_lang::prelude::Pubkey;
+use aquarius_sdk::anchor_client::solana_sdk::signature::read_keypair_file;
+use aquarius_sdk::anchor_client::Cluster;
+use aquarius_sdk::ChainClient;
+use clap::Parser;
+
+#[derive(Parser)]
+#[clap(
+    name = "replicator",
+    version,
+    about = "Mirrors Umbrella sidechain blocks to Solana"
+)]
+struct Opts {
+    /// Cluster name (localnet, devnet, mainnet) or RPC url
+    #[clap(long, short = 'u', default_value = "localnet")]
+    url: Cluster,
+
+    #[clap(long, default_value_t = aquarius_sdk::chain::ID)]
+    program_id: Pubkey,
+
+    /// Keypair of the program owner
+    #[clap(long, short = 'k')]
+    keypair: PathBuf,
+
+    /// Base url of the Umbrella API
+    #[clap(long, env = "UMBRELLA_API_URL")]
+    api_url: String,
+
+    #[clap(long, env = "UMBRELLA_API_KEY", hide_env_values = true)]
+    api_key: Option<String>,
+
+    /// Feed to replicate, can be repeated; all feeds when omitted
+    #[clap(long = "fcd")]
+    fcd_keys: Vec<String>,
+
+    /// Create feeds missing on Solana
+    #[clap(long)]
+    initialize_fcds: bool,
+
+    /// Seconds between replication rounds
+    #[clap(long, default_value_t = 60)]
+    interval: u64,
+
+    /// Maximum feed updates per transaction
+    #[clap(long, default_value_t = 5)]
+    batch_size: usize,
+
+    #[clap(long, default_value_t = 3)]
+    retries: u32,
+
+    /// Milliseconds before the first retry, doubled after each failure
+    #[clap(long, default_value_t = 1000)]
+    retry_delay: u64,
+
+    #[clap(long, default_value = "0.0.0.0:9100")]
+    metrics_address: SocketAddr,
+}
+
+fn main() -> Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    let opts = Opts::parse();
+
+    let payer = read_keypair_file(&opts.keypair)
+        .map_err(|err| anyhow!("{}", err))
+        .with_context(|| format!("reading keypair {}", opts.keypair.display()))?;
+
+    let metrics = Metrics::new()?;
+    metrics.serve(opts.metrics_address)?;
+    log::info!("serving metrics on {}", opts.metrics_address);
+
+    let replicator = Replicator::new(
+        HttpApi::new(&opts.api_url, opts.api_key)?,
+        ChainClient::new(opts.url, Rc::new(payer), opts.program_id),
+        Config {
+            fcd_keys: opts.fcd_keys,
+            initialize_fcds: opts.initialize_fcds,
+            batch_size: opts.batch_size,
+            retry: Retry {
+                attempts: opts.retries + 1,
+                delay: Duration::from_millis(opts.retry_delay),
+            },
+        },
+        metrics,
+    );
+
+    loop {
+        match replicator.tick() {
+            Ok(report) => log::info!("{:?}", report),
+            Err(err) => log::error!("{:#}", err),
+        }
+        thread::sleep(Duration::from_secs(opts.interval));
+    }
+}
@@ -0,0 +1,97 @@
+use std::net::SocketAddr;
+use std::thread;
+
+use anyhow::{anyhow, Result};
+use prometheus::{Encoder, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry, TextEncoder};
+
+/// Prometheus metrics of the replicator.
+#[derive(Clone)]
+pub struct Metrics {
+    pub registry: Registry,
+    /// Latest finalized block id reported by the sidechain.
+    pub sidechain_block_id: IntGauge,
+    /// Block id last stored on Solana.
+    pub replicated_block_id: IntGauge,
+    /// Seconds between the latest sidechain block and the last stored one.
+    pub block_lag_seconds: IntGauge,
+    /// Seconds between the sidechain and the stored value, per feed.
+    pub fcd_lag_seconds: IntGaugeVec,
+    /// Transactions sent, by kind.
+    pub transactions: IntCounterVec,
+    /// Operations that failed after all retries, by kind.
+    pub failures: IntCounterVec,
+}
+
+impl Metrics {
+    pub fn new() -> Result<Self> {
+        let registry = Registry::new_custom(Some("replicator".to_string()), None)?;
+
+        let sidechain_block_id =
+            IntGauge::new("sidechain_block_id", "Latest finalized sidechain block id")?;
+        let replicated_block_id =
+            IntGauge::new("replicated_block_id", "Last block id stored on Solana")?;
+        let block_lag_seconds =
+            IntGauge::new("block_lag_seconds", "Data timestamp lag of stored blocks")?;
+        let fcd_lag_seconds = IntGaugeVec::new(
+            Opts::new(
+                "fcd_lag_seconds",
+                "Data timestamp lag of stored first class data",
+            ),
+            &["key"],
+        )?;
+        let transactions = IntCounterVec::new(
+            Opts::new("transactions_total", "Transactions sent to Solana"),
+            &["kind"],
+        )?;
+        let failures = IntCounterVec::new(
+            Opts::new("failures_total", "Operations failed after all retries"),
+            &["operation"],
+        )?;
+
+        registry.register(Box::new(sidechain_block_id.clone()))?;
+        registry.register(Box::new(replicated_block_id.clone()))?;
+        registry.register(Box::new(block_lag_seconds.clone()))?;
+        registry.register(Box::new(fcd_lag_seconds.clone()))?;
+        registry.register(Box::new(transactions.clone()))?;
+        registry.register(Box::new(failures.clone()))?;
+
+        Ok(Self {
+            registry,
+            sidechain_block_id,
+            replicated_block_id,
+            block_lag_seconds,
+            fcd_lag_seconds,
+            transactions,
+            failures,
+        })
+    }
+
+    /// Metrics in the Prometheus text format.
+    pub fn render(&self) -> Result<String> {
+        let mut buffer = vec![];
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+
+    /// Serves `render` on every request to `address` from a background thread.
+    pub fn serve(&self, address: SocketAddr) -> Result<()> {
+        let server = tiny_http::Server::http(address).map_err(|err| anyhow!("{}", err))?;
+        let metrics = self.clone();
+
+        thread::spawn(move || {
+            for request in server.incoming_requests() {
+                let response = match metrics.render() {
+                    Ok(body) => tiny_http::Response::from_string(body),
+                    Err(err) => {
+                        tiny_http::Response::from_string(err.to_string()).with_status_code(500)
+                    }
+                };
+                if let Err(err) = request.respond(response) {
+                    log::warn!("failed to serve metrics: {}", err);
+                }
+            }
+        });
+
+        Ok(())
+    }
+}
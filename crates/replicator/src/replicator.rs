@@ -0,0 +1,187 @@
+use anyhow::Result;
+use aquarius_sdk::FcdUpdate;
+
+use crate::api::UmbrellaApi;
+use crate::metrics::Metrics;
+use crate::retry::Retry;
+use crate::target::ChainTarget;
+
+pub struct Config {
+    /// Feeds to replicate, all feeds returned by the API when empty.
+    pub fcd_keys: Vec<String>,
+    /// Create feeds that do not exist on Solana yet.
+    pub initialize_fcds: bool,
+    /// Maximum number of feed updates per transaction.
+    pub batch_size: usize,
+    pub retry: Retry,
+}
+
+/// What a single `tick` did.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Report {
+    pub submitted_block: Option<u32>,
+    pub initialized_fcds: Vec<String>,
+    pub updated_fcds: Vec<String>,
+}
+
+pub struct Replicator<A, T> {
+    api: A,
+    target: T,
+    config: Config,
+    metrics: Metrics,
+}
+
+impl<A: UmbrellaApi, T: ChainTarget> Replicator<A, T> {
+    pub fn new(api: A, target: T, config: Config, metrics: Metrics) -> Self {
+        Self {
+            api,
+            target,
+            config,
+            metrics,
+        }
+    }
+
+    pub fn target(&self) -> &T {
+        &self.target
+    }
+
+    /// Replicates the latest block and feeds once.
+    ///
+    /// Data is only sent when it is newer than what is stored and outside
+    /// of `Status.padding`, so the program never rejects it as old or spam.
+    pub fn tick(&self) -> Result<Report> {
+        let mut report = Report::default();
+
+        let block_result = self.replicate_block(&mut report);
+        let fcd_result = self.replicate_fcds(&mut report);
+
+        block_result.and(fcd_result).map(|_| report)
+    }
+
+    fn replicate_block(&self, report: &mut Report) -> Result<()> {
+        let retry = self.config.retry;
+        let status = retry.run("fetching status", || self.target.status())?;
+        let block = retry.run("fetching latest block", || self.api.latest_block())?;
+
+        self.metrics.sidechain_block_id.set(block.block_id.into());
+        self.metrics.replicated_block_id.set(status.last_id.into());
+        self.metrics
+            .block_lag_seconds
+            .set(lag(block.data_timestamp, status.last_data_timestamp));
+
+        if block.data_timestamp <= status.last_data_timestamp {
+            log::debug!("block {} is already replicated", block.block_id);
+            return Ok(());
+        }
+
+        if u64::from(block.data_timestamp)
+            <= u64::from(status.last_data_timestamp) + u64::from(status.padding)
+        {
+            log::debug!("block {} is within padding", block.block_id);
+            return Ok(());
+        }
+
+        let root = block.root_bytes()?;
+        retry
+            .run("submitting block", || {
+                self.target
+                    .submit(block.block_id, root, block.data_timestamp)
+            })
+            .map_err(|err| self.failed("submit", err))?;
+
+        self.metrics
+            .transactions
+            .with_label_values(&["submit"])
+            .inc();
+        self.metrics.replicated_block_id.set(block.block_id.into());
+        self.metrics.block_lag_seconds.set(0);
+        report.submitted_block = Some(block.block_id);
+        Ok(())
+    }
+
+    fn replicate_fcds(&self, report: &mut Report) -> Result<()> {
+        let retry = self.config.retry;
+        let status = retry.run("fetching status", || self.target.status())?;
+        let fcds = retry.run("fetching first class data", || self.api.first_class_data())?;
+
+        let mut updates = vec![];
+        for fcd in fcds {
+            if !self.config.fcd_keys.is_empty() && !self.config.fcd_keys.contains(&fcd.key) {
+                continue;
+            }
+
+            let update = FcdUpdate {
+                value: fcd.value_bytes()?,
+                key: fcd.key,
+                timestamp: fcd.data_timestamp,
+                // the sidechain API does not report confidence intervals
+                confidence: None,
+            };
+
+            let stored = retry.run("fetching first class data", || self.target.fcd(&update.key))?;
+            match stored {
+                None if self.config.initialize_fcds => {
+                    retry
+                        .run("initializing first class data", || {
+                            self.target.initialize_fcd(&update)
+                        })
+                        .map_err(|err| self.failed("initialize_fcd", err))?;
+
+                    self.metrics
+                        .transactions
+                        .with_label_values(&["initialize_fcd"])
+                        .inc();
+                    self.metrics
+                        .fcd_lag_seconds
+                        .with_label_values(&[&update.key])
+                        .set(0);
+                    report.initialized_fcds.push(update.key);
+                }
+                None => log::warn!("{} is not initialized on chain", update.key),
+                Some(stored) => {
+                    self.metrics
+                        .fcd_lag_seconds
+                        .with_label_values(&[&update.key])
+                        .set(lag(update.timestamp, stored.timestamp));
+
+                    if u64::from(update.timestamp)
+                        > u64::from(stored.timestamp) + u64::from(status.padding)
+                    {
+                        updates.push(update);
+                    }
+                }
+            }
+        }
+
+        for batch in updates.chunks(self.config.batch_size.max(1)) {
+            retry
+                .run("updating first class data", || {
+                    self.target.update_fcds(batch)
+                })
+                .map_err(|err| self.failed("update_fcds", err))?;
+
+            self.metrics
+                .transactions
+                .with_label_values(&["update_fcds"])
+                .inc();
+            for update in batch {
+                self.metrics
+                    .fcd_lag_seconds
+                    .with_label_values(&[&update.key])
+                    .set(0);
+                report.updated_fcds.push(update.key.clone());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn failed(&self, operation: &str, err: anyhow::Error) -> anyhow::Error {
+        self.metrics.failures.with_label_values(&[operation]).inc();
+        err
+    }
+}
+
+fn lag(latest: u32, stored: u32) -> i64 {
+    i64::from(latest.saturating_sub(stored))
+}
@@ -0,0 +1,34 @@
+use std::thread;
+use std::time::Duration;
+
+use anyhow::Result;
+
+/// Retry policy for calls to the API and to Solana.
+#[derive(Clone, Copy, Debug)]
+pub struct Retry {
+    pub attempts: u32,
+    /// Delay before the first retry, doubled after every failure.
+    pub delay: Duration,
+}
+
+impl Retry {
+    pub fn run<T>(&self, operation: &str, mut f: impl FnMut() -> Result<T>) -> Result<T> {
+        let mut delay = self.delay;
+        let mut attempt = 1;
+
+        loop {
+            match f() {
+                Ok(value) => return Ok(value),
+                Err(err) if attempt < self.attempts => {
+                    log::warn!("{} failed (attempt {}): {:#}", operation, attempt, err);
+                    thread::sleep(delay);
+                    delay *= 2;
+                    attempt += 1;
+                }
+                Err(err) => {
+                    return Err(err.context(format!("{} failed {} times", operation, attempt)))
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use anyhow::Result;
+use aquarius_sdk::chain::state::{FirstClassData, Status};
+use aquarius_sdk::{ChainClient, FcdUpdate};
+
+/// Write side of the replicator.
+pub trait ChainTarget {
+    fn status(&self) -> Result<Status>;
+
+    fn fcd(&self, key: &str) -> Result<Option<FirstClassData>>;
+
+    fn submit(&self, block_id: u32, root: [u8; 32], timestamp: u32) -> Result<()>;
+
+    fn initialize_fcd(&self, update: &FcdUpdate) -> Result<()>;
+
+    /// Sends all updates in one transaction.
+    fn update_fcds(&self, updates: &[FcdUpdate]) -> Result<()>;
+}
+
+impl ChainTarget for ChainClient {
+    fn status(&self) -> Result<Status> {
+        Ok(ChainClient::status(self)?)
+    }
+
+    fn fcd(&self, key: &str) -> Result<Option<FirstClassData>> {
+        Ok(self.try_fcd(key)?)
+    }
+
+    fn submit(&self, block_id: u32, root: [u8; 32], timestamp: u32) -> Result<()> {
+        let signature = ChainClient::submit(self, block_id, root, timestamp)?;
+        log::info!("submitted block {} ({})", block_id, signature);
+        Ok(())
+    }
+
+    fn initialize_fcd(&self, update: &FcdUpdate) -> Result<()> {
+        let signature =
+            self.initialize_first_class_data(&update.key, update.value, update.timestamp)?;
+        log::info!("initialized {} ({})", update.key, signature);
+        Ok(())
+    }
+
+    fn update_fcds(&self, updates: &[FcdUpdate]) -> Result<()> {
+        let signature = self.update_first_class_data(updates)?;
+        log::info!("updated {} feeds ({})", updates.len(), signature);
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+use std::thread;
+
+use aquarius_replicator::*;
+use aquarius_sdk::encode_value;
+
+/// Serves `responses` in order and returns the base url.
+fn serve(responses: Vec<&'static str>) -> String {
+    let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", server.server_addr());
+
+    thread::spawn(move || {
+        for body in responses {
+            let request = server.recv().unwrap();
+            let authorized = request.headers().iter().any(|header| {
+                header.field.equiv("Authorization") && header.value == "Bearer secret"
+            });
+            let response = if authorized {
+                tiny_http::Response::from_string(body)
+            } else {
+                tiny_http::Response::from_string("").with_status_code(401)
+            };
+            request.respond(response).unwrap();
+        }
+    });
+
+    url
+}
+
+#[test]
+fn reads_latest_finalized_block() {
+    let url = serve(vec![
+        r#"[
+            {"blockId": 1002, "root": "0x00", "dataTimestamp": 1651640400, "status": "completed"},
+            {"blockId": 1001, "root": "0xa875e64b4762d5a34bf3b0346829c407fa82eaedb67d41c6aa4a350862333c0d", "dataTimestamp": 1651640300, "status": "finalized"},
+            {"blockId": 1000, "root": "0x00", "dataTimestamp": 1651640200, "status": "finalized"}
+        ]"#,
+    ]);
+
+    let block = HttpApi::new(&url, Some("secret".to_string()))
+        .unwrap()
+        .latest_block()
+        .unwrap();
+    assert_eq!(block.block_id, 1001);
+    assert_eq!(block.data_timestamp, 1651640300);
+    assert_eq!(block.root_bytes().unwrap()[0], 0xa8);
+}
+
+#[test]
+fn reads_first_class_data() {
+    let url = serve(vec![
+        r#"[
+            {"key": "ETH-USD", "value": 3001.23, "dataTimestamp": 1651640300},
+            {"key": "BTC-USD", "value": "39000.5", "dataTimestamp": 1651640300}
+        ]"#,
+    ]);
+
+    let fcds = HttpApi::new(&url, Some("secret".to_string()))
+        .unwrap()
+        .first_class_data()
+        .unwrap();
+    assert_eq!(fcds.len(), 2);
+    assert_eq!(
+        fcds[0].value_bytes().unwrap(),
+        encode_value("3001.23", "ETH-USD").unwrap()
+    );
+    assert_eq!(
+        fcds[1].value_bytes().unwrap(),
+        encode_value("39000.5", "BTC-USD").unwrap()
+    );
+}
+
+#[test]
+fn fails_on_http_errors() {
+    let url = serve(vec!["[]"]);
+    assert!(HttpApi::new(&url, None)
+        .unwrap()
+        .first_class_data()
+        .is_err());
+}
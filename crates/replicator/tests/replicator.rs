@@ -0,0 +1,296 @@
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use aquarius_replicator::*;
+use aquarius_sdk::chain::state::{FeedMetadata, FirstClassData, Status};
+use aquarius_sdk::{encode_value, FcdUpdate};
+
+const ROOT: &str = "0xa875e64b4762d5a34bf3b0346829c407fa82eaedb67d41c6aa4a350862333c0d";
+
+#[derive(Default)]
+struct MockApi {
+    block: RefCell<Option<SidechainBlock>>,
+    fcds: RefCell<Vec<SidechainFcd>>,
+}
+
+impl MockApi {
+    fn set_block(&self, block_id: u32, data_timestamp: u32) {
+        *self.block.borrow_mut() = Some(SidechainBlock {
+            block_id,
+            root: ROOT.to_string(),
+            data_timestamp,
+            status: "finalized".to_string(),
+        });
+    }
+
+    fn set_fcd(&self, key: &str, value: &str, data_timestamp: u32) {
+        let mut fcds = self.fcds.borrow_mut();
+        fcds.retain(|fcd| fcd.key != key);
+        fcds.push(SidechainFcd {
+            key: key.to_string(),
+            value: serde_json::Value::String(value.to_string()),
+            data_timestamp,
+        });
+    }
+}
+
+impl UmbrellaApi for &MockApi {
+    fn latest_block(&self) -> Result<SidechainBlock> {
+        match self.block.borrow().clone() {
+            Some(block) => Ok(block),
+            None => bail!("no blocks"),
+        }
+    }
+
+    fn first_class_data(&self) -> Result<Vec<SidechainFcd>> {
+        Ok(self.fcds.borrow().clone())
+    }
+}
+
+/// Applies the same checks as the chain program.
+#[derive(Default)]
+struct MockTarget {
+    status: RefCell<Status>,
+    fcds: RefCell<BTreeMap<String, FirstClassData>>,
+    blocks: RefCell<Vec<u32>>,
+    transactions: Cell<usize>,
+    failures_left: Cell<usize>,
+}
+
+impl MockTarget {
+    fn send(&self) -> Result<()> {
+        if self.failures_left.get() > 0 {
+            self.failures_left.set(self.failures_left.get() - 1);
+            bail!("node is behind");
+        }
+        self.transactions.set(self.transactions.get() + 1);
+        Ok(())
+    }
+}
+
+impl ChainTarget for &MockTarget {
+    fn status(&self) -> Result<Status> {
+        Ok(self.status.borrow().clone())
+    }
+
+    fn fcd(&self, key: &str) -> Result<Option<FirstClassData>> {
+        Ok(self.fcds.borrow().get(key).cloned())
+    }
+
+    fn submit(&self, block_id: u32, _root: [u8; 32], timestamp: u32) -> Result<()> {
+        self.send()?;
+        let mut status = self.status.borrow_mut();
+        assert!(
+            status.last_data_timestamp < timestamp,
+            "CannotSubmitOlderData"
+        );
+        assert!(
+            status.last_data_timestamp + status.padding < timestamp,
+            "DoNotSpam"
+        );
+        status.last_id = block_id;
+        status.last_data_timestamp = timestamp;
+        status.next_block_id = block_id + 1;
+        self.blocks.borrow_mut().push(block_id);
+        Ok(())
+    }
+
+    fn initialize_fcd(&self, update: &FcdUpdate) -> Result<()> {
+        self.send()?;
+        let fcd = FirstClassData {
+            key: update.key.clone(),
+            value: update.value,
+            timestamp: update.timestamp,
+            metadata: FeedMetadata::umbrella(&update.key),
+            has_history: false,
+            cumulative: 0,
+            aggregated: false,
+            confidence: [0u8; 32],
+            has_confidence: false,
+            registry_page: None,
+        };
+        assert!(self
+            .fcds
+            .borrow_mut()
+            .insert(update.key.clone(), fcd)
+            .is_none());
+        Ok(())
+    }
+
+    fn update_fcds(&self, updates: &[FcdUpdate]) -> Result<()> {
+        self.send()?;
+        let padding = self.status.borrow().padding;
+        let mut fcds = self.fcds.borrow_mut();
+        for update in updates {
+            let fcd = fcds.get_mut(&update.key).expect("WrongFCDKeyForAccount");
+            assert!(fcd.timestamp < update.timestamp, "CannotSubmitOlderData");
+            assert!(fcd.timestamp + padding < update.timestamp, "DoNotSpam");
+            fcd.value = update.value;
+            fcd.timestamp = update.timestamp;
+        }
+        Ok(())
+    }
+}
+
+fn config() -> Config {
+    Config {
+        fcd_keys: vec![],
+        initialize_fcds: true,
+        batch_size: 2,
+        retry: Retry {
+            attempts: 3,
+            delay: Duration::ZERO,
+        },
+    }
+}
+
+fn replicator<'a>(
+    api: &'a MockApi,
+    target: &'a MockTarget,
+    config: Config,
+) -> (Replicator<&'a MockApi, &'a MockTarget>, Metrics) {
+    let metrics = Metrics::new().unwrap();
+    (
+        Replicator::new(api, target, config, metrics.clone()),
+        metrics,
+    )
+}
+
+#[test]
+fn submits_new_blocks_and_skips_replicated_ones() {
+    let api = MockApi::default();
+    let target = MockTarget::default();
+    let (replicator, metrics) = replicator(&api, &target, config());
+
+    api.set_block(1000, 1651640200);
+    assert_eq!(replicator.tick().unwrap().submitted_block, Some(1000));
+    assert_eq!(replicator.tick().unwrap().submitted_block, None);
+
+    api.set_block(1001, 1651640300);
+    assert_eq!(replicator.tick().unwrap().submitted_block, Some(1001));
+    assert_eq!(*target.blocks.borrow(), vec![1000, 1001]);
+    assert_eq!(metrics.replicated_block_id.get(), 1001);
+    assert_eq!(metrics.block_lag_seconds.get(), 0);
+}
+
+#[test]
+fn waits_for_padding_before_submitting() {
+    let api = MockApi::default();
+    let target = MockTarget::default();
+    target.status.borrow_mut().padding = 60;
+    target.status.borrow_mut().last_data_timestamp = 1651640200;
+    let (replicator, metrics) = replicator(&api, &target, config());
+
+    api.set_block(1001, 1651640260);
+    assert_eq!(replicator.tick().unwrap().submitted_block, None);
+    assert_eq!(metrics.sidechain_block_id.get(), 1001);
+    assert_eq!(metrics.block_lag_seconds.get(), 60);
+
+    api.set_block(1002, 1651640261);
+    assert_eq!(replicator.tick().unwrap().submitted_block, Some(1002));
+}
+
+#[test]
+fn initializes_then_updates_feeds_in_batches() {
+    let api = MockApi::default();
+    let target = MockTarget::default();
+    api.set_block(1000, 1651640200);
+    for key in ["BTC-USD", "ETH-USD", "SOL-USD"] {
+        api.set_fcd(key, "1", 1651640200);
+    }
+    let (replicator, _) = replicator(&api, &target, config());
+
+    let report = replicator.tick().unwrap();
+    assert_eq!(
+        report.initialized_fcds,
+        vec!["BTC-USD", "ETH-USD", "SOL-USD"]
+    );
+    assert_eq!(target.transactions.get(), 4);
+
+    for key in ["BTC-USD", "ETH-USD", "SOL-USD"] {
+        api.set_fcd(key, "2.5", 1651640300);
+    }
+    let report = replicator.tick().unwrap();
+    assert_eq!(report.updated_fcds, vec!["BTC-USD", "ETH-USD", "SOL-USD"]);
+    // two batches of at most two updates
+    assert_eq!(target.transactions.get(), 6);
+    assert_eq!(
+        target.fcds.borrow()["ETH-USD"].value,
+        encode_value("2.5", "ETH-USD").unwrap()
+    );
+
+    assert_eq!(
+        replicator.tick().unwrap().updated_fcds,
+        Vec::<String>::new()
+    );
+}
+
+#[test]
+fn only_replicates_configured_feeds() {
+    let api = MockApi::default();
+    let target = MockTarget::default();
+    api.set_block(1000, 1651640200);
+    api.set_fcd("BTC-USD", "1", 1651640200);
+    api.set_fcd("ETH-USD", "1", 1651640200);
+    let (replicator, _) = replicator(
+        &api,
+        &target,
+        Config {
+            fcd_keys: vec!["ETH-USD".to_string()],
+            initialize_fcds: false,
+            ..config()
+        },
+    );
+
+    assert!(replicator.tick().unwrap().initialized_fcds.is_empty());
+    assert!(target.fcds.borrow().is_empty());
+
+    target.fcds.borrow_mut().insert(
+        "ETH-USD".to_string(),
+        FirstClassData {
+            key: "ETH-USD".to_string(),
+            value: [0u8; 32],
+            timestamp: 1651640100,
+            metadata: FeedMetadata::umbrella("ETH-USD"),
+            has_history: false,
+            cumulative: 0,
+            aggregated: false,
+            confidence: [0u8; 32],
+            has_confidence: false,
+            registry_page: None,
+        },
+    );
+    assert_eq!(replicator.tick().unwrap().updated_fcds, vec!["ETH-USD"]);
+}
+
+#[test]
+fn retries_failed_transactions() {
+    let api = MockApi::default();
+    let target = MockTarget::default();
+    api.set_block(1000, 1651640200);
+    target.failures_left.set(2);
+    let (replicator, metrics) = replicator(&api, &target, config());
+
+    assert_eq!(replicator.tick().unwrap().submitted_block, Some(1000));
+    assert_eq!(metrics.failures.with_label_values(&["submit"]).get(), 0);
+}
+
+#[test]
+fn counts_failures_after_all_retries() {
+    let api = MockApi::default();
+    let target = MockTarget::default();
+    api.set_block(1000, 1651640200);
+    target.failures_left.set(3);
+    let (replicator, metrics) = replicator(&api, &target, config());
+
+    assert!(replicator.tick().is_err());
+    assert_eq!(metrics.failures.with_label_values(&["submit"]).get(), 1);
+    assert!(metrics
+        .render()
+        .unwrap()
+        .contains("replicator_failures_total"));
+
+    assert_eq!(replicator.tick().unwrap().submitted_block, Some(1000));
+}
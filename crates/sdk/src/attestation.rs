@@ -0,0 +1,59 @@
+use anchor_client::anchor_lang::prelude::Pubkey;
+use anchor_client::anchor_lang::solana_program::instruction::Instruction;
+use anchor_client::solana_sdk::ed25519_instruction::{
+    DATA_START, PUBKEY_SERIALIZED_SIZE, SIGNATURE_SERIALIZED_SIZE,
+};
+use anchor_client::solana_sdk::ed25519_program;
+use anchor_client::solana_sdk::signature::{Keypair, Signature, Signer};
+use chain::state::attestation_message;
+
+/// Root of a block signed by a validator with `sign_attestation`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Attestation {
+    pub root: [u8; 32],
+    pub signature: Signature,
+}
+
+/// Signature of `validator` attesting `root` as the root of `block_id`, the
+/// evidence `report_equivocation` takes.
+pub fn sign_attestation(validator: &Keypair, block_id: u32, root: &[u8; 32]) -> Signature {
+    validator.sign_message(&attestation_message(block_id, root))
+}
+
+/// Ed25519 program instruction checking a `sign_attestation` signature, laid out
+/// like `new_ed25519_instruction` but built from the signature alone, as the
+/// reporter of an equivocation doesn't hold the validator's key.
+pub fn attestation_instruction(
+    validator: &Pubkey,
+    block_id: u32,
+    root: &[u8; 32],
+    signature: &Signature,
+) -> Instruction {
+    let message = attestation_message(block_id, root);
+    let public_key_offset = DATA_START;
+    let signature_offset = public_key_offset + PUBKEY_SERIALIZED_SIZE;
+    let message_offset = signature_offset + SIGNATURE_SERIALIZED_SIZE;
+
+    // one signature and a padding byte, then its offsets, all in this instruction
+    let mut data = vec![1u8, 0];
+    for offset in [
+        signature_offset as u16,
+        u16::MAX,
+        public_key_offset as u16,
+        u16::MAX,
+        message_offset as u16,
+        message.len() as u16,
+        u16::MAX,
+    ] {
+        data.extend_from_slice(&offset.to_le_bytes());
+    }
+    data.extend_from_slice(validator.as_ref());
+    data.extend_from_slice(signature.as_ref());
+    data.extend_from_slice(&message);
+
+    Instruction {
+        program_id: ed25519_program::ID,
+        accounts: vec![],
+        data,
+    }
+}
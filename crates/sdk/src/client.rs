@@ -0,0 +1,1342 @@
+use std::rc::Rc;
+
+use anchor_client::anchor_lang::prelude::Pubkey;
+use anchor_client::anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_client::anchor_lang::system_program;
+use anchor_client::anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_client::solana_sdk::account::from_account;
+use anchor_client::solana_sdk::commitment_config::CommitmentConfig;
+use anchor_client::solana_sdk::signature::{Keypair, Signature, Signer};
+use anchor_client::solana_sdk::sysvar::clock::{self, Clock};
+use anchor_client::solana_sdk::sysvar::instructions;
+use anchor_client::{Client, ClientError, Cluster, Program, RequestBuilder};
+use chain::cross_rate::{cross_rate, CrossRate, CrossRateOperation};
+use chain::state::{
+    Authority, Block, EvmBlockHeader, EvmValidatorSet, FcdHistory, FcdRound, FeeConfig, FeeKind,
+    FeeSchedule, FeedEntry, FeedMetadata, FeedRegistry, FirstClassData, RewardVault, StakeConfig,
+    Status, Subscription, ValidatorSet, ValidatorStake, ValueKind, VerifyResult, FEEDS_PER_PAGE,
+};
+use sorted_merkle_tree::HashScheme;
+
+use crate::attestation::{attestation_instruction, Attestation};
+use crate::coder::{encode_hex, U256};
+use crate::errors::{Result, SdkError};
+use crate::evm::evm_signatures_instruction;
+use crate::feed::FeedValue;
+use crate::pda::*;
+
+/// New value for an existing first class data feed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FcdUpdate {
+    pub key: String,
+    pub value: [u8; 32],
+    pub timestamp: u32,
+    /// Half width of the interval around `value` reported by the sources.
+    pub confidence: Option<[u8; 32]>,
+}
+
+/// Token accounts paying fees of a `FeeSchedule` in tokens.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FeeTokenAccounts {
+    /// Token account of the payer the fees are taken from.
+    pub source: Pubkey,
+    /// Token account with the fee config as its authority.
+    pub vault: Pubkey,
+}
+
+/// Reads and writes the accounts of a deployed `chain` program.
+pub struct ChainClient {
+    program: Program,
+    fee_token_accounts: Option<FeeTokenAccounts>,
+}
+
+impl ChainClient {
+    pub fn new(cluster: Cluster, payer: Rc<Keypair>, program_id: Pubkey) -> Self {
+        let client = Client::new_with_options(cluster, payer, CommitmentConfig::confirmed());
+        Self {
+            program: client.program(program_id),
+            fee_token_accounts: None,
+        }
+    }
+
+    /// Pays the fees of verification with these token accounts, for schedules in tokens.
+    pub fn with_fee_token_accounts(mut self, accounts: FeeTokenAccounts) -> Self {
+        self.fee_token_accounts = Some(accounts);
+        self
+    }
+
+    pub fn program(&self) -> &Program {
+        &self.program
+    }
+
+    pub fn program_id(&self) -> Pubkey {
+        self.program.id()
+    }
+
+    pub fn authority(&self) -> Result<Authority> {
+        Ok(self
+            .program
+            .account(authority_address(&self.program_id()))?)
+    }
+
+    pub fn status(&self) -> Result<Status> {
+        Ok(self.program.account(status_address(&self.program_id()))?)
+    }
+
+    pub fn block(&self, block_id: u32) -> Result<Block> {
+        let (address, _) = block_address(&self.program_id(), block_id);
+        Ok(self.program.account(address)?)
+    }
+
+    pub fn fcd(&self, key: &str) -> Result<FirstClassData> {
+        let (address, _) = fcd_address(&self.program_id(), key)?;
+        Ok(self.program.account(address)?)
+    }
+
+    /// The feed of `key` with its decimals, kind and description.
+    pub fn feed(&self, key: &str) -> Result<FeedValue> {
+        FeedValue::from_fcd(&self.fcd(key)?)
+    }
+
+    /// Like `feed`, but fails unless its confidence is within `max_ratio_bps`
+    /// basis points of the value.
+    pub fn confident_feed(&self, key: &str, max_ratio_bps: u32) -> Result<FeedValue> {
+        let feed = self.feed(key)?;
+        feed.check_confidence(max_ratio_bps)?;
+        Ok(feed)
+    }
+
+    /// All `FirstClassData` accounts owned by the program.
+    pub fn fcds(&self) -> Result<Vec<(Pubkey, FirstClassData)>> {
+        Ok(self.program.accounts(vec![])?)
+    }
+
+    /// Like `fcd`, but `None` when the feed was never initialized.
+    pub fn try_fcd(&self, key: &str) -> Result<Option<FirstClassData>> {
+        match self.fcd(key) {
+            Err(SdkError::Client(ClientError::AccountNotFound)) => Ok(None),
+            result => result.map(Some),
+        }
+    }
+
+    /// History of the feed of `key`, `None` when it has none.
+    pub fn fcd_history(&self, key: &str) -> Result<Option<FcdHistory>> {
+        let (fcd, _) = fcd_address(&self.program_id(), key)?;
+        match self
+            .program
+            .account(fcd_history_address(&self.program_id(), &fcd))
+        {
+            Err(ClientError::AccountNotFound) => Ok(None),
+            result => Ok(Some(result?)),
+        }
+    }
+
+    /// Time weighted average of the feed of `key` over the last `window_secs`
+    /// seconds, computed from its history and the cluster clock like `get_twap`.
+    pub fn twap(&self, key: &str, window_secs: u32) -> Result<U256> {
+        let unavailable = || SdkError::TwapUnavailable(key.to_string());
+        let fcd = self.fcd(key)?;
+        let history = self.fcd_history(key)?.ok_or_else(unavailable)?;
+        if window_secs == 0 || fcd.resolved_metadata().kind == ValueKind::Raw as u8 {
+            return Err(unavailable());
+        }
+
+        let clock_account = self
+            .program
+            .rpc()
+            .get_account(&clock::ID)
+            .map_err(ClientError::from)?;
+        let clock: Clock = from_account(&clock_account).ok_or_else(unavailable)?;
+        let now = u32::try_from(clock.unix_timestamp)
+            .unwrap_or(u32::MAX)
+            .max(fcd.timestamp);
+        let twap = history
+            .twap(&fcd, now, window_secs)
+            .ok_or_else(unavailable)?;
+        Ok(U256::from(twap))
+    }
+
+    /// Rate derived from the feeds of `base` and `quote` by `operation`, computed
+    /// like `get_cross_rate` with the older of their timestamps.
+    pub fn cross_rate(
+        &self,
+        base: &str,
+        quote: &str,
+        operation: CrossRateOperation,
+    ) -> Result<CrossRate> {
+        let base = self.fcd(base)?;
+        let quote = self.fcd(quote)?;
+        let base_metadata = base.resolved_metadata();
+        let quote_metadata = quote.resolved_metadata();
+        let unavailable =
+            || SdkError::CrossRateUnavailable(format!("{} and {}", base.key, quote.key));
+        if base_metadata.kind == ValueKind::Raw as u8 || quote_metadata.kind == ValueKind::Raw as u8
+        {
+            return Err(unavailable());
+        }
+
+        let value = cross_rate(
+            operation,
+            &base.value,
+            base_metadata.decimals,
+            &quote.value,
+            quote_metadata.decimals,
+        )
+        .ok_or_else(unavailable)?;
+        Ok(CrossRate {
+            value,
+            timestamp: base.timestamp.min(quote.timestamp),
+        })
+    }
+
+    /// Fee schedule, `None` until `set_fee_schedule`.
+    pub fn fee_config(&self) -> Result<Option<FeeConfig>> {
+        match self.program.account(fee_config_address(&self.program_id())) {
+            Err(ClientError::AccountNotFound) => Ok(None),
+            result => Ok(Some(result?)),
+        }
+    }
+
+    /// Subscription of `consumer`, `None` if it never subscribed.
+    pub fn subscription(&self, consumer: &Pubkey) -> Result<Option<Subscription>> {
+        match self
+            .program
+            .account(subscription_address(&self.program_id(), consumer))
+        {
+            Err(ClientError::AccountNotFound) => Ok(None),
+            result => Ok(Some(result?)),
+        }
+    }
+
+    /// Remaining accounts paying the fee of a `kind` call by the payer: none while
+    /// fees are disabled, otherwise the ones `charge_fee` expects for an exempt
+    /// payer, an active subscription, lamports or the fee token accounts.
+    pub fn fee_accounts(&self, kind: FeeKind) -> Result<Vec<AccountMeta>> {
+        if !self.status()?.fees_enabled {
+            return Ok(vec![]);
+        }
+        let program_id = self.program_id();
+        let payer = self.program.payer();
+        let fee_config = self.fee_config()?.unwrap_or_default();
+
+        let mut accounts = vec![
+            AccountMeta::new(fee_config_address(&program_id), false),
+            AccountMeta::new(payer, true),
+        ];
+        if fee_config.schedule.fee(kind) == 0 || fee_config.is_exempt(&payer) {
+            return Ok(accounts);
+        }
+
+        let now = self.clock()?.unix_timestamp;
+        if let Some(subscription) = self.subscription(&payer)? {
+            if subscription.is_active(now) {
+                accounts.push(AccountMeta::new_readonly(
+                    subscription_address(&program_id, &payer),
+                    false,
+                ));
+                return Ok(accounts);
+            }
+        }
+
+        accounts.extend(self.fee_payment_accounts(&fee_config.schedule)?);
+        Ok(accounts)
+    }
+
+    /// Accounts moving a payment under `schedule` from the payer.
+    fn fee_payment_accounts(&self, schedule: &FeeSchedule) -> Result<Vec<AccountMeta>> {
+        if !schedule.in_tokens() {
+            return Ok(vec![AccountMeta::new_readonly(system_program::ID, false)]);
+        }
+        let token_accounts = self
+            .fee_token_accounts
+            .ok_or_else(|| SdkError::FeeTokenAccountsRequired(schedule.mint.to_string()))?;
+        Ok(vec![
+            AccountMeta::new(token_accounts.source, false),
+            AccountMeta::new(token_accounts.vault, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+        ])
+    }
+
+    fn clock(&self) -> Result<Clock> {
+        let account = self
+            .program
+            .rpc()
+            .get_account(&clock::ID)
+            .map_err(ClientError::from)?;
+        from_account(&account).ok_or_else(|| SdkError::InvalidValue("clock sysvar".to_string()))
+    }
+
+    /// Reward points and owed lamports, `None` until `set_rewards_enabled`.
+    pub fn reward_vault(&self) -> Result<Option<RewardVault>> {
+        match self
+            .program
+            .account(reward_vault_address(&self.program_id()))
+        {
+            Err(ClientError::AccountNotFound) => Ok(None),
+            result => Ok(Some(result?)),
+        }
+    }
+
+    /// The `RewardVault` submissions take as their last remaining account while
+    /// rewards are enabled.
+    fn reward_accounts(&self) -> Result<Vec<AccountMeta>> {
+        Ok(if self.status()?.rewards_enabled {
+            vec![AccountMeta::new(
+                reward_vault_address(&self.program_id()),
+                false,
+            )]
+        } else {
+            vec![]
+        })
+    }
+
+    /// Staking token and slashing rate, `None` until `set_stake_config`.
+    pub fn stake_config(&self) -> Result<Option<StakeConfig>> {
+        match self
+            .program
+            .account(stake_config_address(&self.program_id()))
+        {
+            Err(ClientError::AccountNotFound) => Ok(None),
+            result => Ok(Some(result?)),
+        }
+    }
+
+    /// Stake of `validator`, `None` if it never staked.
+    pub fn validator_stake(&self, validator: &Pubkey) -> Result<Option<ValidatorStake>> {
+        match self
+            .program
+            .account(validator_stake_address(&self.program_id(), validator))
+        {
+            Err(ClientError::AccountNotFound) => Ok(None),
+            result => Ok(Some(result?)),
+        }
+    }
+
+    /// Validators of aggregated feeds, `None` until `set_validators`.
+    pub fn validator_set(&self) -> Result<Option<ValidatorSet>> {
+        match self
+            .program
+            .account(validator_set_address(&self.program_id()))
+        {
+            Err(ClientError::AccountNotFound) => Ok(None),
+            result => Ok(Some(result?)),
+        }
+    }
+
+    /// Ethereum addresses of the sidechain validators, `None` until `set_evm_validators`.
+    pub fn evm_validator_set(&self) -> Result<Option<EvmValidatorSet>> {
+        match self
+            .program
+            .account(evm_validator_set_address(&self.program_id()))
+        {
+            Err(ClientError::AccountNotFound) => Ok(None),
+            result => Ok(Some(result?)),
+        }
+    }
+
+    /// Open round of the aggregated feed of `key`.
+    pub fn fcd_round(&self, key: &str) -> Result<FcdRound> {
+        let (fcd, _) = fcd_address(&self.program_id(), key)?;
+        Ok(self
+            .program
+            .account(fcd_round_address(&self.program_id(), &fcd))?)
+    }
+
+    /// Page `page` of the feed registry, `None` past the last page.
+    pub fn feed_registry(&self, page: u32) -> Result<Option<FeedRegistry>> {
+        match self
+            .program
+            .account(feed_registry_address(&self.program_id(), page))
+        {
+            Err(ClientError::AccountNotFound) => Ok(None),
+            result => Ok(Some(result?)),
+        }
+    }
+
+    /// All registered feeds, read page by page from the feed registry.
+    pub fn feeds(&self) -> Result<Vec<FeedEntry>> {
+        let mut feeds = vec![];
+        for page in 0.. {
+            match self.feed_registry(page)? {
+                Some(registry) => feeds.extend(registry.entries),
+                None => break,
+            }
+        }
+        Ok(feeds)
+    }
+
+    /// A request creating the registry page with room for a feed if needed, and that page.
+    fn with_registry_page(&self) -> Result<(RequestBuilder<'_>, Pubkey)> {
+        let program_id = self.program_id();
+        let (page, page_exists) = self.registry_page_with_room()?;
+        let registry = feed_registry_address(&program_id, page);
+
+        let mut request = self.program.request();
+        if !page_exists {
+            request = request.instruction(self.instruction(
+                chain::accounts::InitializeFeedRegistryPage {
+                    owner: self.program.payer(),
+                    authority: authority_address(&program_id),
+                    registry,
+                    system_program: system_program::ID,
+                },
+                chain::instruction::InitializeFeedRegistryPage { page },
+            ));
+        }
+        Ok((request, registry))
+    }
+
+    /// First registry page with room for a feed, and whether it still has to be created.
+    fn registry_page_with_room(&self) -> Result<(u32, bool)> {
+        for page in 0.. {
+            match self.feed_registry(page)? {
+                Some(registry) if registry.entries.len() < FEEDS_PER_PAGE => {
+                    return Ok((page, true))
+                }
+                Some(_) => continue,
+                None => return Ok((page, false)),
+            }
+        }
+        unreachable!("registry pages are indexed by u32")
+    }
+
+    /// Registry page listing `key`.
+    fn registry_page_of(&self, key: &str) -> Result<u32> {
+        for page in 0.. {
+            match self.feed_registry(page)? {
+                Some(registry) if registry.position(key).is_some() => return Ok(page),
+                Some(_) => continue,
+                None => break,
+            }
+        }
+        Err(SdkError::FeedNotRegistered(key.to_string()))
+    }
+
+    pub fn submit(&self, block_id: u32, root: [u8; 32], timestamp: u32) -> Result<Signature> {
+        let program_id = self.program_id();
+        let (block, seed) = block_address(&program_id, block_id);
+        Ok(self
+            .program
+            .request()
+            .accounts(chain::accounts::Submit {
+                owner: self.program.payer(),
+                authority: authority_address(&program_id),
+                block,
+                status: status_address(&program_id),
+                system_program: system_program::ID,
+            })
+            .accounts(self.reward_accounts()?)
+            .args(chain::instruction::Submit {
+                seed,
+                block_id,
+                root,
+                timestamp,
+            })
+            .send()?)
+    }
+
+    /// Submits a block whose root was built with `hash_scheme` instead of the legacy one.
+    pub fn submit_with_hash_scheme(
+        &self,
+        block_id: u32,
+        root: [u8; 32],
+        timestamp: u32,
+        hash_scheme: HashScheme,
+    ) -> Result<Signature> {
+        let program_id = self.program_id();
+        let (block, seed) = block_address(&program_id, block_id);
+        Ok(self
+            .program
+            .request()
+            .accounts(chain::accounts::Submit {
+                owner: self.program.payer(),
+                authority: authority_address(&program_id),
+                block,
+                status: status_address(&program_id),
+                system_program: system_program::ID,
+            })
+            .accounts(self.reward_accounts()?)
+            .args(chain::instruction::SubmitWithHashScheme {
+                seed,
+                block_id,
+                root,
+                timestamp,
+                hash_scheme: hash_scheme as u8,
+            })
+            .send()?)
+    }
+
+    /// Initializes the feed with Umbrella metadata and lists it on a registry
+    /// page with room, creating the next page when all are full.
+    pub fn initialize_first_class_data(
+        &self,
+        key: &str,
+        value: [u8; 32],
+        timestamp: u32,
+    ) -> Result<Signature> {
+        let program_id = self.program_id();
+        let (fcd, seed) = fcd_address(&program_id, key)?;
+        let (request, registry) = self.with_registry_page()?;
+
+        Ok(request
+            .instruction(self.instruction(
+                self.initialize_fcd_accounts(fcd, registry),
+                chain::instruction::InitializeFirstClassData {
+                    seed,
+                    key: key.to_string(),
+                    value,
+                    timestamp,
+                },
+            ))
+            .send()?)
+    }
+
+    /// Like `initialize_first_class_data`, with the feed's decimals, kind and description.
+    pub fn initialize_first_class_data_with_metadata(
+        &self,
+        key: &str,
+        value: [u8; 32],
+        timestamp: u32,
+        decimals: u8,
+        kind: ValueKind,
+        description: &str,
+    ) -> Result<Signature> {
+        let program_id = self.program_id();
+        let (fcd, seed) = fcd_address(&program_id, key)?;
+        let (request, registry) = self.with_registry_page()?;
+
+        Ok(request
+            .instruction(self.instruction(
+                self.initialize_fcd_accounts(fcd, registry),
+                chain::instruction::InitializeFirstClassDataWithMetadata {
+                    seed,
+                    key: key.to_string(),
+                    value,
+                    timestamp,
+                    metadata: FeedMetadata {
+                        decimals,
+                        kind: kind as u8,
+                        description: description.to_string(),
+                    },
+                },
+            ))
+            .send()?)
+    }
+
+    fn initialize_fcd_accounts(
+        &self,
+        fcd: Pubkey,
+        registry: Pubkey,
+    ) -> chain::accounts::InitializeFirstClassData {
+        let program_id = self.program_id();
+        chain::accounts::InitializeFirstClassData {
+            owner: self.program.payer(),
+            authority: authority_address(&program_id),
+            fcd,
+            closed: closed_fcd_address(&program_id, &fcd),
+            registry,
+            system_program: system_program::ID,
+        }
+    }
+
+    /// Lists a feed initialized before the registry existed.
+    pub fn register_first_class_data(&self, key: &str) -> Result<Signature> {
+        let program_id = self.program_id();
+        let (fcd, _) = fcd_address(&program_id, key)?;
+        let (request, registry) = self.with_registry_page()?;
+
+        Ok(request
+            .instruction(self.instruction(
+                chain::accounts::RegisterFirstClassData {
+                    owner: self.program.payer(),
+                    authority: authority_address(&program_id),
+                    fcd,
+                    registry,
+                },
+                chain::instruction::RegisterFirstClassData {
+                    key: key.to_string(),
+                },
+            ))
+            .send()?)
+    }
+
+    /// Sets the decimals, kind and description of a registered feed.
+    pub fn set_first_class_data_metadata(
+        &self,
+        key: &str,
+        decimals: u8,
+        kind: ValueKind,
+        description: &str,
+    ) -> Result<Signature> {
+        let program_id = self.program_id();
+        let (fcd, _) = fcd_address(&program_id, key)?;
+        Ok(self
+            .program
+            .request()
+            .accounts(chain::accounts::SetFirstClassDataMetadata {
+                owner: self.program.payer(),
+                authority: authority_address(&program_id),
+                fcd,
+                registry: feed_registry_address(&program_id, self.registry_page_of(key)?),
+            })
+            .args(chain::instruction::SetFirstClassDataMetadata {
+                key: key.to_string(),
+                metadata: FeedMetadata {
+                    decimals,
+                    kind: kind as u8,
+                    description: description.to_string(),
+                },
+            })
+            .send()?)
+    }
+
+    /// Replaces the validators of aggregated feeds, `quorum` of which have to
+    /// submit within `window` seconds to finalize a round.
+    pub fn set_validators(
+        &self,
+        validators: Vec<Pubkey>,
+        quorum: u8,
+        window: u32,
+    ) -> Result<Signature> {
+        let program_id = self.program_id();
+        Ok(self
+            .program
+            .request()
+            .accounts(chain::accounts::SetValidators {
+                owner: self.program.payer(),
+                authority: authority_address(&program_id),
+                validator_set: validator_set_address(&program_id),
+                system_program: system_program::ID,
+            })
+            .args(chain::instruction::SetValidators {
+                validators,
+                quorum,
+                window,
+            })
+            .send()?)
+    }
+
+    /// Switches the feed of `key` to the median of validator observations.
+    pub fn initialize_fcd_aggregation(&self, key: &str) -> Result<Signature> {
+        let program_id = self.program_id();
+        let (fcd, _) = fcd_address(&program_id, key)?;
+        Ok(self
+            .program
+            .request()
+            .accounts(chain::accounts::InitializeFcdAggregation {
+                owner: self.program.payer(),
+                authority: authority_address(&program_id),
+                fcd,
+                round: fcd_round_address(&program_id, &fcd),
+                system_program: system_program::ID,
+            })
+            .args(chain::instruction::InitializeFcdAggregation {
+                key: key.to_string(),
+            })
+            .send()?)
+    }
+
+    /// Submits the payer's observation of an aggregated feed as a validator.
+    pub fn submit_fcd_observation(
+        &self,
+        key: &str,
+        value: [u8; 32],
+        timestamp: u32,
+    ) -> Result<Signature> {
+        let program_id = self.program_id();
+        let (fcd, _) = fcd_address(&program_id, key)?;
+        Ok(self
+            .program
+            .request()
+            .accounts(chain::accounts::SubmitFcdObservation {
+                validator: self.program.payer(),
+                validator_set: validator_set_address(&program_id),
+                status: status_address(&program_id),
+                fcd,
+                round: fcd_round_address(&program_id, &fcd),
+            })
+            // appended to when the submission finalizes the round
+            .accounts(AccountMeta::new(
+                fcd_history_address(&program_id, &fcd),
+                false,
+            ))
+            .accounts(self.reward_accounts()?)
+            .args(chain::instruction::SubmitFcdObservation {
+                key: key.to_string(),
+                value,
+                timestamp,
+            })
+            .send()?)
+    }
+
+    /// Starts recording the last `capacity` values of the feed of `key`.
+    pub fn initialize_fcd_history(&self, key: &str, capacity: u16) -> Result<Signature> {
+        let program_id = self.program_id();
+        let (fcd, _) = fcd_address(&program_id, key)?;
+        Ok(self
+            .program
+            .request()
+            .accounts(chain::accounts::InitializeFcdHistory {
+                owner: self.program.payer(),
+                authority: authority_address(&program_id),
+                fcd,
+                history: fcd_history_address(&program_id, &fcd),
+                system_program: system_program::ID,
+            })
+            .args(chain::instruction::InitializeFcdHistory {
+                key: key.to_string(),
+                capacity,
+            })
+            .send()?)
+    }
+
+    /// Closes the account of `key`, with its history and aggregation round, sending
+    /// their rent to `recipient`. The key can't be initialized again for
+    /// `FCD_REUSE_COOLDOWN` seconds.
+    pub fn close_first_class_data(&self, key: &str, recipient: Pubkey) -> Result<Signature> {
+        let program_id = self.program_id();
+        let (fcd, _) = fcd_address(&program_id, key)?;
+        // the program expects page 0 for feeds on no page
+        let registry_page = self.fcd(key)?.registry_page.unwrap_or_default();
+        Ok(self
+            .program
+            .request()
+            .accounts(chain::accounts::CloseFirstClassData {
+                owner: self.program.payer(),
+                authority: authority_address(&program_id),
+                fcd,
+                closed: closed_fcd_address(&program_id, &fcd),
+                registry: feed_registry_address(&program_id, registry_page),
+                round: fcd_round_address(&program_id, &fcd),
+                recipient,
+                system_program: system_program::ID,
+            })
+            .accounts(AccountMeta::new(
+                fcd_history_address(&program_id, &fcd),
+                false,
+            ))
+            .args(chain::instruction::CloseFirstClassData {
+                key: key.to_string(),
+            })
+            .send()?)
+    }
+
+    /// Updates several feeds in a single transaction, appending to their
+    /// histories where they have one.
+    pub fn update_first_class_data(&self, updates: &[FcdUpdate]) -> Result<Signature> {
+        let program_id = self.program_id();
+        let reward_accounts = self.reward_accounts()?;
+        let mut request = self.program.request();
+
+        for update in updates {
+            let (fcd, _) = fcd_address(&program_id, &update.key)?;
+            let accounts = chain::accounts::UpdateFirstClassData {
+                owner: self.program.payer(),
+                authority: authority_address(&program_id),
+                fcd,
+                status: status_address(&program_id),
+                system_program: system_program::ID,
+            };
+            let mut instruction = match update.confidence {
+                Some(confidence) => self.instruction(
+                    accounts,
+                    chain::instruction::UpdateFirstClassDataWithConfidence {
+                        key: update.key.clone(),
+                        value: update.value,
+                        timestamp: update.timestamp,
+                        confidence,
+                    },
+                ),
+                None => self.instruction(
+                    accounts,
+                    chain::instruction::UpdateFirstClassData {
+                        key: update.key.clone(),
+                        value: update.value,
+                        timestamp: update.timestamp,
+                    },
+                ),
+            };
+            // ignored by the program for feeds without a history
+            instruction.accounts.push(AccountMeta::new(
+                fcd_history_address(&program_id, &fcd),
+                false,
+            ));
+            instruction.accounts.extend(reward_accounts.iter().cloned());
+            request = request.instruction(instruction);
+        }
+
+        Ok(request.send()?)
+    }
+
+    pub fn set_padding(&self, padding: u32) -> Result<Signature> {
+        let program_id = self.program_id();
+        Ok(self
+            .program
+            .request()
+            .accounts(chain::accounts::SetPadding {
+                owner: self.program.payer(),
+                authority: authority_address(&program_id),
+                status: status_address(&program_id),
+                system_program: system_program::ID,
+            })
+            .args(chain::instruction::SetPadding { padding })
+            .send()?)
+    }
+
+    /// Sets the longest proof `verify_proof_for_block` accepts, 0 for the default.
+    pub fn set_max_proof_length(&self, max_proof_length: u8) -> Result<Signature> {
+        let program_id = self.program_id();
+        Ok(self
+            .program
+            .request()
+            .accounts(chain::accounts::SetMaxProofLength {
+                owner: self.program.payer(),
+                authority: authority_address(&program_id),
+                status: status_address(&program_id),
+                system_program: system_program::ID,
+            })
+            .args(chain::instruction::SetMaxProofLength { max_proof_length })
+            .send()?)
+    }
+
+    /// Lets `guardian` pause and unpause besides the owner, `Pubkey::default()` for none.
+    pub fn set_guardian(&self, guardian: Pubkey) -> Result<Signature> {
+        Ok(self
+            .program
+            .request()
+            .accounts(chain::accounts::SetGuardian {
+                owner: self.program.payer(),
+                authority: authority_address(&self.program_id()),
+                system_program: system_program::ID,
+            })
+            .args(chain::instruction::SetGuardian { guardian })
+            .send()?)
+    }
+
+    /// Sets the `PAUSE_*` flags in `flags`, signed by the owner or guardian.
+    pub fn pause(&self, flags: u8) -> Result<Signature> {
+        Ok(self
+            .program
+            .request()
+            .accounts(self.pause_accounts())
+            .args(chain::instruction::Pause { flags })
+            .send()?)
+    }
+
+    /// Clears the `PAUSE_*` flags in `flags`, signed by the owner or guardian.
+    pub fn unpause(&self, flags: u8) -> Result<Signature> {
+        Ok(self
+            .program
+            .request()
+            .accounts(self.pause_accounts())
+            .args(chain::instruction::Unpause { flags })
+            .send()?)
+    }
+
+    fn pause_accounts(&self) -> chain::accounts::Pause {
+        let program_id = self.program_id();
+        chain::accounts::Pause {
+            pauser: self.program.payer(),
+            authority: authority_address(&program_id),
+            status: status_address(&program_id),
+        }
+    }
+
+    /// Upgrades `Authority` and `Status` to the current layout, paying the extra rent.
+    pub fn migrate(&self) -> Result<Signature> {
+        let program_id = self.program_id();
+        Ok(self
+            .program
+            .request()
+            .accounts(chain::accounts::Migrate {
+                owner: self.program.payer(),
+                authority: authority_address(&program_id),
+                status: status_address(&program_id),
+                system_program: system_program::ID,
+            })
+            .args(chain::instruction::Migrate {})
+            .send()?)
+    }
+
+    pub fn transfer_ownership(&self, new_owner: &Keypair) -> Result<Signature> {
+        Ok(self
+            .program
+            .request()
+            .accounts(chain::accounts::TransferOwnership {
+                owner: self.program.payer(),
+                authority: authority_address(&self.program_id()),
+                new_owner: new_owner.pubkey(),
+                system_program: system_program::ID,
+            })
+            .args(chain::instruction::TransferOwnership {})
+            .signer(new_owner)
+            .send()?)
+    }
+
+    /// Sets the fees of verification and reads, charged while `enabled`, and the
+    /// keys calling for free.
+    pub fn set_fee_schedule(
+        &self,
+        schedule: FeeSchedule,
+        exempt: Vec<Pubkey>,
+        enabled: bool,
+    ) -> Result<Signature> {
+        let program_id = self.program_id();
+        Ok(self
+            .program
+            .request()
+            .accounts(chain::accounts::SetFeeSchedule {
+                owner: self.program.payer(),
+                authority: authority_address(&program_id),
+                fee_config: fee_config_address(&program_id),
+                status: status_address(&program_id),
+                system_program: system_program::ID,
+            })
+            .args(chain::instruction::SetFeeSchedule {
+                schedule,
+                exempt,
+                enabled,
+            })
+            .send()?)
+    }
+
+    /// Extends the subscription of `consumer` by `periods` subscription periods,
+    /// paid by the payer.
+    pub fn subscribe(&self, consumer: &Pubkey, periods: u32) -> Result<Signature> {
+        let program_id = self.program_id();
+        let fee_config = self.fee_config()?.unwrap_or_default();
+        let payment_accounts = if fee_config.schedule.in_tokens() {
+            self.fee_payment_accounts(&fee_config.schedule)?
+        } else {
+            vec![]
+        };
+
+        Ok(self
+            .program
+            .request()
+            .instruction(self.instruction_with(
+                chain::accounts::Subscribe {
+                    payer: self.program.payer(),
+                    consumer: *consumer,
+                    fee_config: fee_config_address(&program_id),
+                    subscription: subscription_address(&program_id, consumer),
+                    system_program: system_program::ID,
+                },
+                chain::instruction::Subscribe { periods },
+                payment_accounts,
+            ))
+            .send()?)
+    }
+
+    /// Sends `amount` of the collected fees to `recipient`, a token account of the
+    /// fee mint taking it out of `vault` for schedules in tokens.
+    pub fn withdraw_fees(
+        &self,
+        amount: u64,
+        recipient: &Pubkey,
+        vault: Option<&Pubkey>,
+    ) -> Result<Signature> {
+        let program_id = self.program_id();
+        let remaining_accounts = match vault {
+            Some(vault) => vec![
+                AccountMeta::new(*vault, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+            None => vec![],
+        };
+
+        Ok(self
+            .program
+            .request()
+            .instruction(self.instruction_with(
+                chain::accounts::WithdrawFees {
+                    owner: self.program.payer(),
+                    authority: authority_address(&program_id),
+                    fee_config: fee_config_address(&program_id),
+                    recipient: *recipient,
+                },
+                chain::instruction::WithdrawFees { amount },
+                remaining_accounts,
+            ))
+            .send()?)
+    }
+
+    /// Starts or stops crediting reward points to the keys submitting data.
+    pub fn set_rewards_enabled(&self, enabled: bool) -> Result<Signature> {
+        let program_id = self.program_id();
+        Ok(self
+            .program
+            .request()
+            .accounts(chain::accounts::SetRewardsEnabled {
+                owner: self.program.payer(),
+                authority: authority_address(&program_id),
+                reward_vault: reward_vault_address(&program_id),
+                status: status_address(&program_id),
+                system_program: system_program::ID,
+            })
+            .args(chain::instruction::SetRewardsEnabled { enabled })
+            .send()?)
+    }
+
+    /// Shares `amount` of the collected lamport fees among the keys with reward points.
+    pub fn distribute_rewards(&self, amount: u64) -> Result<Signature> {
+        let program_id = self.program_id();
+        Ok(self
+            .program
+            .request()
+            .accounts(chain::accounts::DistributeRewards {
+                owner: self.program.payer(),
+                authority: authority_address(&program_id),
+                fee_config: fee_config_address(&program_id),
+                reward_vault: reward_vault_address(&program_id),
+            })
+            .args(chain::instruction::DistributeRewards { amount })
+            .send()?)
+    }
+
+    /// Sends the payer the rewards distributed to it.
+    pub fn claim_rewards(&self) -> Result<Signature> {
+        Ok(self
+            .program
+            .request()
+            .accounts(chain::accounts::ClaimRewards {
+                claimant: self.program.payer(),
+                reward_vault: reward_vault_address(&self.program_id()),
+            })
+            .args(chain::instruction::ClaimRewards {})
+            .send()?)
+    }
+
+    /// Holds validator stakes in `vault`, a token account with the stake config as
+    /// its authority, and sends slashed ones to `treasury`.
+    pub fn set_stake_config(
+        &self,
+        vault: &Pubkey,
+        treasury: &Pubkey,
+        slash_bps: u16,
+        unbonding_period: u32,
+    ) -> Result<Signature> {
+        let program_id = self.program_id();
+        Ok(self
+            .program
+            .request()
+            .accounts(chain::accounts::SetStakeConfig {
+                owner: self.program.payer(),
+                authority: authority_address(&program_id),
+                stake_config: stake_config_address(&program_id),
+                vault: *vault,
+                treasury: *treasury,
+                system_program: system_program::ID,
+            })
+            .args(chain::instruction::SetStakeConfig {
+                slash_bps,
+                unbonding_period,
+            })
+            .send()?)
+    }
+
+    /// Stakes `amount` tokens of the payer, a registered validator, from `source`.
+    pub fn stake(&self, source: &Pubkey, amount: u64) -> Result<Signature> {
+        let program_id = self.program_id();
+        let validator = self.program.payer();
+        let stake_config: StakeConfig = self.program.account(stake_config_address(&program_id))?;
+        Ok(self
+            .program
+            .request()
+            .accounts(chain::accounts::Stake {
+                validator,
+                validator_set: validator_set_address(&program_id),
+                stake_config: stake_config_address(&program_id),
+                validator_stake: validator_stake_address(&program_id, &validator),
+                source: *source,
+                vault: stake_config.vault,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            })
+            .args(chain::instruction::Stake { amount })
+            .send()?)
+    }
+
+    /// Starts unbonding `amount` tokens staked by the payer.
+    pub fn request_unstake(&self, amount: u64) -> Result<Signature> {
+        let program_id = self.program_id();
+        let validator = self.program.payer();
+        Ok(self
+            .program
+            .request()
+            .accounts(chain::accounts::RequestUnstake {
+                validator,
+                stake_config: stake_config_address(&program_id),
+                validator_stake: validator_stake_address(&program_id, &validator),
+            })
+            .args(chain::instruction::RequestUnstake { amount })
+            .send()?)
+    }
+
+    /// Sends the unbonded stake of the payer to its `destination` token account.
+    pub fn withdraw_stake(&self, destination: &Pubkey) -> Result<Signature> {
+        let program_id = self.program_id();
+        let validator = self.program.payer();
+        let stake_config: StakeConfig = self.program.account(stake_config_address(&program_id))?;
+        Ok(self
+            .program
+            .request()
+            .accounts(chain::accounts::WithdrawStake {
+                validator,
+                stake_config: stake_config_address(&program_id),
+                validator_stake: validator_stake_address(&program_id, &validator),
+                vault: stake_config.vault,
+                destination: *destination,
+                token_program: spl_token::ID,
+            })
+            .args(chain::instruction::WithdrawStake {})
+            .send()?)
+    }
+
+    /// Slashes `validator` for attesting two different roots of `block_id`, sending
+    /// the signatures to the ed25519 program ahead of the report.
+    pub fn report_equivocation(
+        &self,
+        validator: &Pubkey,
+        block_id: u32,
+        first: &Attestation,
+        second: &Attestation,
+    ) -> Result<Signature> {
+        let program_id = self.program_id();
+        let stake_config: StakeConfig = self.program.account(stake_config_address(&program_id))?;
+        Ok(self
+            .program
+            .request()
+            .instruction(attestation_instruction(
+                validator,
+                block_id,
+                &first.root,
+                &first.signature,
+            ))
+            .instruction(attestation_instruction(
+                validator,
+                block_id,
+                &second.root,
+                &second.signature,
+            ))
+            .instruction(self.instruction(
+                chain::accounts::ReportEquivocation {
+                    reporter: self.program.payer(),
+                    stake_config: stake_config_address(&program_id),
+                    validator_stake: validator_stake_address(&program_id, validator),
+                    slashed_block: slashed_block_address(&program_id, validator, block_id),
+                    block: block_address(&program_id, block_id).0,
+                    vault: stake_config.vault,
+                    treasury: stake_config.treasury,
+                    token_program: spl_token::ID,
+                    instructions: instructions::ID,
+                    system_program: system_program::ID,
+                },
+                chain::instruction::ReportEquivocation {
+                    validator: *validator,
+                    block_id,
+                    first_root: first.root,
+                    second_root: second.root,
+                },
+            ))
+            .send()?)
+    }
+
+    /// Replaces the Ethereum addresses of the sidechain validators and the number of
+    /// their signatures needed to import a block.
+    pub fn set_evm_validators(&self, validators: Vec<[u8; 20]>, quorum: u8) -> Result<Signature> {
+        let program_id = self.program_id();
+        Ok(self
+            .program
+            .request()
+            .accounts(chain::accounts::SetEvmValidators {
+                owner: self.program.payer(),
+                authority: authority_address(&program_id),
+                evm_validator_set: evm_validator_set_address(&program_id),
+                system_program: system_program::ID,
+            })
+            .args(chain::instruction::SetEvmValidators { validators, quorum })
+            .send()?)
+    }
+
+    /// Imports the block of an ABI-encoded sidechain header with the `eth_sign`
+    /// signatures of its validators, checked by the secp256k1 program.
+    pub fn import_evm_block(&self, payload: &[u8], signatures: &[[u8; 65]]) -> Result<Signature> {
+        let program_id = self.program_id();
+        let header = EvmBlockHeader::decode(payload)
+            .ok_or_else(|| SdkError::InvalidValue(encode_hex(payload)))?;
+        let (block, seed) = block_address(&program_id, header.block_id);
+
+        Ok(self
+            .program
+            .request()
+            .instruction(evm_signatures_instruction(0, payload, signatures)?)
+            .instruction(self.instruction(
+                chain::accounts::ImportEvmBlock {
+                    importer: self.program.payer(),
+                    evm_validator_set: evm_validator_set_address(&program_id),
+                    block,
+                    status: status_address(&program_id),
+                    instructions: instructions::ID,
+                    system_program: system_program::ID,
+                },
+                chain::instruction::ImportEvmBlock {
+                    seed,
+                    payload: payload.to_vec(),
+                },
+            ))
+            .send()?)
+    }
+
+    /// Runs `verify_proof_for_block` on chain and returns the stored result.
+    pub fn verify_proof_for_block(
+        &self,
+        block_id: u32,
+        proof: Vec<[u8; 32]>,
+        key: [u8; 32],
+        value: [u8; 32],
+    ) -> Result<bool> {
+        let (block, seed) = block_address(&self.program_id(), block_id);
+        let verify_result = Keypair::new();
+
+        self.program
+            .request()
+            .instruction(self.instruction(
+                chain::accounts::InitializeVerifyResult {
+                    verify_result: verify_result.pubkey(),
+                    user: self.program.payer(),
+                    system_program: system_program::ID,
+                },
+                chain::instruction::InitializeVerifyResult {},
+            ))
+            .instruction(self.instruction_with(
+                chain::accounts::Verify {
+                    block,
+                    verify_result: verify_result.pubkey(),
+                    status: status_address(&self.program_id()),
+                },
+                chain::instruction::VerifyProofForBlock {
+                    seed,
+                    proof,
+                    key,
+                    value,
+                },
+                self.fee_accounts(FeeKind::Verify)?,
+            ))
+            .signer(&verify_result)
+            .send()?;
+
+        let result: VerifyResult = self.program.account(verify_result.pubkey())?;
+        Ok(result.result)
+    }
+
+    /// Runs `verify_message_inclusion` on chain and returns the stored result.
+    pub fn verify_message_inclusion(
+        &self,
+        block_id: u32,
+        message: Vec<u8>,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<bool> {
+        let (block, seed) = block_address(&self.program_id(), block_id);
+        let verify_result = Keypair::new();
+
+        self.program
+            .request()
+            .instruction(self.instruction(
+                chain::accounts::InitializeVerifyResult {
+                    verify_result: verify_result.pubkey(),
+                    user: self.program.payer(),
+                    system_program: system_program::ID,
+                },
+                chain::instruction::InitializeVerifyResult {},
+            ))
+            .instruction(self.instruction_with(
+                chain::accounts::Verify {
+                    block,
+                    verify_result: verify_result.pubkey(),
+                    status: status_address(&self.program_id()),
+                },
+                chain::instruction::VerifyMessageInclusion {
+                    seed,
+                    message,
+                    proof,
+                },
+                self.fee_accounts(FeeKind::Verify)?,
+            ))
+            .signer(&verify_result)
+            .send()?;
+
+        let result: VerifyResult = self.program.account(verify_result.pubkey())?;
+        Ok(result.result)
+    }
+
+    /// Runs `verify_against_latest` on chain and returns the stored result.
+    ///
+    /// The latest block is read from `Status` first, so the request fails with
+    /// `ConstraintSeeds` if another block is submitted in between.
+    pub fn verify_against_latest(
+        &self,
+        proof: Vec<[u8; 32]>,
+        key: [u8; 32],
+        value: [u8; 32],
+        max_age: u32,
+    ) -> Result<bool> {
+        let (block, _) = block_address(&self.program_id(), self.status()?.last_id);
+        let verify_result = Keypair::new();
+
+        self.program
+            .request()
+            .instruction(self.instruction(
+                chain::accounts::InitializeVerifyResult {
+                    verify_result: verify_result.pubkey(),
+                    user: self.program.payer(),
+                    system_program: system_program::ID,
+                },
+                chain::instruction::InitializeVerifyResult {},
+            ))
+            .instruction(self.instruction_with(
+                chain::accounts::VerifyAgainstLatest {
+                    status: status_address(&self.program_id()),
+                    block,
+                    verify_result: verify_result.pubkey(),
+                },
+                chain::instruction::VerifyAgainstLatest {
+                    proof,
+                    key,
+                    value,
+                    max_age,
+                },
+                self.fee_accounts(FeeKind::Verify)?,
+            ))
+            .signer(&verify_result)
+            .send()?;
+
+        let result: VerifyResult = self.program.account(verify_result.pubkey())?;
+        Ok(result.result)
+    }
+
+    /// Builds a `chain` instruction, for requests bundling several of them.
+    pub fn instruction(
+        &self,
+        accounts: impl ToAccountMetas,
+        args: impl InstructionData,
+    ) -> Instruction {
+        Instruction {
+            program_id: self.program_id(),
+            accounts: accounts.to_account_metas(None),
+            data: args.data(),
+        }
+    }
+
+    /// Builds a `chain` instruction followed by `remaining_accounts`.
+    fn instruction_with(
+        &self,
+        accounts: impl ToAccountMetas,
+        args: impl InstructionData,
+        remaining_accounts: Vec<AccountMeta>,
+    ) -> Instruction {
+        let mut instruction = self.instruction(accounts, args);
+        instruction.accounts.extend(remaining_accounts);
+        instruction
+    }
+}
@@ -0,0 +1,107 @@
+use crate::errors::{Result, SdkError};
+
+pub use u256::U256;
+pub(crate) use u256::U512;
+
+#[allow(clippy::assign_op_pattern, clippy::manual_div_ceil)]
+mod u256 {
+    uint::construct_uint! {
+        pub struct U256(4);
+    }
+
+    // room for products of two `U256`s
+    uint::construct_uint! {
+        pub struct U512(8);
+    }
+}
+
+/// Decimals used by Umbrella for numeric values.
+pub const DECIMALS: usize = 18;
+
+/// Keys with this prefix carry raw 32 byte values instead of numbers.
+pub const FIXED_PREFIX: &str = "FIXED_";
+
+pub fn is_fixed_key(key: &str) -> bool {
+    key.starts_with(FIXED_PREFIX)
+}
+
+/// Encodes a value the same way as `LeafValueCoder.encode` for unsigned
+/// numbers and `FIXED_` keys: hex input is taken as is, anything else is
+/// parsed as a decimal number with 18 decimals.
+pub fn encode_value(value: &str, key: &str) -> Result<[u8; 32]> {
+    if is_fixed_key(key) || value.starts_with("0x") {
+        return decode_hex(value);
+    }
+
+    let invalid = || SdkError::InvalidValue(value.to_string());
+    let (integer, fraction) = value.split_once('.').unwrap_or((value, ""));
+    if fraction.len() > DECIMALS {
+        return Err(invalid());
+    }
+
+    let digits = format!("{}{:0<width$}", integer, fraction, width = DECIMALS);
+    let number = U256::from_dec_str(&digits).map_err(|_| invalid())?;
+    Ok(to_bytes(number))
+}
+
+/// Decodes a value written by `encode_value`, trimming trailing zeros.
+pub fn decode_value(value: &[u8; 32], key: &str) -> String {
+    if is_fixed_key(key) {
+        return encode_hex(value);
+    }
+
+    format_decimal(U256::from_big_endian(value), DECIMALS)
+}
+
+/// Formats `number` as a fixed point number with `decimals` decimals,
+/// trimming trailing zeros.
+pub fn format_decimal(number: U256, decimals: usize) -> String {
+    // U256 ignores width in its Display impl, so the padding is done on the string
+    let digits = format!("{:0>width$}", number.to_string(), width = decimals + 1);
+    let (integer, fraction) = digits.split_at(digits.len() - decimals);
+    let fraction = fraction.trim_end_matches('0');
+
+    if fraction.is_empty() {
+        integer.to_string()
+    } else {
+        format!("{}.{}", integer, fraction)
+    }
+}
+
+pub fn decode_hex(value: &str) -> Result<[u8; 32]> {
+    decode_hex_array(value)
+}
+
+/// Decodes `0x` prefixed or bare hex of exactly `N` bytes.
+pub fn decode_hex_array<const N: usize>(value: &str) -> Result<[u8; N]> {
+    let bytes = decode_hex_bytes(value)?;
+    bytes
+        .try_into()
+        .map_err(|_| SdkError::InvalidValue(value.to_string()))
+}
+
+/// Decodes `0x` prefixed or bare hex of any length.
+pub fn decode_hex_bytes(value: &str) -> Result<Vec<u8>> {
+    let digits = value.trim_start_matches("0x");
+    if digits.len() % 2 == 1 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(SdkError::InvalidValue(value.to_string()));
+    }
+
+    (0..digits.len() / 2)
+        .map(|i| {
+            u8::from_str_radix(&digits[2 * i..2 * i + 2], 16)
+                .map_err(|_| SdkError::InvalidValue(value.to_string()))
+        })
+        .collect()
+}
+
+pub fn encode_hex(bytes: &[u8]) -> String {
+    let digits: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("0x{}", digits)
+}
+
+pub(crate) fn to_bytes(number: U256) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    number.to_big_endian(&mut bytes);
+    bytes
+}
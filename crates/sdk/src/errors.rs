@@ -0,0 +1,29 @@
+use anchor_client::ClientError;
+use sorted_merkle_tree::TreeError;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum SdkError {
+    #[error(transparent)]
+    Client(#[from] ClientError),
+    #[error(transparent)]
+    Tree(#[from] TreeError),
+    #[error("invalid value {0:?}")]
+    InvalidValue(String),
+    #[error("feed {0:?} is not in the feed registry")]
+    FeedNotRegistered(String),
+    #[error("no time weighted average of {0:?} over the window")]
+    TwapUnavailable(String),
+    #[error("fees are paid in tokens of {0}, set the token accounts paying them")]
+    FeeTokenAccountsRequired(String),
+    #[error("no cross rate of {0:?}")]
+    CrossRateUnavailable(String),
+    #[error("feed {0:?} reports no confidence")]
+    NoConfidence(String),
+    #[error("confidence of {key:?} is wider than {max_ratio_bps} basis points of its value")]
+    ConfidenceTooWide { key: String, max_ratio_bps: u32 },
+    #[error("unknown value kind {0}")]
+    UnknownValueKind(u8),
+}
+
+pub type Result<T> = std::result::Result<T, SdkError>;
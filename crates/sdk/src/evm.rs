@@ -0,0 +1,72 @@
+use anchor_client::anchor_lang::solana_program::instruction::Instruction;
+use anchor_client::anchor_lang::solana_program::keccak;
+use anchor_client::anchor_lang::solana_program::secp256k1_recover::secp256k1_recover;
+use anchor_client::solana_sdk::secp256k1_program;
+use chain::state::evm_signed_message;
+
+use crate::coder::encode_hex;
+use crate::errors::{Result, SdkError};
+
+// per signature: a 20 byte address, a 64 byte signature and a recovery id
+const SIGNED_ADDRESS_SIZE: usize = 20 + 64 + 1;
+const SIGNATURE_OFFSETS_SIZE: usize = 11;
+
+/// Ethereum address of the validator that signed `payload` with `signature`, an
+/// `eth_sign` signature of its keccak256 hash as `r || s || v`.
+pub fn evm_signer(payload: &[u8], signature: &[u8; 65]) -> Result<[u8; 20]> {
+    let hash = keccak::hash(&evm_signed_message(payload));
+    let public_key = secp256k1_recover(&hash.to_bytes(), recovery_id(signature)?, &signature[..64])
+        .map_err(|_| SdkError::InvalidValue(encode_hex(signature)))?;
+
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&keccak::hash(&public_key.to_bytes()).to_bytes()[12..]);
+    Ok(address)
+}
+
+/// Secp256k1 program instruction checking `signatures` of `payload`, to be placed
+/// at `index` of the transaction importing it. The signers are recovered from the
+/// signatures, and the message is shared by all of them.
+pub fn evm_signatures_instruction(
+    index: u8,
+    payload: &[u8],
+    signatures: &[[u8; 65]],
+) -> Result<Instruction> {
+    let message = evm_signed_message(payload);
+    let data_start = 1 + SIGNATURE_OFFSETS_SIZE * signatures.len();
+    let message_offset = data_start + SIGNED_ADDRESS_SIZE * signatures.len();
+
+    let mut data = vec![signatures.len() as u8];
+    let mut signed = vec![];
+    for (i, signature) in signatures.iter().enumerate() {
+        let address_offset = data_start + SIGNED_ADDRESS_SIZE * i;
+        data.extend_from_slice(&((address_offset + 20) as u16).to_le_bytes());
+        data.push(index);
+        data.extend_from_slice(&(address_offset as u16).to_le_bytes());
+        data.push(index);
+        data.extend_from_slice(&(message_offset as u16).to_le_bytes());
+        data.extend_from_slice(&(message.len() as u16).to_le_bytes());
+        data.push(index);
+
+        signed.extend_from_slice(&evm_signer(payload, signature)?);
+        signed.extend_from_slice(&signature[..64]);
+        signed.push(recovery_id(signature)?);
+    }
+    data.extend(signed);
+    data.extend(message);
+
+    Ok(Instruction {
+        program_id: secp256k1_program::ID,
+        accounts: vec![],
+        data,
+    })
+}
+
+/// Recovery id of `signature`, whose `v` is 27 or 28 in Ethereum and the recovery
+/// id itself elsewhere.
+fn recovery_id(signature: &[u8; 65]) -> Result<u8> {
+    match signature[64] {
+        v @ (27 | 28) => Ok(v - 27),
+        v @ (0 | 1) => Ok(v),
+        _ => Err(SdkError::InvalidValue(encode_hex(signature))),
+    }
+}
@@ -0,0 +1,101 @@
+use std::fmt;
+
+use chain::state::{FirstClassData, ValueKind};
+
+use crate::coder::{encode_hex, format_decimal, U256, U512};
+use crate::errors::{Result, SdkError};
+
+/// A first class data feed read with its metadata.
+///
+/// Feeds initialized before metadata existed are read with Umbrella
+/// conventions: 18 decimal prices, raw bytes for `FIXED_` keys.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeedValue {
+    pub key: String,
+    pub kind: ValueKind,
+    pub decimals: u8,
+    pub description: String,
+    pub value: [u8; 32],
+    pub timestamp: u32,
+    /// Half width of the interval around `value` reported with it, in the
+    /// same units. `None` when the last update did not report one.
+    pub confidence: Option<[u8; 32]>,
+}
+
+impl FeedValue {
+    pub fn from_fcd(fcd: &FirstClassData) -> Result<Self> {
+        let metadata = fcd.resolved_metadata();
+        let kind = ValueKind::from_u8(metadata.kind)
+            .ok_or_else(|| SdkError::UnknownValueKind(metadata.kind))?;
+
+        Ok(FeedValue {
+            key: fcd.key.clone(),
+            kind,
+            decimals: metadata.decimals,
+            description: metadata.description,
+            value: fcd.value,
+            timestamp: fcd.timestamp,
+            confidence: fcd.has_confidence.then_some(fcd.confidence),
+        })
+    }
+
+    /// The value as an integer scaled by `10^decimals`, `None` for raw feeds.
+    pub fn number(&self) -> Option<U256> {
+        match self.kind {
+            ValueKind::Raw => None,
+            _ => Some(U256::from_big_endian(&self.value)),
+        }
+    }
+
+    /// The confidence as an integer scaled like `number`, `None` for raw feeds
+    /// and values reported without one.
+    pub fn confidence_number(&self) -> Option<U256> {
+        self.number()?;
+        self.confidence
+            .map(|confidence| U256::from_big_endian(&confidence))
+    }
+
+    /// Fails unless the confidence is reported and at most `max_ratio_bps`
+    /// basis points of the value, e.g. 50 for prices known within 0.5%.
+    pub fn check_confidence(&self, max_ratio_bps: u32) -> Result<()> {
+        let (value, confidence) = match (self.number(), self.confidence_number()) {
+            (Some(value), Some(confidence)) => (value, confidence),
+            _ => return Err(SdkError::NoConfidence(self.key.clone())),
+        };
+
+        let widened = |number: U256, factor: u32| {
+            let mut bytes = [0u8; 32];
+            number.to_big_endian(&mut bytes);
+            U512::from_big_endian(&bytes) * U512::from(factor)
+        };
+        if widened(confidence, 10_000) > widened(value, max_ratio_bps) {
+            return Err(SdkError::ConfidenceTooWide {
+                key: self.key.clone(),
+                max_ratio_bps,
+            });
+        }
+        Ok(())
+    }
+
+    /// The value as a decimal string, `None` for raw feeds.
+    pub fn to_decimal_string(&self) -> Option<String> {
+        self.number()
+            .map(|number| format_decimal(number, self.decimals.into()))
+    }
+
+    /// The value as a float, `None` for raw feeds. Loses precision past ~15
+    /// significant digits; use `number` or `to_decimal_string` for exact values.
+    pub fn to_f64(&self) -> Option<f64> {
+        self.to_decimal_string()?.parse().ok()
+    }
+}
+
+/// Decimal numbers for numeric feeds, hex for raw ones.
+impl fmt::Display for FeedValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.to_decimal_string() {
+            Some(decimal) => f.write_str(&decimal),
+            None => f.write_str(&encode_hex(&self.value)),
+        }
+    }
+}
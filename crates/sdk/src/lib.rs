@@ -0,0 +1,26 @@
+//! Rust client for the Umbrella `chain` program.
+//!
+//! Derives the program's PDAs, encodes and decodes Umbrella values, reads
+//! feeds with their metadata, signs block attestations, recovers sidechain
+//! validator signatures, and wraps `anchor_client` for reading accounts and
+//! sending owner instructions.
+
+pub use attestation::*;
+pub use client::*;
+pub use coder::*;
+pub use errors::*;
+pub use evm::*;
+pub use feed::*;
+pub use pda::*;
+
+pub use anchor_client;
+pub use chain;
+pub use sorted_merkle_tree;
+
+pub mod attestation;
+pub mod client;
+pub mod coder;
+pub mod errors;
+pub mod evm;
+pub mod feed;
+pub mod pda;
@@ -0,0 +1,115 @@
+use anchor_client::anchor_lang::prelude::Pubkey;
+use sorted_merkle_tree::encode_key;
+
+use crate::coder::{to_bytes, DECIMALS, U256};
+use crate::errors::Result;
+
+pub const AUTHORITY_SEED: &[u8] = b"authority";
+pub const STATUS_SEED: &[u8] = b"status";
+pub const CLOSED_FCD_SEED: &[u8] = b"closed";
+pub const FEED_REGISTRY_SEED: &[u8] = b"feed_registry";
+pub const FCD_HISTORY_SEED: &[u8] = b"history";
+pub const VALIDATOR_SET_SEED: &[u8] = b"validators";
+pub const FCD_ROUND_SEED: &[u8] = b"round";
+pub const FEE_CONFIG_SEED: &[u8] = b"fee_config";
+pub const SUBSCRIPTION_SEED: &[u8] = b"subscription";
+pub const REWARD_VAULT_SEED: &[u8] = b"rewards";
+pub const STAKE_CONFIG_SEED: &[u8] = b"stake_config";
+pub const STAKE_SEED: &[u8] = b"stake";
+pub const SLASHED_BLOCK_SEED: &[u8] = b"slashed";
+pub const EVM_VALIDATOR_SET_SEED: &[u8] = b"evm_validators";
+
+pub fn authority_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[AUTHORITY_SEED], program_id).0
+}
+
+pub fn status_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[STATUS_SEED], program_id).0
+}
+
+/// Seed of a `Block` account: the block id encoded as an Umbrella value,
+/// like `derivePDAFromBlockId` in the TypeScript tests.
+pub fn block_seed(block_id: u32) -> Vec<u8> {
+    let number = U256::from(block_id) * U256::exp10(DECIMALS);
+    to_bytes(number).to_vec()
+}
+
+pub fn block_address(program_id: &Pubkey, block_id: u32) -> (Pubkey, Vec<u8>) {
+    let seed = block_seed(block_id);
+    let (address, _) = Pubkey::find_program_address(&[&seed], program_id);
+    (address, seed)
+}
+
+/// Seed of a `FirstClassData` account: the key encoded as a leaf key.
+pub fn fcd_seed(key: &str) -> Result<Vec<u8>> {
+    Ok(encode_key(key)?.to_vec())
+}
+
+pub fn fcd_address(program_id: &Pubkey, key: &str) -> Result<(Pubkey, Vec<u8>)> {
+    let seed = fcd_seed(key)?;
+    let (address, _) = Pubkey::find_program_address(&[&seed], program_id);
+    Ok((address, seed))
+}
+
+/// Address of the `ClosedFirstClassData` left by closing the FCD at `fcd`.
+pub fn closed_fcd_address(program_id: &Pubkey, fcd: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[CLOSED_FCD_SEED, fcd.as_ref()], program_id).0
+}
+
+/// Address of page `page` of the `FeedRegistry`.
+pub fn feed_registry_address(program_id: &Pubkey, page: u32) -> Pubkey {
+    Pubkey::find_program_address(&[FEED_REGISTRY_SEED, &page.to_le_bytes()], program_id).0
+}
+
+/// Address of the `FcdHistory` of the FCD at `fcd`.
+pub fn fcd_history_address(program_id: &Pubkey, fcd: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[FCD_HISTORY_SEED, fcd.as_ref()], program_id).0
+}
+
+pub fn validator_set_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[VALIDATOR_SET_SEED], program_id).0
+}
+
+/// Address of the `FcdRound` collecting validator observations for the FCD at `fcd`.
+pub fn fcd_round_address(program_id: &Pubkey, fcd: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[FCD_ROUND_SEED, fcd.as_ref()], program_id).0
+}
+
+pub fn fee_config_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[FEE_CONFIG_SEED], program_id).0
+}
+
+/// Address of the `Subscription` of `consumer`.
+pub fn subscription_address(program_id: &Pubkey, consumer: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[SUBSCRIPTION_SEED, consumer.as_ref()], program_id).0
+}
+
+pub fn reward_vault_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[REWARD_VAULT_SEED], program_id).0
+}
+
+pub fn stake_config_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[STAKE_CONFIG_SEED], program_id).0
+}
+
+/// Address of the `ValidatorStake` of `validator`.
+pub fn validator_stake_address(program_id: &Pubkey, validator: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[STAKE_SEED, validator.as_ref()], program_id).0
+}
+
+/// Address of the `SlashedBlock` recording the equivocation of `validator` at `block_id`.
+pub fn slashed_block_address(program_id: &Pubkey, validator: &Pubkey, block_id: u32) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            SLASHED_BLOCK_SEED,
+            validator.as_ref(),
+            &block_id.to_le_bytes(),
+        ],
+        program_id,
+    )
+    .0
+}
+
+pub fn evm_validator_set_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[EVM_VALIDATOR_SET_SEED], program_id).0
+}
@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use aquarius_sdk::anchor_client::solana_sdk::ed25519_instruction::verify;
+use aquarius_sdk::anchor_client::solana_sdk::feature_set::FeatureSet;
+use aquarius_sdk::anchor_client::solana_sdk::signature::{Keypair, Signer};
+use aquarius_sdk::*;
+
+#[test]
+fn builds_attestations_the_ed25519_program_verifies() {
+    let validator = Keypair::new();
+    let root = [7u8; 32];
+    let signature = sign_attestation(&validator, 42, &root);
+
+    let instruction = attestation_instruction(&validator.pubkey(), 42, &root, &signature);
+
+    let feature_set = Arc::new(FeatureSet::all_enabled());
+    assert!(verify(&instruction.data, &[&instruction.data], &feature_set).is_ok());
+}
+
+#[test]
+fn rejects_attestations_of_another_root() {
+    let validator = Keypair::new();
+    let signature = sign_attestation(&validator, 42, &[7u8; 32]);
+
+    let instruction = attestation_instruction(&validator.pubkey(), 42, &[8u8; 32], &signature);
+
+    let feature_set = Arc::new(FeatureSet::all_enabled());
+    assert!(verify(&instruction.data, &[&instruction.data], &feature_set).is_err());
+}
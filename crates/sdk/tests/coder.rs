@@ -0,0 +1,83 @@
+use aquarius_sdk::anchor_client::anchor_lang::prelude::Pubkey;
+use aquarius_sdk::*;
+
+#[test]
+fn decodes_umbrella_numbers_with_18_decimals() {
+    let value =
+        decode_hex("0x000000000000000000000000000000000000000000000000259ae7ce85275000").unwrap();
+    assert_eq!(decode_value(&value, "1INCH-DAI"), "2.709733");
+    assert_eq!(encode_value("2.709733", "1INCH-DAI").unwrap(), value);
+}
+
+#[test]
+fn encodes_whole_and_small_numbers() {
+    for value in [
+        "0",
+        "1",
+        "3001.23",
+        "0.000000000000000001",
+        "123456789012345",
+    ] {
+        let encoded = encode_value(value, "ETH-USD").unwrap();
+        assert_eq!(decode_value(&encoded, "ETH-USD"), value);
+    }
+}
+
+#[test]
+fn rejects_malformed_values() {
+    assert!(encode_value("-1", "ETH-USD").is_err());
+    assert!(encode_value("1.0000000000000000001", "ETH-USD").is_err());
+    assert!(encode_value("abc", "ETH-USD").is_err());
+    assert!(encode_value("0x1234", "FIXED_ROOT").is_err());
+}
+
+#[test]
+fn fixed_keys_keep_raw_hex() {
+    let root = "0x1786dd07dffc4abfe4fb2bb007dd4fdf93a690e185142a14af877654625066ac";
+    let encoded = encode_value(root, "FIXED_ROOT").unwrap();
+    assert_eq!(decode_value(&encoded, "FIXED_ROOT"), root);
+    assert_eq!(encode_hex(&encoded), root);
+}
+
+#[test]
+fn block_seed_is_the_block_id_encoded_as_value() {
+    assert_eq!(
+        block_seed(343062),
+        encode_value("343062", "").unwrap().to_vec()
+    );
+    assert_eq!(
+        encode_hex(&block_seed(343062)),
+        "0x0000000000000000000000000000000000000000000048a56da4139fd2980000"
+    );
+    for block_id in [0, 1, 343062, u32::MAX] {
+        assert_eq!(
+            block_seed(block_id),
+            chain::state::Block::seed(block_id).to_vec()
+        );
+    }
+}
+
+#[test]
+fn derives_addresses_from_seeds() {
+    let program_id = chain::ID;
+    let (block, seed) = block_address(&program_id, 1335);
+    assert_eq!(block, Pubkey::find_program_address(&[&seed], &program_id).0);
+
+    let (fcd, seed) = fcd_address(&program_id, "ETH-USD").unwrap();
+    assert_eq!(
+        seed,
+        sorted_merkle_tree::encode_key("ETH-USD").unwrap().to_vec()
+    );
+    assert_eq!(fcd, Pubkey::find_program_address(&[&seed], &program_id).0);
+
+    assert_ne!(authority_address(&program_id), status_address(&program_id));
+    assert!(fcd_address(&program_id, &"X".repeat(33)).is_err());
+}
+
+#[test]
+fn decodes_hex_of_any_length() {
+    assert_eq!(decode_hex_bytes("0x0a0b").unwrap(), vec![10, 11]);
+    assert_eq!(decode_hex_array::<2>("0a0b").unwrap(), [10, 11]);
+    assert!(decode_hex_bytes("0x0a0").is_err());
+    assert!(decode_hex_array::<3>("0x0a0b").is_err());
+}
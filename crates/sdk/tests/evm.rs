@@ -0,0 +1,57 @@
+use aquarius_sdk::anchor_client::anchor_lang::solana_program::keccak;
+use aquarius_sdk::chain::state::{evm_signed_message, EvmBlockHeader};
+use aquarius_sdk::*;
+
+// private key 0x0101...01 and its Ethereum address
+const KEY: [u8; 32] = [1; 32];
+const ADDRESS: &str = "0x1a642f0e3c3af545e7acbd38b07251b3990914f1";
+
+fn payload() -> [u8; 96] {
+    EvmBlockHeader {
+        block_id: 7,
+        root: [9; 32],
+        timestamp: 1651640000,
+    }
+    .encode()
+}
+
+/// `eth_sign` of the payload hash with `KEY`, `v` being 27 or 28.
+fn eth_signature() -> [u8; 65] {
+    let hash = keccak::hash(&evm_signed_message(&payload())).to_bytes();
+    let key = libsecp256k1::SecretKey::parse(&KEY).unwrap();
+    let (signature, recovery_id) = libsecp256k1::sign(&libsecp256k1::Message::parse(&hash), &key);
+    let mut bytes = [0u8; 65];
+    bytes[..64].copy_from_slice(&signature.serialize());
+    bytes[64] = 27 + recovery_id.serialize();
+    bytes
+}
+
+#[test]
+fn recovers_the_signer_of_a_header() {
+    let signer = evm_signer(&payload(), &eth_signature()).unwrap();
+
+    assert_eq!(encode_hex(&signer), ADDRESS);
+}
+
+#[test]
+fn rejects_signatures_with_an_invalid_v() {
+    let mut signature = eth_signature();
+    signature[64] = 29;
+
+    assert!(evm_signer(&payload(), &signature).is_err());
+}
+
+#[test]
+fn builds_signatures_the_secp256k1_program_verifies() {
+    use std::sync::Arc;
+
+    use aquarius_sdk::anchor_client::solana_sdk::feature_set::FeatureSet;
+    use aquarius_sdk::anchor_client::solana_sdk::secp256k1_instruction::verify;
+
+    let instruction =
+        evm_signatures_instruction(0, &payload(), &[eth_signature(), eth_signature()]).unwrap();
+
+    let feature_set = Arc::new(FeatureSet::all_enabled());
+    assert!(verify(&instruction.data, &[&instruction.data], &feature_set).is_ok());
+    assert_eq!(instruction.data[0], 2);
+}
@@ -0,0 +1,161 @@
+use aquarius_sdk::chain::state::{FeedMetadata, FirstClassData, ValueKind};
+use aquarius_sdk::*;
+
+fn fcd(key: &str, value: [u8; 32], metadata: FeedMetadata) -> FirstClassData {
+    FirstClassData {
+        key: key.to_string(),
+        value,
+        timestamp: 1651640000,
+        metadata,
+        has_history: false,
+        cumulative: 0,
+        aggregated: false,
+        confidence: [0u8; 32],
+        has_confidence: false,
+        registry_page: None,
+    }
+}
+
+#[test]
+fn reads_values_with_their_decimals() {
+    let metadata = FeedMetadata {
+        decimals: 6,
+        kind: ValueKind::Volume as u8,
+        description: "24h ETH volume in USD".to_string(),
+    };
+    let mut value = [0u8; 32];
+    value[24..].copy_from_slice(&1_234_500_000u64.to_be_bytes());
+
+    let feed = FeedValue::from_fcd(&fcd("ETH-VOL", value, metadata)).unwrap();
+
+    assert_eq!(feed.kind, ValueKind::Volume);
+    assert_eq!(feed.description, "24h ETH volume in USD");
+    assert_eq!(feed.number(), Some(U256::from(1_234_500_000u64)));
+    assert_eq!(feed.to_decimal_string().unwrap(), "1234.5");
+    assert_eq!(feed.to_f64(), Some(1234.5));
+    assert_eq!(feed.to_string(), "1234.5");
+}
+
+#[test]
+fn reads_raw_values_as_hex() {
+    let value = [0xab; 32];
+    let feed = FeedValue::from_fcd(&fcd(
+        "FIXED_ROOT",
+        value,
+        FeedMetadata::umbrella("FIXED_ROOT"),
+    ))
+    .unwrap();
+
+    assert_eq!(feed.kind, ValueKind::Raw);
+    assert_eq!(feed.number(), None);
+    assert_eq!(feed.to_f64(), None);
+    assert_eq!(feed.to_string(), encode_hex(&value));
+}
+
+#[test]
+fn reads_feeds_without_metadata_with_umbrella_conventions() {
+    let value = encode_value("3001.23", "ETH-USD").unwrap();
+    let feed = FeedValue::from_fcd(&fcd("ETH-USD", value, FeedMetadata::default())).unwrap();
+    assert_eq!(feed.kind, ValueKind::Price);
+    assert_eq!(feed.decimals, 18);
+    assert_eq!(feed.to_string(), "3001.23");
+
+    let raw = FeedValue::from_fcd(&fcd("FIXED_ROOT", [1; 32], FeedMetadata::default())).unwrap();
+    assert_eq!(raw.kind, ValueKind::Raw);
+}
+
+#[test]
+fn rejects_unknown_value_kinds() {
+    let metadata = FeedMetadata {
+        kind: 9,
+        ..FeedMetadata::umbrella("ETH-USD")
+    };
+    assert!(matches!(
+        FeedValue::from_fcd(&fcd("ETH-USD", [0; 32], metadata)),
+        Err(SdkError::UnknownValueKind(9))
+    ));
+}
+
+#[test]
+fn formats_numbers_with_any_decimals() {
+    assert_eq!(format_decimal(U256::from(1_500u64), 3), "1.5");
+    assert_eq!(format_decimal(U256::from(7u64), 2), "0.07");
+    assert_eq!(format_decimal(U256::from(42u64), 0), "42");
+}
+
+#[test]
+fn checks_the_confidence_ratio() {
+    let mut stored = fcd(
+        "ETH-USD",
+        encode_value("2000", "ETH-USD").unwrap(),
+        FeedMetadata::umbrella("ETH-USD"),
+    );
+    stored.confidence = encode_value("10", "ETH-USD").unwrap();
+    stored.has_confidence = true;
+    let feed = FeedValue::from_fcd(&stored).unwrap();
+
+    assert_eq!(
+        feed.confidence_number(),
+        Some(U256::from(10u64) * U256::exp10(18))
+    );
+    assert!(feed.check_confidence(50).is_ok());
+    assert!(feed.check_confidence(100).is_ok());
+    assert!(matches!(
+        feed.check_confidence(49),
+        Err(SdkError::ConfidenceTooWide {
+            max_ratio_bps: 49,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn rejects_values_without_confidence() {
+    let feed = FeedValue::from_fcd(&fcd(
+        "ETH-USD",
+        encode_value("2000", "ETH-USD").unwrap(),
+        FeedMetadata::umbrella("ETH-USD"),
+    ))
+    .unwrap();
+    assert_eq!(feed.confidence, None);
+    assert!(matches!(
+        feed.check_confidence(10_000),
+        Err(SdkError::NoConfidence(_))
+    ));
+
+    let mut stored = fcd("FIXED_ROOT", [1; 32], FeedMetadata::umbrella("FIXED_ROOT"));
+    stored.confidence = [1; 32];
+    stored.has_confidence = true;
+    let raw = FeedValue::from_fcd(&stored).unwrap();
+    assert_eq!(raw.confidence_number(), None);
+    assert!(matches!(
+        raw.check_confidence(10_000),
+        Err(SdkError::NoConfidence(_))
+    ));
+}
+
+#[test]
+fn accepts_a_zero_confidence_of_a_unanimous_round() {
+    let mut stored = fcd(
+        "ETH-USD",
+        encode_value("2000", "ETH-USD").unwrap(),
+        FeedMetadata::umbrella("ETH-USD"),
+    );
+    stored.has_confidence = true;
+    let feed = FeedValue::from_fcd(&stored).unwrap();
+
+    assert_eq!(feed.confidence, Some([0u8; 32]));
+    assert_eq!(feed.confidence_number(), Some(U256::zero()));
+    assert!(feed.check_confidence(0).is_ok());
+}
+
+#[test]
+fn compares_confidence_ratios_of_large_values_without_overflow() {
+    let mut stored = fcd("ETH-USD", [0xff; 32], FeedMetadata::umbrella("ETH-USD"));
+    stored.confidence = [0x0f; 32];
+    stored.has_confidence = true;
+    let feed = FeedValue::from_fcd(&stored).unwrap();
+
+    assert!(feed.check_confidence(10_000).is_ok());
+    assert!(feed.check_confidence(100).is_err());
+}
@@ -0,0 +1,21 @@
+use crate::errors::TreeError;
+use crate::hash::Hash;
+
+/// Encodes a feed key the same way as `LeafKeyCoder.encode` from
+/// `@umb-network/toolbox`: the utf8 bytes, left padded with zeros to 32 bytes.
+pub fn encode_key(key: &str) -> Result<Hash, TreeError> {
+    let bytes = key.as_bytes();
+    if bytes.len() > 32 {
+        return Err(TreeError::KeyTooLong(key.to_string()));
+    }
+
+    let mut encoded = [0u8; 32];
+    encoded[32 - bytes.len()..].copy_from_slice(bytes);
+    Ok(encoded)
+}
+
+/// Reverses `encode_key`, dropping the zero padding.
+pub fn decode_key(encoded: &Hash) -> String {
+    let start = encoded.iter().position(|b| *b != 0).unwrap_or(32);
+    String::from_utf8_lossy(&encoded[start..]).into_owned()
+}
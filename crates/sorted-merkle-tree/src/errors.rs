@@ -0,0 +1,9 @@
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum TreeError {
+    #[error("key {0:?} does not fit into 32 bytes")]
+    KeyTooLong(String),
+    #[error("key {0:?} is not part of the tree")]
+    UnknownKey(String),
+}
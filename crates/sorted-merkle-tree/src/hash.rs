@@ -0,0 +1,117 @@
+use sha3::{Digest, Keccak256};
+
+pub type Hash = [u8; 32];
+
+/// Number of trailing root bytes replaced by the block timestamp.
+pub const TIMESTAMP_BYTES: usize = 4;
+
+/// Byte prepended to leaves by `HashScheme::Prefixed`.
+pub const LEAF_PREFIX: u8 = 0x00;
+
+/// Byte prepended to inner nodes by `HashScheme::Prefixed`.
+pub const NODE_PREFIX: u8 = 0x01;
+
+/// Byte prepended to the hash of a message by `hash_message`.
+pub const MESSAGE_PREFIX: u8 = 0x02;
+
+/// How leaves and inner nodes are hashed, matching `chain::merkle::HashScheme`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HashScheme {
+    /// `keccak(key || value)` and `keccak(a || b)`, used by Umbrella sidechain roots.
+    #[default]
+    Legacy = 0,
+    /// `keccak(0x00 || key || value)` and `keccak(0x01 || a || b)`.
+    Prefixed = 1,
+}
+
+impl HashScheme {
+    pub fn from_u8(scheme: u8) -> Option<Self> {
+        match scheme {
+            0 => Some(HashScheme::Legacy),
+            1 => Some(HashScheme::Prefixed),
+            _ => None,
+        }
+    }
+
+    pub fn hash_leaf(self, key: &Hash, value: &Hash) -> Hash {
+        self.hash(LEAF_PREFIX, key, value)
+    }
+
+    /// Hashes two nodes smallest first (equal hashes keep their order).
+    pub fn hash_pair(self, a: &Hash, b: &Hash) -> Hash {
+        if a <= b {
+            self.hash(NODE_PREFIX, a, b)
+        } else {
+            self.hash(NODE_PREFIX, b, a)
+        }
+    }
+
+    pub fn compute_root(self, proof: &[Hash], leaf: Hash) -> Hash {
+        proof
+            .iter()
+            .fold(leaf, |computed, element| self.hash_pair(element, &computed))
+    }
+
+    pub fn verify_squashed_root(self, squashed_root: &Hash, proof: &[Hash], leaf: Hash) -> bool {
+        extract_root(&self.compute_root(proof, leaf)) == extract_root(squashed_root)
+    }
+
+    fn hash(self, prefix: u8, a: &Hash, b: &Hash) -> Hash {
+        match self {
+            HashScheme::Legacy => keccak(&[*a, *b].concat()),
+            HashScheme::Prefixed => keccak(&[&[prefix][..], a, b].concat()),
+        }
+    }
+}
+
+pub fn keccak(data: &[u8]) -> Hash {
+    let mut hasher = Keccak256::new();
+    hasher.update(data);
+    hasher.finalize().into()
+}
+
+/// Leaf hash of the legacy scheme.
+pub fn hash_leaf(key: &Hash, value: &Hash) -> Hash {
+    HashScheme::Legacy.hash_leaf(key, value)
+}
+
+/// Leaf of an arbitrary-length message, `keccak(0x02 || keccak(message))` in
+/// every scheme, matching `chain::merkle::hash_message`.
+pub fn hash_message(message: &[u8]) -> Hash {
+    keccak(&[&[MESSAGE_PREFIX][..], &keccak(message)].concat())
+}
+
+/// Pair hash of the legacy scheme, matching `compare_hashes` in the
+/// on-chain verifier.
+pub fn hash_pair(a: &Hash, b: &Hash) -> Hash {
+    HashScheme::Legacy.hash_pair(a, b)
+}
+
+/// Replaces the last four bytes of `root` with the big endian `timestamp`.
+pub fn squash_root(root: &Hash, timestamp: u32) -> Hash {
+    let mut squashed = *root;
+    squashed[32 - TIMESTAMP_BYTES..].copy_from_slice(&timestamp.to_be_bytes());
+    squashed
+}
+
+/// Zeroes the timestamp bytes of a squashed root.
+pub fn extract_root(squashed_root: &Hash) -> Hash {
+    let mut root = *squashed_root;
+    root[32 - TIMESTAMP_BYTES..].fill(0);
+    root
+}
+
+/// Reads the timestamp squashed into a root.
+pub fn extract_timestamp(squashed_root: &Hash) -> u32 {
+    let mut timestamp = [0u8; TIMESTAMP_BYTES];
+    timestamp.copy_from_slice(&squashed_root[32 - TIMESTAMP_BYTES..]);
+    u32::from_be_bytes(timestamp)
+}
+
+pub fn compute_root(proof: &[Hash], leaf: Hash) -> Hash {
+    HashScheme::Legacy.compute_root(proof, leaf)
+}
+
+pub fn verify_squashed_root(squashed_root: &Hash, proof: &[Hash], leaf: Hash) -> bool {
+    HashScheme::Legacy.verify_squashed_root(squashed_root, proof, leaf)
+}
@@ -0,0 +1,15 @@
+//! Off-chain builder for Umbrella's sorted merkle trees.
+//!
+//! Produces the roots and proofs accepted by the `chain` program's
+//! `verify_proof_for_block` instruction, using the same leaf encoding and
+//! pair ordering as the on-chain verifier.
+
+pub use coder::*;
+pub use errors::*;
+pub use hash::*;
+pub use tree::*;
+
+pub mod coder;
+pub mod errors;
+pub mod hash;
+pub mod tree;
@@ -0,0 +1,143 @@
+use std::collections::BTreeMap;
+
+use crate::coder::encode_key;
+use crate::errors::TreeError;
+use crate::hash::*;
+
+/// A leaf together with the sibling hashes needed to rebuild the root.
+///
+/// `key`, `value` and `proof` are exactly the arguments expected by
+/// `verify_proof_for_block`, for a block submitted with `scheme`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Proof {
+    pub key: Hash,
+    pub value: Hash,
+    pub proof: Vec<Hash>,
+    pub scheme: HashScheme,
+}
+
+impl Proof {
+    pub fn leaf(&self) -> Hash {
+        self.scheme.hash_leaf(&self.key, &self.value)
+    }
+
+    pub fn verify(&self, squashed_root: &Hash) -> bool {
+        self.scheme
+            .verify_squashed_root(squashed_root, &self.proof, self.leaf())
+    }
+}
+
+/// Merkle tree over key/value data, built the same way as `SortedMerkleTree`
+/// from `@umb-network/toolbox`.
+///
+/// Leaves are ordered by key. Each level hashes neighbouring nodes as sorted
+/// pairs, and an odd node at the end of a level is promoted unchanged.
+#[derive(Clone, Debug)]
+pub struct SortedMerkleTree {
+    indexes: BTreeMap<String, usize>,
+    leaves: Vec<(Hash, Hash)>,
+    levels: Vec<Vec<Hash>>,
+    scheme: HashScheme,
+}
+
+impl SortedMerkleTree {
+    /// Tree hashed with the legacy scheme, as built by the Umbrella sidechain.
+    pub fn new(data: &BTreeMap<String, Hash>) -> Result<Self, TreeError> {
+        Self::with_scheme(data, HashScheme::Legacy)
+    }
+
+    pub fn with_scheme(
+        data: &BTreeMap<String, Hash>,
+        scheme: HashScheme,
+    ) -> Result<Self, TreeError> {
+        let mut indexes = BTreeMap::new();
+        let mut leaves = Vec::with_capacity(data.len());
+
+        for (index, (key, value)) in data.iter().enumerate() {
+            indexes.insert(key.clone(), index);
+            leaves.push((encode_key(key)?, *value));
+        }
+
+        let mut levels = vec![leaves
+            .iter()
+            .map(|(key, value)| scheme.hash_leaf(key, value))
+            .collect::<Vec<_>>()];
+
+        while levels[levels.len() - 1].len() > 1 {
+            let next = next_level(&levels[levels.len() - 1], scheme);
+            levels.push(next);
+        }
+
+        Ok(Self {
+            indexes,
+            leaves,
+            levels,
+            scheme,
+        })
+    }
+
+    pub fn scheme(&self) -> HashScheme {
+        self.scheme
+    }
+
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    /// Root of the tree, or the zero hash when there is no data.
+    pub fn root(&self) -> Hash {
+        self.levels[self.levels.len() - 1]
+            .first()
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Root with `timestamp` squashed into it, as submitted to the program.
+    pub fn squashed_root(&self, timestamp: u32) -> Hash {
+        squash_root(&self.root(), timestamp)
+    }
+
+    pub fn proof(&self, key: &str) -> Result<Proof, TreeError> {
+        let mut index = *self
+            .indexes
+            .get(key)
+            .ok_or_else(|| TreeError::UnknownKey(key.to_string()))?;
+        let (key, value) = self.leaves[index];
+
+        let mut proof = Vec::with_capacity(self.levels.len() - 1);
+        for level in &self.levels[..self.levels.len() - 1] {
+            let sibling = index ^ 1;
+            if sibling < level.len() {
+                proof.push(level[sibling]);
+            }
+            index /= 2;
+        }
+
+        Ok(Proof {
+            key,
+            value,
+            proof,
+            scheme: self.scheme,
+        })
+    }
+
+    /// Proofs for several keys at once, in the order the keys were given.
+    pub fn multi_proof<K: AsRef<str>>(&self, keys: &[K]) -> Result<Vec<Proof>, TreeError> {
+        keys.iter().map(|key| self.proof(key.as_ref())).collect()
+    }
+}
+
+fn next_level(nodes: &[Hash], scheme: HashScheme) -> Vec<Hash> {
+    nodes
+        .chunks(2)
+        .map(|pair| match pair {
+            [a, b] => scheme.hash_pair(a, b),
+            [single] => *single,
+            _ => unreachable!(),
+        })
+        .collect()
+}
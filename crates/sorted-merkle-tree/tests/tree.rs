@@ -0,0 +1,184 @@
+use std::collections::BTreeMap;
+
+use chain::merkle as on_chain;
+use sorted_merkle_tree::*;
+
+fn value(n: u64) -> Hash {
+    let mut value = [0u8; 32];
+    value[24..].copy_from_slice(&n.to_be_bytes());
+    value
+}
+
+fn data(size: u64) -> BTreeMap<String, Hash> {
+    (0..size)
+        .map(|i| (format!("KEY-{}", i), value(i * 1_000_000_007)))
+        .collect()
+}
+
+fn decode(hex_str: &str) -> Hash {
+    let mut hash = [0u8; 32];
+    hex::decode_to_slice(hex_str.trim_start_matches("0x"), &mut hash).unwrap();
+    hash
+}
+
+#[test]
+fn empty_tree_has_zero_root() {
+    let tree = SortedMerkleTree::new(&BTreeMap::new()).unwrap();
+    assert!(tree.is_empty());
+    assert_eq!(tree.root(), [0u8; 32]);
+    assert_eq!(
+        tree.proof("ETH-USD"),
+        Err(TreeError::UnknownKey("ETH-USD".to_string()))
+    );
+}
+
+#[test]
+fn single_leaf_tree_root_is_the_leaf() {
+    let tree = SortedMerkleTree::new(&data(1)).unwrap();
+    let proof = tree.proof("KEY-0").unwrap();
+    assert!(proof.proof.is_empty());
+    assert_eq!(tree.root(), proof.leaf());
+}
+
+#[test]
+fn rejects_keys_longer_than_32_bytes() {
+    let key = "X".repeat(33);
+    let data = BTreeMap::from([(key.clone(), value(1))]);
+    assert_eq!(
+        SortedMerkleTree::new(&data).unwrap_err(),
+        TreeError::KeyTooLong(key)
+    );
+}
+
+#[test]
+fn encodes_keys_like_leaf_key_coder() {
+    assert_eq!(
+        encode_key("1INCH-DAI").unwrap(),
+        decode("0x000000000000000000000000000000000000000000000031494e43482d444149")
+    );
+    assert_eq!(decode_key(&encode_key("ETH-USD").unwrap()), "ETH-USD");
+}
+
+#[test]
+fn squashes_timestamp_into_root() {
+    let tree = SortedMerkleTree::new(&data(5)).unwrap();
+    let squashed = tree.squashed_root(1651641200);
+    assert_eq!(extract_timestamp(&squashed), 1651641200);
+    assert_eq!(extract_root(&squashed), extract_root(&tree.root()));
+    assert_eq!(squashed[..28], tree.root()[..28]);
+}
+
+#[test]
+fn every_proof_verifies_for_odd_and_even_trees() {
+    for size in 1..=33 {
+        let data = data(size);
+        let tree = SortedMerkleTree::new(&data).unwrap();
+        let root = tree.squashed_root(1651641200 + size as u32);
+
+        for key in data.keys() {
+            let proof = tree.proof(key).unwrap();
+            assert!(proof.verify(&root), "size {} key {}", size, key);
+            assert_eq!(compute_root(&proof.proof, proof.leaf()), tree.root());
+        }
+    }
+}
+
+#[test]
+fn multi_proof_keeps_requested_order() {
+    let tree = SortedMerkleTree::new(&data(10)).unwrap();
+    let proofs = tree.multi_proof(&["KEY-7", "KEY-2"]).unwrap();
+    assert_eq!(proofs[0], tree.proof("KEY-7").unwrap());
+    assert_eq!(proofs[1], tree.proof("KEY-2").unwrap());
+    assert!(tree.multi_proof(&["KEY-1", "NOPE"]).is_err());
+}
+
+#[test]
+fn proofs_are_accepted_by_the_on_chain_verifier() {
+    for size in [1, 2, 3, 7, 8, 100] {
+        let tree = SortedMerkleTree::new(&data(size)).unwrap();
+        let root = tree.squashed_root(1651641200);
+
+        for proof in tree
+            .multi_proof(&["KEY-0", &format!("KEY-{}", size - 1)])
+            .unwrap()
+        {
+            assert!(on_chain::verify_squashed_root(
+                root,
+                &proof.proof,
+                proof.leaf()
+            ));
+            assert_eq!(
+                on_chain::compute_root(&proof.proof, proof.leaf()),
+                tree.root()
+            );
+
+            let mut tampered = proof.clone();
+            tampered.value[31] ^= 1;
+            assert!(!on_chain::verify_squashed_root(
+                root,
+                &tampered.proof,
+                tampered.leaf()
+            ));
+        }
+    }
+}
+
+#[test]
+fn matches_fixture_from_verify_tests() {
+    let proof = Proof {
+        key: decode("0x000000000000000000000000000000000000000000000031494e43482d444149"),
+        value: decode("0x000000000000000000000000000000000000000000000000259ae7ce85275000"),
+        proof: [
+            "8aa4e4134178289504b4b6c7c85527b41905cf3d51ad95eaec44a87fbe773b82",
+            "2555c92539183bfa28387c6e98403aeb44f8b7602d0580e4679f2432405b62b1",
+            "6bb2d161e2d374a8aa779e0c61ecef7e82b7a6ba6543bf997212ea164c7ec540",
+            "e3cd6c525d52487eb7439d1042dbd917a9b421fd2656a98a6f8af593fd4f4453",
+            "39afef9403f6ccd794a1bf6c48a55a0d4164d8ab9f32992410f62629bd57a6b7",
+            "72d0fddd950ac6ce7f54a48d4003843d526ee02fc21d8c305012bdd17f7058af",
+            "fb1199eb1639a574b06bd4f2fc619a9004fb55dd9016c6b24c4c79498a24099f",
+            "fa9e1fb3aa77f7249c18bd4dbd99bd9c3766a6bf6ab00eac7d5380732059566a",
+            "81b18433beaada4ee9a058a3eb1580498a61789809abb60517ec0ca5e0bcf948",
+            "a8440a4bf999006045d796a91e23fec4b23eee861ba9735d41dc804a76ae0643",
+            "dcec74631415edf80085bdb0907dfb4dd6928db21ebe31b201b1c61cd5a6b412",
+            "e1c181e05f242407fcce79feb83cad315d8d86e5d668f8fa8586d92f7eab082e",
+        ]
+        .iter()
+        .map(|h| decode(h))
+        .collect(),
+        scheme: HashScheme::Legacy,
+    };
+    let root = decode("0xff3a1d60902efa015c36f653c5d28e0b4a13bc5bdb8944b218fe2f6f6272b87a");
+
+    assert!(proof.verify(&root));
+    assert!(on_chain::verify_squashed_root(
+        root,
+        &proof.proof,
+        proof.leaf()
+    ));
+}
+
+#[test]
+fn prefixed_proofs_are_accepted_by_the_on_chain_verifier() {
+    let tree = SortedMerkleTree::with_scheme(&data(13), HashScheme::Prefixed).unwrap();
+    let root = tree.squashed_root(1651641200);
+    assert_ne!(
+        tree.root(),
+        SortedMerkleTree::new(&data(13)).unwrap().root()
+    );
+
+    for i in 0..13 {
+        let proof = tree.proof(&format!("KEY-{}", i)).unwrap();
+        assert_eq!(proof.scheme, HashScheme::Prefixed);
+        assert!(proof.verify(&root));
+        assert!(on_chain::HashScheme::Prefixed.verify_squashed_root(
+            root,
+            &proof.proof,
+            on_chain::HashScheme::Prefixed.hash_leaf(proof.key, proof.value)
+        ));
+        assert!(!on_chain::verify_squashed_root(
+            root,
+            &proof.proof,
+            on_chain::hash_leaf(proof.key, proof.value)
+        ));
+    }
+}
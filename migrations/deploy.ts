@@ -12,6 +12,8 @@ import {
   getAddressFromToml,
   derivePDAFromBlockId,
   derivePDAFromFCDKey,
+  deriveClosedFCDPDA,
+  deriveFeedRegistryPDA,
   encodeDataValue,
   decodeDataValue,
   encodeBlockRoot,
@@ -119,10 +121,35 @@ const initializeChain = async (padding: number) => {
   console.log('initialize tx: ' + tx);
 }
 
+const initializeFeedRegistryPage = async (page: number) => {
+  const registryPda = await deriveFeedRegistryPDA(page, program.programId);
+
+  try {
+    await program.account.feedRegistry.fetch(registryPda);
+  } catch (e) {
+    const tx = await program.rpc.initializeFeedRegistryPage(
+      page,
+      {
+        accounts: {
+          owner: anchor.getProvider().wallet.publicKey,
+          authority: authorityPda,
+          registry: registryPda,
+          systemProgram: SystemProgram.programId,
+        },
+      },
+    );
+    console.log('Feed registry page ' + page + ' initialized with tx: ' + tx);
+  }
+
+  return registryPda;
+}
+
 const initializeFCDs = async () => {
   //const timestamp = parseInt(String(Date.now() / 1000));
   const timestamp = 0;
   const promises = [];
+  // all feeds fit on the first page of 32
+  const registryPda = await initializeFeedRegistryPage(0);
 
 
   for (const {key, value} of fcds) {
@@ -132,6 +159,7 @@ const initializeFCDs = async () => {
       key,
       program.programId
     );
+    const closedPda = await deriveClosedFCDPDA(fcdPda, program.programId);
 
     try {
       const [fcdPda] = await derivePDAFromFCDKey(
@@ -153,6 +181,8 @@ const initializeFCDs = async () => {
               owner: anchor.getProvider().wallet.publicKey,
               authority: authorityPda,
               fcd: fcdPda,
+              closed: closedPda,
+              registry: registryPda,
               systemProgram: SystemProgram.programId,
             },
           },
@@ -1,9 +1,15 @@
+//! Example consumer of the chain program: a toy vault that accepts lamport
+//! deposits only while a fresh Umbrella price of its feed lies within bounds.
+//!
+//! The price is taken either from the feed's first class data account, read
+//! directly, or from a key/value proof against a submitted block, verified by
+//! `verify_proof_for_block` through CPI.
+
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
 use chain::cpi::accounts::Verify;
 use chain::program::Chain;
-use chain::{self};
-use chain::state::{Block, VerifyResult};
-use hex;
+use chain::state::{numeric_value, Block, FirstClassData, Status, VerifyResult};
 
 declare_id!("BmmRtz8Zf4rjQgWT643QG2eqHVkXzebSsnR7XipFTrAg");
 
@@ -11,158 +17,257 @@ declare_id!("BmmRtz8Zf4rjQgWT643QG2eqHVkXzebSsnR7XipFTrAg");
 pub mod caller {
     use super::*;
 
-    pub fn cpi_call_verify_true(
-        ctx: Context<CpiReturnContext>,
-        _seed: Vec<u8>
+    /// Creates a vault guarded by the feed `key`, whose prices have to lie within
+    /// `min_price..=max_price` (in the units of the feed's values) and be at most
+    /// `max_age` seconds old by the cluster clock.
+    pub fn initialize_vault(
+        ctx: Context<InitializeVault>,
+        key: String,
+        min_price: u128,
+        max_price: u128,
+        max_age: u32
     ) -> Result<()> {
+        require!(min_price <= max_price, VaultError::InvalidBounds);
+
+        let vault = &mut ctx.accounts.vault;
+        vault.authority = ctx.accounts.authority.key();
+        vault.key = feed_key(&key)?;
+        vault.min_price = min_price;
+        vault.max_price = max_price;
+        vault.max_age = max_age;
+        vault.deposits = 0;
 
-        let mut proofs = vec![[0u8; 32]; 12];
-        assert_eq!(hex::decode_to_slice("8aa4e4134178289504b4b6c7c85527b41905cf3d51ad95eaec44a87fbe773b82", &mut proofs[0] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("2555c92539183bfa28387c6e98403aeb44f8b7602d0580e4679f2432405b62b1", &mut proofs[1] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("6bb2d161e2d374a8aa779e0c61ecef7e82b7a6ba6543bf997212ea164c7ec540", &mut proofs[2] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("e3cd6c525d52487eb7439d1042dbd917a9b421fd2656a98a6f8af593fd4f4453", &mut proofs[3] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("39afef9403f6ccd794a1bf6c48a55a0d4164d8ab9f32992410f62629bd57a6b7", &mut proofs[4] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("72d0fddd950ac6ce7f54a48d4003843d526ee02fc21d8c305012bdd17f7058af", &mut proofs[5] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("fb1199eb1639a574b06bd4f2fc619a9004fb55dd9016c6b24c4c79498a24099f", &mut proofs[6] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("fa9e1fb3aa77f7249c18bd4dbd99bd9c3766a6bf6ab00eac7d5380732059566a", &mut proofs[7] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("81b18433beaada4ee9a058a3eb1580498a61789809abb60517ec0ca5e0bcf948", &mut proofs[8] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("a8440a4bf999006045d796a91e23fec4b23eee861ba9735d41dc804a76ae0643", &mut proofs[9] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("dcec74631415edf80085bdb0907dfb4dd6928db21ebe31b201b1c61cd5a6b412", &mut proofs[10] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("e1c181e05f242407fcce79feb83cad315d8d86e5d668f8fa8586d92f7eab082e", &mut proofs[11] as &mut [u8]), Ok(()));
-
-        let mut key = [0u8; 32];
-        let mut value = [0u8; 32];
-        assert_eq!(hex::decode_to_slice("000000000000000000000000000000000000000000000031494e43482d444149", &mut key as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("000000000000000000000000000000000000000000000000259ae7ce85275000", &mut value as &mut [u8]), Ok(()));
-
-        let cpi_program = ctx.accounts.cpi_return_program.to_account_info();
-
-        let cpi_accounts = Verify {
-            block: ctx.accounts.block.to_account_info(),
-            verify_result: ctx.accounts.cpi_return.to_account_info(),
-        };
-
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        let result = chain::cpi::verify_proof_for_block(cpi_ctx, _seed, proofs, key, value)?;
         Ok(())
     }
 
-    pub fn cpi_call_verify_false_tempered_proofs(
-        ctx: Context<CpiReturnContext>,
-        _seed: Vec<u8>
-    ) -> Result<()> {
+    /// Deposits `amount` lamports at the price stored in the feed's first class
+    /// data account.
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let fcd = &ctx.accounts.fcd;
+        let price = numeric_value(&fcd.value);
+        check_price(&ctx.accounts.vault, price, fcd.timestamp)?;
 
-        let mut proofs = vec![[0u8; 32]; 12];
-        assert_eq!(hex::decode_to_slice("8aa4e4134178289504b4b6c7c85527b41905cf3d51ad95eaec44a87fbe773b82", &mut proofs[0] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("2555c92539183bfa28387c6e98403aeb44f8b7602d0580e4679f2432405b62b1", &mut proofs[1] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("6bb2d161e2d374a8aa779e0c61ecef7e82b7a6ba6543bf997212ea164c7ec540", &mut proofs[2] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("e3cd6c525d52487eb7439d1042dbd917a9b421fd2656a98a6f8af593fd4f4453", &mut proofs[3] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("39afef9403f6ccd794a1bf6c48a55a0d4164d8ab9f32992410f62629bd57a6b7", &mut proofs[4] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("72d0fddd950ac6ce7f54a48d4003843d526ee02fc21d8c305012bdd17f7058af", &mut proofs[5] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("fb1199eb1639a574b06bd4f2fc619a9004fb55dd9016c6b24c4c79498a24099f", &mut proofs[6] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("fa9e1fb3aa77f7249c18bd4dbd99bd9c3766a6bf6ab00eac7d5380732059566a", &mut proofs[7] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("81b18433beaada4ee9a058a3eb1580498a61789809abb60517ec0ca5e0bcf948", &mut proofs[8] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("a8440a4bf999006045d796a91e23fec4b23eee861ba9735d41dc804a76ae0643", &mut proofs[9] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("dcec74631415edf80085bdb0907dfb4dd6928db21ebe31b201b1c61cd5a6b412", &mut proofs[10] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("deadbeaf5f242407fcce79feb83cad315d8d86e5d668f8fa8586d92f7eab082e", &mut proofs[11] as &mut [u8]), Ok(()));
-
-        let mut key = [0u8; 32];
-        let mut value = [0u8; 32];
-        assert_eq!(hex::decode_to_slice("000000000000000000000000000000000000000000000031494e43482d444149", &mut key as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("000000000000000000000000000000000000000000000000259ae7ce85275000", &mut value as &mut [u8]), Ok(()));
-
-        let cpi_program = ctx.accounts.cpi_return_program.to_account_info();
-
-        let cpi_accounts = Verify {
-            block: ctx.accounts.block.to_account_info(),
-            verify_result: ctx.accounts.cpi_return.to_account_info(),
-        };
-
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        let result = chain::cpi::verify_proof_for_block(cpi_ctx, _seed, proofs, key, value)?;
-        Ok(())
+        accept_deposit(
+            &mut ctx.accounts.vault,
+            &ctx.accounts.depositor,
+            &ctx.accounts.system_program,
+            amount,
+            price,
+            fcd.timestamp
+        )
     }
 
-    pub fn cpi_call_verify_false_tempered_key(
-        ctx: Context<CpiReturnContext>,
-        _seed: Vec<u8>
+    /// Deposits `amount` lamports at the price `value` of the vault's feed in the
+    /// block at `seed`, proven with `proof`. Fee accounts of the chain program go
+    /// in the remaining accounts and are passed on to the verification.
+    pub fn deposit_with_proof<'info>(
+        ctx: Context<'_, '_, '_, 'info, DepositWithProof<'info>>,
+        seed: Vec<u8>,
+        value: [u8; 32],
+        proof: Vec<[u8; 32]>,
+        amount: u64
     ) -> Result<()> {
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.chain_program.to_account_info(),
+            Verify {
+                block: ctx.accounts.block.to_account_info(),
+                verify_result: ctx.accounts.verify_result.to_account_info(),
+                status: ctx.accounts.status.to_account_info()
+            }
+        )
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+        chain::cpi::verify_proof_for_block(cpi_ctx, seed, proof, ctx.accounts.vault.key, value)?;
 
-        let mut proofs = vec![[0u8; 32]; 12];
-        assert_eq!(hex::decode_to_slice("8aa4e4134178289504b4b6c7c85527b41905cf3d51ad95eaec44a87fbe773b82", &mut proofs[0] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("2555c92539183bfa28387c6e98403aeb44f8b7602d0580e4679f2432405b62b1", &mut proofs[1] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("6bb2d161e2d374a8aa779e0c61ecef7e82b7a6ba6543bf997212ea164c7ec540", &mut proofs[2] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("e3cd6c525d52487eb7439d1042dbd917a9b421fd2656a98a6f8af593fd4f4453", &mut proofs[3] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("39afef9403f6ccd794a1bf6c48a55a0d4164d8ab9f32992410f62629bd57a6b7", &mut proofs[4] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("72d0fddd950ac6ce7f54a48d4003843d526ee02fc21d8c305012bdd17f7058af", &mut proofs[5] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("fb1199eb1639a574b06bd4f2fc619a9004fb55dd9016c6b24c4c79498a24099f", &mut proofs[6] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("fa9e1fb3aa77f7249c18bd4dbd99bd9c3766a6bf6ab00eac7d5380732059566a", &mut proofs[7] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("81b18433beaada4ee9a058a3eb1580498a61789809abb60517ec0ca5e0bcf948", &mut proofs[8] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("a8440a4bf999006045d796a91e23fec4b23eee861ba9735d41dc804a76ae0643", &mut proofs[9] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("dcec74631415edf80085bdb0907dfb4dd6928db21ebe31b201b1c61cd5a6b412", &mut proofs[10] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("e1c181e05f242407fcce79feb83cad315d8d86e5d668f8fa8586d92f7eab082e", &mut proofs[11] as &mut [u8]), Ok(()));
-
-        let mut key = [0u8; 32];
-        let mut value = [0u8; 32];
-        assert_eq!(hex::decode_to_slice("000000000000000000000000000000000000000000000031494e4348deadbeaf", &mut key as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("000000000000000000000000000000000000000000000000259ae7ce85275000", &mut value as &mut [u8]), Ok(()));
-
-        let cpi_program = ctx.accounts.cpi_return_program.to_account_info();
-
-        let cpi_accounts = Verify {
-            block: ctx.accounts.block.to_account_info(),
-            verify_result: ctx.accounts.cpi_return.to_account_info(),
-        };
-
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        let result = chain::cpi::verify_proof_for_block(cpi_ctx, _seed, proofs, key, value)?;
-        Ok(())
+        ctx.accounts.verify_result.reload()?;
+        require!(ctx.accounts.verify_result.result, VaultError::UnverifiedPrice);
+
+        let price = numeric_value(&value);
+        let timestamp = ctx.accounts.block.timestamp;
+        check_price(&ctx.accounts.vault, price, timestamp)?;
+
+        accept_deposit(
+            &mut ctx.accounts.vault,
+            &ctx.accounts.depositor,
+            &ctx.accounts.system_program,
+            amount,
+            price,
+            timestamp
+        )
     }
 
-    pub fn cpi_call_verify_false_tempered_value(
-        ctx: Context<CpiReturnContext>,
-        _seed: Vec<u8>
-    ) -> Result<()> {
+    /// Sends `amount` of the deposited lamports to the vault authority, keeping
+    /// the vault rent exempt.
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        let vault = ctx.accounts.vault.to_account_info();
+        let rent = Rent::get()?.minimum_balance(vault.data_len());
+        require!(
+            vault.lamports().saturating_sub(rent) >= amount,
+            VaultError::InsufficientFunds
+        );
+        **vault.try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.authority.try_borrow_mut_lamports()? += amount;
+
+        emit!(LogWithdrawal {
+            vault: vault.key(),
+            amount
+        });
 
-        let mut proofs = vec![[0u8; 32]; 12];
-        assert_eq!(hex::decode_to_slice("8aa4e4134178289504b4b6c7c85527b41905cf3d51ad95eaec44a87fbe773b82", &mut proofs[0] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("2555c92539183bfa28387c6e98403aeb44f8b7602d0580e4679f2432405b62b1", &mut proofs[1] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("6bb2d161e2d374a8aa779e0c61ecef7e82b7a6ba6543bf997212ea164c7ec540", &mut proofs[2] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("e3cd6c525d52487eb7439d1042dbd917a9b421fd2656a98a6f8af593fd4f4453", &mut proofs[3] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("39afef9403f6ccd794a1bf6c48a55a0d4164d8ab9f32992410f62629bd57a6b7", &mut proofs[4] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("72d0fddd950ac6ce7f54a48d4003843d526ee02fc21d8c305012bdd17f7058af", &mut proofs[5] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("fb1199eb1639a574b06bd4f2fc619a9004fb55dd9016c6b24c4c79498a24099f", &mut proofs[6] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("fa9e1fb3aa77f7249c18bd4dbd99bd9c3766a6bf6ab00eac7d5380732059566a", &mut proofs[7] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("81b18433beaada4ee9a058a3eb1580498a61789809abb60517ec0ca5e0bcf948", &mut proofs[8] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("a8440a4bf999006045d796a91e23fec4b23eee861ba9735d41dc804a76ae0643", &mut proofs[9] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("dcec74631415edf80085bdb0907dfb4dd6928db21ebe31b201b1c61cd5a6b412", &mut proofs[10] as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("e1c181e05f242407fcce79feb83cad315d8d86e5d668f8fa8586d92f7eab082e", &mut proofs[11] as &mut [u8]), Ok(()));
-
-        let mut key = [0u8; 32];
-        let mut value = [0u8; 32];
-        assert_eq!(hex::decode_to_slice("000000000000000000000000000000000000000000000031494e43482d444149", &mut key as &mut [u8]), Ok(()));
-        assert_eq!(hex::decode_to_slice("000000000000000000000000000000000000000000000000259ae7cedeadbeaf", &mut value as &mut [u8]), Ok(()));
-
-        let cpi_program = ctx.accounts.cpi_return_program.to_account_info();
-
-        let cpi_accounts = Verify {
-            block: ctx.accounts.block.to_account_info(),
-            verify_result: ctx.accounts.cpi_return.to_account_info(),
-        };
-
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        let result = chain::cpi::verify_proof_for_block(cpi_ctx, _seed, proofs, key, value)?;
         Ok(())
     }
 }
 
+/// Feed key as the chain program encodes it: left padded with zeros to 32 bytes,
+/// the seed of the feed's first class data account and its key in block trees.
+pub fn feed_key(key: &str) -> Result<[u8; 32]> {
+    require!(key.len() <= 32, VaultError::InvalidFeedKey);
+    let mut encoded = [0u8; 32];
+    encoded[32 - key.len()..].copy_from_slice(key.as_bytes());
+    Ok(encoded)
+}
+
+fn check_price(vault: &Vault, price: u128, timestamp: u32) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now - i64::from(timestamp) <= i64::from(vault.max_age),
+        VaultError::StalePrice
+    );
+    require!(
+        vault.min_price <= price && price <= vault.max_price,
+        VaultError::PriceOutOfBounds
+    );
+
+    Ok(())
+}
+
+fn accept_deposit<'info>(
+    vault: &mut Account<'info, Vault>,
+    depositor: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    amount: u64,
+    price: u128,
+    timestamp: u32
+) -> Result<()> {
+    require!(amount > 0, VaultError::ZeroDeposit);
+
+    system_program::transfer(
+        CpiContext::new(
+            system_program.to_account_info(),
+            system_program::Transfer {
+                from: depositor.to_account_info(),
+                to: vault.to_account_info()
+            }
+        ),
+        amount
+    )?;
+    vault.deposits = vault.deposits.saturating_add(amount);
+
+    msg!(
+        "LogDeposit: {:?} lamports by {:?} at price {:?} of {:?}",
+        amount,
+        depositor.key().to_string(),
+        price,
+        timestamp
+    );
+    emit!(LogDeposit {
+        vault: vault.key(),
+        depositor: depositor.key(),
+        amount,
+        price,
+        timestamp
+    });
+
+    Ok(())
+}
+
+#[account]
+#[derive(Default)]
+pub struct Vault {
+    pub authority: Pubkey,
+    // `feed_key` of the feed guarding deposits
+    pub key: [u8; 32],
+    pub min_price: u128,
+    pub max_price: u128,
+    pub max_age: u32,
+    // lamports deposited over the vault's lifetime
+    pub deposits: u64
+}
+
+impl Vault {
+    // space: 8 discriminator + 32 authority + 32 key + 16 min_price + 16 max_price
+    // + 4 max_age + 8 deposits
+    pub const SPACE: usize = 8 + 32 + 32 + 16 + 16 + 4 + 8;
+}
+
+#[error_code]
+pub enum VaultError {
+    InvalidFeedKey,
+    InvalidBounds,
+    ZeroDeposit,
+    StalePrice,
+    PriceOutOfBounds,
+    UnverifiedPrice,
+    InsufficientFunds,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogDeposit {
+    pub vault: Pubkey,
+    pub depositor: Pubkey,
+    pub amount: u64,
+    pub price: u128,
+    // data timestamp of the price
+    pub timestamp: u32,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogWithdrawal {
+    pub vault: Pubkey,
+    pub amount: u64,
+}
+
+#[derive(Accounts)]
+pub struct InitializeVault<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(init, payer = authority, space = Vault::SPACE)]
+    pub vault: Account<'info, Vault>,
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
-// #[instruction(seed: Vec<u8>)]
-pub struct CpiReturnContext<'info> {
+pub struct Deposit<'info> {
     #[account(mut)]
-    pub cpi_return: Account<'info, VerifyResult>,
-    //#[account(seeds = [&seed], bump)]
+    pub depositor: Signer<'info>,
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+    // the chain program's account of the vault's feed
+    #[account(seeds = [&vault.key], bump, seeds::program = chain::ID)]
+    pub fcd: Account<'info, FirstClassData>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DepositWithProof<'info> {
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+    // checked against the seed by `verify_proof_for_block`
     pub block: Account<'info, Block>,
+    pub status: Account<'info, Status>,
+    // written by `verify_proof_for_block`, initialized with `initialize_verify_result`
+    #[account(mut)]
+    pub verify_result: Account<'info, VerifyResult>,
+    pub chain_program: Program<'info, Chain>,
+    pub system_program: Program<'info, System>,
+}
 
-    pub cpi_return_program: Program<'info, Chain>,
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority)]
+    pub vault: Account<'info, Vault>,
 }
@@ -0,0 +1,21 @@
+#![no_main]
+
+use chain::merkle::*;
+use libfuzzer_sys::fuzz_target;
+
+// Any proof, including empty and very long ones, folds deterministically and
+// agrees with the off-chain reference implementation.
+fuzz_target!(|input: (Vec<[u8; 32]>, [u8; 32], [u8; 32])| {
+    let (proof, leaf, root) = input;
+
+    let computed = compute_root(&proof, leaf);
+    assert_eq!(computed, compute_root(&proof, leaf));
+    assert_eq!(computed, sorted_merkle_tree::compute_root(&proof, leaf));
+    assert_eq!(
+        verify_squashed_root(root, &proof, leaf),
+        extract_root(computed) == extract_root(root)
+    );
+    if proof.is_empty() {
+        assert_eq!(computed, leaf);
+    }
+});
@@ -0,0 +1,82 @@
+#![no_main]
+
+use std::collections::BTreeMap;
+
+use arbitrary::Arbitrary;
+use chain::merkle::*;
+use libfuzzer_sys::fuzz_target;
+use sorted_merkle_tree::{HashScheme as TreeScheme, SortedMerkleTree};
+
+#[derive(Arbitrary, Debug)]
+enum Mutation {
+    None,
+    Proof { element: usize, bit: u8 },
+    Key { bit: u8 },
+    Value { bit: u8 },
+    Root { bit: u8 },
+}
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    values: BTreeMap<u16, [u8; 32]>,
+    index: usize,
+    timestamp: u32,
+    prefixed: bool,
+    mutation: Mutation,
+}
+
+fn flip(hash: &mut [u8; 32], bit: u8) {
+    hash[usize::from(bit / 8)] ^= 1 << (bit % 8);
+}
+
+// Proofs from a reference tree verify, and any single bit flip outside the
+// timestamp bytes of the root makes them fail.
+fuzz_target!(|input: Input| {
+    if input.values.is_empty() {
+        return;
+    }
+    let data = input
+        .values
+        .iter()
+        .map(|(key, value)| (format!("KEY-{}", key), *value))
+        .collect::<BTreeMap<_, _>>();
+    let (scheme, tree_scheme) = if input.prefixed {
+        (HashScheme::Prefixed, TreeScheme::Prefixed)
+    } else {
+        (HashScheme::Legacy, TreeScheme::Legacy)
+    };
+    let tree = SortedMerkleTree::with_scheme(&data, tree_scheme).unwrap();
+    let key = data.keys().nth(input.index % data.len()).unwrap();
+    let proof = tree.proof(key).unwrap();
+    let mut root = tree.squashed_root(input.timestamp);
+    let (mut elements, mut key, mut value) = (proof.proof.clone(), proof.key, proof.value);
+
+    let expected = match input.mutation {
+        Mutation::None => true,
+        Mutation::Proof { element, bit } => {
+            if elements.is_empty() {
+                return;
+            }
+            let len = elements.len();
+            flip(&mut elements[element % len], bit);
+            false
+        }
+        Mutation::Key { bit } => {
+            flip(&mut key, bit);
+            false
+        }
+        Mutation::Value { bit } => {
+            flip(&mut value, bit);
+            false
+        }
+        Mutation::Root { bit } => {
+            flip(&mut root, bit);
+            usize::from(bit / 8) >= 32 - TIMESTAMP_BYTES
+        }
+    };
+
+    assert_eq!(
+        scheme.verify_squashed_root(root, &elements, scheme.hash_leaf(key, value)),
+        expected
+    );
+});
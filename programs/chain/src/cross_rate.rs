@@ -0,0 +1,106 @@
+//! Fixed-point rates derived from two feeds, such as SOL-ETH from SOL-USD and ETH-USD.
+
+use anchor_lang::prelude::*;
+use u512::U512;
+
+#[allow(clippy::assign_op_pattern, clippy::manual_div_ceil)]
+mod u512 {
+    uint::construct_uint! {
+        pub struct U512(8);
+    }
+}
+
+/// Decimals of derived rates, those of Umbrella prices.
+pub const CROSS_RATE_DECIMALS: u8 = 18;
+
+/// How `get_cross_rate` combines its `base` and `quote` feeds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CrossRateOperation {
+    /// `base / quote`, e.g. SOL-ETH from SOL-USD and ETH-USD.
+    Divide = 0,
+    /// `base * quote`, e.g. SOL-EUR from SOL-USD and USD-EUR.
+    Multiply = 1,
+    /// `1 / base`, e.g. USD-ETH from ETH-USD. `quote` only counts for the timestamp,
+    /// so callers pass `base` twice.
+    Invert = 2,
+}
+
+impl CrossRateOperation {
+    pub fn from_u8(operation: u8) -> Option<Self> {
+        match operation {
+            0 => Some(CrossRateOperation::Divide),
+            1 => Some(CrossRateOperation::Multiply),
+            2 => Some(CrossRateOperation::Invert),
+            _ => None,
+        }
+    }
+
+    /// Whether the operation would divide by a zero `base` or `quote`.
+    pub fn divides_by_zero(self, base: &[u8; 32], quote: &[u8; 32]) -> bool {
+        match self {
+            CrossRateOperation::Divide => *quote == [0u8; 32],
+            CrossRateOperation::Multiply => false,
+            CrossRateOperation::Invert => *base == [0u8; 32],
+        }
+    }
+}
+
+/// Return data of `get_cross_rate`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CrossRate {
+    // big endian, with `CROSS_RATE_DECIMALS`
+    pub value: [u8; 32],
+    // the older data timestamp of the two feeds
+    pub timestamp: u32,
+}
+
+/// Combines the big endian `base` and `quote` values, with their own decimals, into
+/// a rate with `CROSS_RATE_DECIMALS`, rounded down. Intermediate products are 512 bit
+/// wide; `None` when dividing by zero or when the rate doesn't fit in 256 bits.
+pub fn cross_rate(
+    operation: CrossRateOperation,
+    base: &[u8; 32],
+    base_decimals: u8,
+    quote: &[u8; 32],
+    quote_decimals: u8
+) -> Option<[u8; 32]> {
+    let base = U512::from_big_endian(base);
+    let quote = U512::from_big_endian(quote);
+    let decimals = i32::from(CROSS_RATE_DECIMALS);
+    let base_decimals = i32::from(base_decimals);
+    let quote_decimals = i32::from(quote_decimals);
+
+    let rate = match operation {
+        CrossRateOperation::Divide => {
+            scale(base, decimals + quote_decimals - base_decimals)?.checked_div(quote)?
+        }
+        CrossRateOperation::Multiply => {
+            scale(base.checked_mul(quote)?, decimals - base_decimals - quote_decimals)?
+        }
+        CrossRateOperation::Invert => pow10(decimals + base_decimals)?.checked_div(base)?,
+    };
+
+    let mut bytes = [0u8; 64];
+    rate.to_big_endian(&mut bytes);
+    if bytes[..32].iter().any(|byte| *byte != 0) {
+        return None;
+    }
+    let mut value = [0u8; 32];
+    value.copy_from_slice(&bytes[32..]);
+    Some(value)
+}
+
+/// `number * 10^exponent`, rounded down for negative exponents.
+fn scale(number: U512, exponent: i32) -> Option<U512> {
+    if exponent >= 0 {
+        number.checked_mul(pow10(exponent)?)
+    } else {
+        // past 10^154 the quotient of any 512 bit number is zero
+        Some(pow10(-exponent).map_or(U512::zero(), |divisor| number / divisor))
+    }
+}
+
+/// `10^exponent`, `None` when it doesn't fit in 512 bits.
+fn pow10(exponent: i32) -> Option<U512> {
+    (0..exponent).try_fold(U512::one(), |power, _| power.checked_mul(U512::from(10u8)))
+}
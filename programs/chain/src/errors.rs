@@ -8,4 +8,60 @@ pub enum ChainError {
     DoNotSpam,
     OnlyOwnerViolation,
     WrongFCDKeyForAccount,
+    UnknownHashScheme,
+    ProofTooLong,
+    StaleBlock,
+    Paused,
+    NotOwnerOrGuardian,
+    UnknownPauseFlags,
+    AlreadyMigrated,
+    FCDKeyCoolingDown,
+    FeedKeyTooLong,
+    FeedRegistryPageFull,
+    FeedAlreadyRegistered,
+    FeedNotRegistered,
+    UnknownValueKind,
+    FeedDescriptionTooLong,
+    InvalidHistoryCapacity,
+    MissingFCDHistory,
+    NoObservation,
+    InvalidTwapWindow,
+    TwapUnavailable,
+    TwapWindowNotCovered,
+    TooManyValidators,
+    DuplicateValidator,
+    InvalidQuorum,
+    InvalidRoundWindow,
+    NotValidator,
+    AlreadySubmitted,
+    AggregatedFeed,
+    UnknownCrossRateOperation,
+    CrossRateUnavailable,
+    CrossRateDivisionByZero,
+    CrossRateOverflow,
+    TooManyFeeExempt,
+    InvalidSubscriptionPeriod,
+    MissingFeeAccounts,
+    InvalidFeeVault,
+    WrongSubscription,
+    SubscriptionExpired,
+    SubscriptionsUnavailable,
+    InsufficientFees,
+    MissingRewardVault,
+    NoRewards,
+    InvalidSlashRate,
+    InvalidStakeVault,
+    InvalidStakeAmount,
+    InsufficientStake,
+    StakeUnbonding,
+    NotEquivocation,
+    MissingAttestationSignature,
+    InvalidEvmPayload,
+    EvmQuorumNotReached,
+    RootNotSubmitted,
+    NothingToSlash,
+    FeedNotAggregated,
+    InvalidBlockSeed,
+    RoundInProgress,
+    FutureTimestamp,
 }
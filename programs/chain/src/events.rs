@@ -0,0 +1,277 @@
+use anchor_lang::prelude::*;
+use crate::state::FeeSchedule;
+
+#[event]
+#[derive(Debug)]
+pub struct LogInitialization {
+    pub initializer: Pubkey,
+    pub padding: u32,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogBlockSubmitted {
+    pub block_id: u32,
+    pub root: [u8; 32],
+    pub timestamp: u32,
+    pub owner: Pubkey,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogFCDInitialized {
+    pub key: String,
+    pub value: [u8; 32],
+    pub timestamp: u32,
+    pub owner: Pubkey,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogFCDUpdated {
+    pub key: String,
+    pub value: [u8; 32],
+    pub timestamp: u32,
+    pub owner: Pubkey,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogFCDConfidenceUpdated {
+    pub key: String,
+    pub confidence: [u8; 32],
+    pub timestamp: u32,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogFCDClosed {
+    pub key: String,
+    pub recipient: Pubkey,
+    pub owner: Pubkey,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogFCDMetadataSet {
+    pub key: String,
+    pub decimals: u8,
+    pub kind: u8,
+    pub description: String,
+    pub owner: Pubkey,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogFCDHistoryInitialized {
+    pub key: String,
+    pub capacity: u16,
+    pub owner: Pubkey,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogValidatorsSet {
+    pub validators: Vec<Pubkey>,
+    pub quorum: u8,
+    pub window: u32,
+    pub owner: Pubkey,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogFCDAggregationInitialized {
+    pub key: String,
+    pub owner: Pubkey,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogFCDObservationSubmitted {
+    pub key: String,
+    pub value: [u8; 32],
+    pub timestamp: u32,
+    pub validator: Pubkey,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogFeeScheduleSet {
+    pub schedule: FeeSchedule,
+    pub exempt: Vec<Pubkey>,
+    pub enabled: bool,
+    pub owner: Pubkey,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogSubscribed {
+    pub consumer: Pubkey,
+    pub paid_until: i64,
+    pub payer: Pubkey,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogFeesWithdrawn {
+    pub amount: u64,
+    pub recipient: Pubkey,
+    pub owner: Pubkey,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogRewardsEnabledSet {
+    pub enabled: bool,
+    pub owner: Pubkey,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogRewardsDistributed {
+    pub amount: u64,
+    pub total_points: u64,
+    pub owner: Pubkey,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogRewardsClaimed {
+    pub key: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogStakeConfigSet {
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub treasury: Pubkey,
+    pub slash_bps: u16,
+    pub unbonding_period: u32,
+    pub owner: Pubkey,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogStaked {
+    pub validator: Pubkey,
+    pub amount: u64,
+    pub staked: u64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogUnstakeRequested {
+    pub validator: Pubkey,
+    pub amount: u64,
+    pub unbonding_until: i64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogStakeWithdrawn {
+    pub validator: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogEquivocationReported {
+    pub validator: Pubkey,
+    pub block_id: u32,
+    pub first_root: [u8; 32],
+    pub second_root: [u8; 32],
+    pub slashed: u64,
+    pub reporter: Pubkey,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogEvmValidatorsSet {
+    pub validators: Vec<[u8; 20]>,
+    pub quorum: u8,
+    pub owner: Pubkey,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogEvmBlockImported {
+    pub block_id: u32,
+    pub signers: Vec<[u8; 20]>,
+    pub importer: Pubkey,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogPaddingSet {
+    pub padding: u32,
+    pub owner: Pubkey,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogOwnershipChanged {
+    pub old_owner: Pubkey,
+    pub new_owner: Pubkey,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogMaxProofLengthSet {
+    pub max_proof_length: u8,
+    pub owner: Pubkey,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogGuardianSet {
+    pub guardian: Pubkey,
+    pub owner: Pubkey,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogPaused {
+    pub flags: u8,
+    pub paused: u8,
+    pub by: Pubkey,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogUnpaused {
+    pub flags: u8,
+    pub paused: u8,
+    pub by: Pubkey,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogMigrated {
+    pub version: u8,
+    pub owner: Pubkey,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogFeedRegistryPageInitialized {
+    pub page: u32,
+    pub owner: Pubkey,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogFeedRegistered {
+    pub key: String,
+    pub fcd: Pubkey,
+    pub page: u32,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct LogFeedUnregistered {
+    pub key: String,
+    pub fcd: Pubkey,
+    pub page: u32,
+}
@@ -0,0 +1,237 @@
+use crate::errors::ChainError;
+use crate::events::{
+    LogFCDAggregationInitialized, LogFCDConfidenceUpdated, LogFCDObservationSubmitted,
+    LogFCDUpdated, LogValidatorsSet
+};
+use crate::instructions::rewards::accrue_rewards;
+use crate::instructions::update_first_class_data::{check_update, set_value};
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+pub fn set_validators(
+    ctx: Context<SetValidators>,
+    validators: Vec<Pubkey>,
+    quorum: u8,
+    window: u32
+) -> Result<()> {
+    require!(
+        validators.len() <= MAX_VALIDATORS,
+        ChainError::TooManyValidators
+    );
+
+    for (i, validator) in validators.iter().enumerate() {
+        require!(
+            !validators[..i].contains(validator),
+            ChainError::DuplicateValidator
+        );
+    }
+
+    require!(
+        quorum > 0 && usize::from(quorum) <= validators.len(),
+        ChainError::InvalidQuorum
+    );
+
+    require!(window > 0, ChainError::InvalidRoundWindow);
+
+    let validator_set = &mut ctx.accounts.validator_set;
+    validator_set.validators = validators.clone();
+    validator_set.quorum = quorum;
+    validator_set.window = window;
+
+    msg!(
+        "LogValidatorsSet: {:?} validators, quorum {:?} by {:?}",
+        validators.len(),
+        quorum,
+        ctx.accounts.owner.key().to_string()
+    );
+    emit!(LogValidatorsSet {
+        validators,
+        quorum,
+        window,
+        owner: ctx.accounts.owner.key()
+    });
+
+    Ok(())
+}
+
+pub fn initialize_fcd_aggregation(
+    ctx: Context<InitializeFcdAggregation>,
+    key: String
+) -> Result<()> {
+    require!(
+        ctx.accounts.fcd.key == key,
+        ChainError::WrongFCDKeyForAccount
+    );
+
+    ctx.accounts.round.fcd = ctx.accounts.fcd.key();
+    ctx.accounts.fcd.aggregated = true;
+
+    msg!(
+        "LogFCDAggregationInitialized: {:?} by {:?}",
+        key,
+        ctx.accounts.owner.key().to_string()
+    );
+    emit!(LogFCDAggregationInitialized {
+        key,
+        owner: ctx.accounts.owner.key()
+    });
+
+    Ok(())
+}
+
+/// Adds the validator's value for `timestamp` to the feed's round, and sets the FCD to
+/// the median once the round reaches the quorum. While a round is open, values for
+/// other timestamps are refused; one that doesn't reach the quorum within the window
+/// is dropped.
+pub fn submit_fcd_observation(
+    ctx: Context<SubmitFcdObservation>,
+    key: String,
+    value: [u8; 32],
+    timestamp: u32
+) -> Result<()> {
+    let validator = ctx.accounts.validator.key();
+    let validator_set = &ctx.accounts.validator_set;
+    require!(
+        validator_set.contains(&validator),
+        ChainError::NotValidator
+    );
+
+    require!(
+        ctx.accounts.fcd.key == key,
+        ChainError::WrongFCDKeyForAccount
+    );
+
+    require!(ctx.accounts.fcd.aggregated, ChainError::FeedNotAggregated);
+
+    check_update(&ctx.accounts.fcd, &ctx.accounts.status, timestamp)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(i64::from(timestamp) <= now, ChainError::FutureTimestamp);
+
+    let round = &mut ctx.accounts.round;
+    if !round.is_open(now, validator_set.window) {
+        round.timestamp = timestamp;
+        round.started_at = now;
+        round.submissions.clear();
+    }
+
+    // a single validator can't drop the others' values by moving on to another timestamp
+    require!(round.timestamp == timestamp, ChainError::RoundInProgress);
+
+    require!(
+        round.submissions.iter().all(|submission| submission.validator != validator),
+        ChainError::AlreadySubmitted
+    );
+
+    round.submissions.push(Submission { validator, value });
+    emit!(LogFCDObservationSubmitted {
+        key: key.clone(),
+        value,
+        timestamp,
+        validator
+    });
+
+    if round.submissions.len() < usize::from(validator_set.quorum) {
+        return Ok(());
+    }
+
+    let median = round.median().ok_or(ChainError::InvalidQuorum)?;
+    // raw values have no distance between them to report
+    let confidence = if ctx.accounts.fcd.resolved_metadata().kind == ValueKind::Raw as u8 {
+        None
+    } else {
+        Some(round.half_spread().ok_or(ChainError::InvalidQuorum)?)
+    };
+    let signers: Vec<Pubkey> = round.submissions.iter().map(|submission| submission.validator).collect();
+    round.submissions.clear();
+    let address = ctx.accounts.fcd.key();
+    set_value(&mut ctx.accounts.fcd, address, median, timestamp, confidence, ctx.remaining_accounts)?;
+    accrue_rewards(&ctx.accounts.status, &signers, ctx.remaining_accounts)?;
+
+    msg!(
+        "LogFCDUpdated: {:?} by {:?} with timestamp {:?}",
+        key,
+        validator.to_string(),
+        timestamp
+    );
+    emit!(LogFCDUpdated {
+        key: key.clone(),
+        value: median,
+        timestamp,
+        owner: validator
+    });
+
+    if let Some(confidence) = confidence {
+        emit!(LogFCDConfidenceUpdated {
+            key,
+            confidence,
+            timestamp
+        });
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetValidators<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner @ ChainError::OnlyOwnerViolation,
+    )]
+    pub authority: Account<'info, Authority>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = ValidatorSet::SPACE,
+        seeds = [b"validators"],
+        bump
+    )]
+    pub validator_set: Account<'info, ValidatorSet>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeFcdAggregation<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner @ ChainError::OnlyOwnerViolation,
+    )]
+    pub authority: Account<'info, Authority>,
+
+    #[account(mut)]
+    pub fcd: Account<'info, FirstClassData>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = FcdRound::SPACE,
+        seeds = [b"round", fcd.key().as_ref()],
+        bump
+    )]
+    pub round: Account<'info, FcdRound>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitFcdObservation<'info> {
+    pub validator: Signer<'info>,
+
+    #[account(seeds = [b"validators"], bump)]
+    pub validator_set: Account<'info, ValidatorSet>,
+
+    #[account(seeds = [b"status"], bump)]
+    pub status: Account<'info, Status>,
+
+    // followed by the feed's `FcdHistory` as a remaining account when it has one,
+    // then the `RewardVault` while rewards are enabled
+    #[account(mut)]
+    pub fcd: Account<'info, FirstClassData>,
+
+    #[account(mut, seeds = [b"round", fcd.key().as_ref()], bump)]
+    pub round: Account<'info, FcdRound>,
+}
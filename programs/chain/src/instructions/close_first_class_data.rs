@@ -0,0 +1,102 @@
+use crate::errors::ChainError;
+use crate::events::LogFCDClosed;
+use crate::instructions::fcd_history::fcd_history;
+use crate::instructions::feed_registry::remove_feed;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+pub fn close_first_class_data(
+    ctx: Context<CloseFirstClassData>,
+    key: String
+) -> Result<()> {
+    require!(
+        ctx.accounts.fcd.key == key,
+        ChainError::WrongFCDKeyForAccount
+    );
+
+    // feeds initialized before the registry existed aren't listed on any page
+    if ctx.accounts.fcd.registry_page.is_some() {
+        let info = ctx.accounts.registry.to_account_info();
+        let mut registry = FeedRegistry::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        remove_feed(&mut registry, &key)?;
+        registry.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+    }
+
+    // the history goes with the feed, so that a re-initialized key starts a new one
+    let recipient = ctx.accounts.recipient.to_account_info();
+    if let Some((history, _)) = fcd_history(&ctx.accounts.fcd, ctx.accounts.fcd.key(), ctx.remaining_accounts)? {
+        close_into(history, &recipient)?;
+    }
+    // and so does the round, which validators could otherwise keep writing to
+    if ctx.accounts.fcd.aggregated {
+        close_into(&ctx.accounts.round.to_account_info(), &recipient)?;
+    }
+    ctx.accounts.closed.closed_at = Clock::get()?.unix_timestamp;
+
+    msg!(
+        "LogFCDClosed: {:?} by {:?} to {:?}",
+        key,
+        ctx.accounts.owner.key().to_string(),
+        ctx.accounts.recipient.key().to_string()
+    );
+    emit!(LogFCDClosed {
+        key,
+        recipient: ctx.accounts.recipient.key(),
+        owner: ctx.accounts.owner.key()
+    });
+
+    Ok(())
+}
+
+/// Moves the rent of `account` to `recipient` and clears its data, so the runtime
+/// removes it at the end of the transaction.
+pub(crate) fn close_into(account: &AccountInfo, recipient: &AccountInfo) -> Result<()> {
+    **recipient.try_borrow_mut_lamports()? += account.lamports();
+    **account.try_borrow_mut_lamports()? = 0;
+    account.try_borrow_mut_data()?.fill(0);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(key: String)]
+pub struct CloseFirstClassData<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner @ ChainError::OnlyOwnerViolation,
+    )]
+    pub authority: Account<'info, Authority>,
+
+    // followed by the feed's `FcdHistory` as a remaining account when it has one
+    #[account(mut, close = recipient)]
+    pub fcd: Account<'info, FirstClassData>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = ClosedFirstClassData::SPACE,
+        seeds = [b"closed", fcd.key().as_ref()],
+        bump
+    )]
+    pub closed: Account<'info, ClosedFirstClassData>,
+
+    /// CHECK: the `FeedRegistry` page listing the feed, or that of page 0, left alone
+    /// and possibly not existing, for a feed on no page
+    #[account(
+        mut,
+        seeds = [b"feed_registry".as_ref(), &fcd.registry_page.unwrap_or_default().to_le_bytes()],
+        bump
+    )]
+    pub registry: UncheckedAccount<'info>,
+
+    /// CHECK: the feed's `FcdRound`, closed along with an aggregated feed and left
+    /// alone, possibly not existing, otherwise
+    #[account(mut, seeds = [b"round", fcd.key().as_ref()], bump)]
+    pub round: UncheckedAccount<'info>,
+
+    /// CHECK: any account can receive the rent of the closed FCD
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,56 @@
+use crate::cross_rate::{cross_rate, CrossRate, CrossRateOperation};
+use crate::errors::ChainError;
+use crate::instructions::fees::charge_fee;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::set_return_data;
+
+/// Sets the rate derived from the `base` and `quote` feeds by `operation` as
+/// return data, a Borsh `CrossRate` with the older of their timestamps.
+pub fn get_cross_rate(
+    ctx: Context<GetCrossRate>,
+    operation: u8
+) -> Result<()> {
+    charge_fee(&ctx.accounts.status, FeeKind::Read, ctx.remaining_accounts)?;
+
+    let operation = CrossRateOperation::from_u8(operation)
+        .ok_or(ChainError::UnknownCrossRateOperation)?;
+
+    let base = &ctx.accounts.base;
+    let quote = &ctx.accounts.quote;
+    let base_metadata = base.resolved_metadata();
+    let quote_metadata = quote.resolved_metadata();
+    require!(
+        base_metadata.kind != ValueKind::Raw as u8 && quote_metadata.kind != ValueKind::Raw as u8,
+        ChainError::CrossRateUnavailable
+    );
+
+    require!(
+        !operation.divides_by_zero(&base.value, &quote.value),
+        ChainError::CrossRateDivisionByZero
+    );
+
+    let value = cross_rate(
+        operation,
+        &base.value,
+        base_metadata.decimals,
+        &quote.value,
+        quote_metadata.decimals
+    ).ok_or(ChainError::CrossRateOverflow)?;
+
+    let rate = CrossRate {
+        value,
+        timestamp: base.timestamp.min(quote.timestamp)
+    };
+    set_return_data(&rate.try_to_vec()?);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct GetCrossRate<'info> {
+    pub base: Account<'info, FirstClassData>,
+    pub quote: Account<'info, FirstClassData>,
+    #[account(seeds = [b"status"], bump)]
+    pub status: Account<'info, Status>,
+}
@@ -0,0 +1,205 @@
+use crate::errors::ChainError;
+use crate::events::{LogBlockSubmitted, LogEvmBlockImported, LogEvmValidatorsSet};
+use crate::instructions::submit::store_block;
+use crate::merkle::HashScheme;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::secp256k1_program;
+use anchor_lang::solana_program::sysvar::instructions::load_instruction_at_checked;
+
+// layout of secp256k1 program instruction data: a signature count, then per signature
+// a u16 signature offset, u8 instruction index, u16 address offset, u8 instruction
+// index, u16 message offset, u16 message size and u8 instruction index
+const SECP256K1_OFFSETS_START: usize = 1;
+const SECP256K1_OFFSETS_SIZE: usize = 11;
+
+/// Replaces the Ethereum addresses of the sidechain validators whose signatures
+/// `import_evm_block` counts.
+pub fn set_evm_validators(
+    ctx: Context<SetEvmValidators>,
+    validators: Vec<[u8; 20]>,
+    quorum: u8
+) -> Result<()> {
+    require!(
+        validators.len() <= MAX_VALIDATORS,
+        ChainError::TooManyValidators
+    );
+
+    for (i, validator) in validators.iter().enumerate() {
+        require!(
+            !validators[..i].contains(validator),
+            ChainError::DuplicateValidator
+        );
+    }
+
+    require!(
+        quorum > 0 && usize::from(quorum) <= validators.len(),
+        ChainError::InvalidQuorum
+    );
+
+    let evm_validator_set = &mut ctx.accounts.evm_validator_set;
+    evm_validator_set.validators = validators.clone();
+    evm_validator_set.quorum = quorum;
+
+    msg!(
+        "LogEvmValidatorsSet: {:?} validators, quorum {:?} by {:?}",
+        validators.len(),
+        quorum,
+        ctx.accounts.owner.key().to_string()
+    );
+    emit!(LogEvmValidatorsSet {
+        validators,
+        quorum,
+        owner: ctx.accounts.owner.key()
+    });
+
+    Ok(())
+}
+
+/// Stores the block of an ABI-encoded sidechain header signed by a quorum of the
+/// EVM validators. Their signatures have to be checked by secp256k1 program
+/// instructions of the same transaction, over `evm_signed_message(payload)`. Anyone
+/// can import a block, which is then checked against the status like a `submit`.
+pub fn import_evm_block(
+    ctx: Context<ImportEvmBlock>,
+    seed: Vec<u8>,
+    payload: Vec<u8>
+) -> Result<()> {
+    let header = EvmBlockHeader::decode(&payload).ok_or(ChainError::InvalidEvmPayload)?;
+    require!(
+        seed == Block::seed(header.block_id),
+        ChainError::InvalidEvmPayload
+    );
+
+    let message = evm_signed_message(&payload);
+    let mut signers = secp256k1_signers(&ctx.accounts.instructions, &message)?;
+    signers.retain(|signer| ctx.accounts.evm_validator_set.contains(signer));
+    signers.sort_unstable();
+    signers.dedup();
+    require!(
+        signers.len() >= usize::from(ctx.accounts.evm_validator_set.quorum),
+        ChainError::EvmQuorumNotReached
+    );
+
+    store_block(
+        &mut ctx.accounts.status,
+        &mut ctx.accounts.block,
+        header.block_id,
+        header.root,
+        header.timestamp,
+        HashScheme::Legacy as u8
+    )?;
+
+    let importer = ctx.accounts.importer.key();
+    msg!(
+        "LogEvmBlockImported: {:?} signed by {:?} validators, imported by {:?}",
+        header.block_id,
+        signers.len(),
+        importer.to_string()
+    );
+    emit!(LogBlockSubmitted {
+        block_id: header.block_id,
+        root: header.root,
+        timestamp: header.timestamp,
+        owner: importer
+    });
+    emit!(LogEvmBlockImported {
+        block_id: header.block_id,
+        signers,
+        importer
+    });
+
+    Ok(())
+}
+
+/// Ethereum addresses that secp256k1 program instructions of the transaction
+/// recovered from signatures of `message`. The runtime rejects the whole transaction
+/// when any of those signatures doesn't match its address, so finding the address
+/// and message among the checked data is enough.
+fn secp256k1_signers(instructions: &AccountInfo, message: &[u8]) -> Result<Vec<[u8; 20]>> {
+    let mut signers = vec![];
+    let mut index = 0;
+    while let Ok(instruction) = load_instruction_at_checked(index, instructions) {
+        if instruction.program_id == secp256k1_program::ID {
+            signers.extend(secp256k1_checks(&instruction.data, index, message));
+        }
+        index += 1;
+    }
+
+    Ok(signers)
+}
+
+/// Addresses of the signatures over `message` checked by the secp256k1 program
+/// instruction at `index` with `data`, with the address and message in the
+/// instruction itself.
+fn secp256k1_checks(data: &[u8], index: usize, message: &[u8]) -> Vec<[u8; 20]> {
+    let count = data.first().copied().unwrap_or(0);
+    (0..usize::from(count))
+        .filter_map(|i| {
+            let start = SECP256K1_OFFSETS_START + i * SECP256K1_OFFSETS_SIZE;
+            let offsets = data.get(start..start + SECP256K1_OFFSETS_SIZE)?;
+            let offset = |at: usize| usize::from(u16::from_le_bytes([offsets[at], offsets[at + 1]]));
+            // address and message read from the instruction's own data
+            if usize::from(offsets[5]) != index || usize::from(offsets[10]) != index {
+                return None;
+            }
+
+            let (address, message_start, message_size) = (offset(3), offset(6), offset(8));
+            if data.get(message_start..message_start + message_size) != Some(message) {
+                return None;
+            }
+            let mut signer = [0u8; 20];
+            signer.copy_from_slice(data.get(address..address + 20)?);
+            Some(signer)
+        })
+        .collect()
+}
+
+#[derive(Accounts)]
+pub struct SetEvmValidators<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner @ ChainError::OnlyOwnerViolation,
+    )]
+    pub authority: Account<'info, Authority>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = EvmValidatorSet::SPACE,
+        seeds = [b"evm_validators"],
+        bump
+    )]
+    pub evm_validator_set: Account<'info, EvmValidatorSet>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(seed: Vec<u8>)]
+pub struct ImportEvmBlock<'info> {
+    #[account(mut)]
+    pub importer: Signer<'info>,
+
+    #[account(seeds = [b"evm_validators"], bump)]
+    pub evm_validator_set: Account<'info, EvmValidatorSet>,
+
+    // space: 8 discriminator + 4 block_id + 32 root + 4 timestamp + 1 hash_scheme
+    #[account(
+        init,
+        payer = importer,
+        space = 8 + 4 + 32 + 4 + 1,
+        seeds = [&seed],
+        bump
+    )]
+    pub block: Account<'info, Block>,
+
+    #[account(mut)]
+    pub status: Account<'info, Status>,
+
+    /// CHECK: the instructions sysvar, holding the secp256k1 program instructions
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
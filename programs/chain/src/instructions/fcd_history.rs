@@ -0,0 +1,131 @@
+use crate::errors::ChainError;
+use crate::events::LogFCDHistoryInitialized;
+use crate::instructions::fees::charge_fee;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::set_return_data;
+
+pub fn initialize_fcd_history(
+    ctx: Context<InitializeFcdHistory>,
+    key: String,
+    capacity: u16
+) -> Result<()> {
+    require!(
+        ctx.accounts.fcd.key == key,
+        ChainError::WrongFCDKeyForAccount
+    );
+
+    let fcd = &mut ctx.accounts.fcd;
+    let history = &mut ctx.accounts.history;
+    history.fcd = fcd.key();
+    history.capacity = capacity;
+    if fcd.timestamp != 0 {
+        history.push(Observation {
+            value: fcd.value,
+            timestamp: fcd.timestamp,
+            cumulative: fcd.cumulative
+        });
+    }
+    fcd.has_history = true;
+
+    msg!(
+        "LogFCDHistoryInitialized: {:?} by {:?}",
+        key,
+        ctx.accounts.owner.key().to_string()
+    );
+    emit!(LogFCDHistoryInitialized {
+        key,
+        capacity,
+        owner: ctx.accounts.owner.key()
+    });
+
+    Ok(())
+}
+
+/// Sets the Borsh `Observation` at or before `timestamp` as return data.
+pub fn get_fcd_observation(
+    ctx: Context<GetFcdObservation>,
+    timestamp: u32
+) -> Result<()> {
+    charge_fee(&ctx.accounts.status, FeeKind::Read, ctx.remaining_accounts)?;
+
+    let observation = ctx.accounts.history
+        .at_or_before(timestamp)
+        .ok_or(ChainError::NoObservation)?;
+    set_return_data(&observation.try_to_vec()?);
+
+    Ok(())
+}
+
+/// The `FcdHistory` of the FCD at `address`, expected as the first remaining
+/// account when the feed has one.
+pub(crate) fn fcd_history<'a, 'info>(
+    fcd: &FirstClassData,
+    address: Pubkey,
+    remaining_accounts: &'a [AccountInfo<'info>]
+) -> Result<Option<(&'a AccountInfo<'info>, FcdHistory)>> {
+    if !fcd.has_history {
+        return Ok(None);
+    }
+
+    let info = remaining_accounts.first().ok_or(ChainError::MissingFCDHistory)?;
+    require!(info.owner == &crate::ID, ChainError::MissingFCDHistory);
+    let history = FcdHistory::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    require!(history.fcd == address, ChainError::MissingFCDHistory);
+
+    Ok(Some((info, history)))
+}
+
+/// Appends the current value of `fcd` to its history, if it has one.
+pub(crate) fn record_observation(
+    fcd: &FirstClassData,
+    address: Pubkey,
+    remaining_accounts: &[AccountInfo]
+) -> Result<()> {
+    if let Some((info, mut history)) = fcd_history(fcd, address, remaining_accounts)? {
+        history.push(Observation {
+            value: fcd.value,
+            timestamp: fcd.timestamp,
+            cumulative: fcd.cumulative
+        });
+        history.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(key: String, capacity: u16)]
+pub struct InitializeFcdHistory<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner @ ChainError::OnlyOwnerViolation,
+    )]
+    pub authority: Account<'info, Authority>,
+
+    // checked before `history` is allocated with `capacity`
+    #[account(
+        mut,
+        constraint = capacity > 0 && capacity <= MAX_HISTORY_LENGTH @ ChainError::InvalidHistoryCapacity,
+    )]
+    pub fcd: Account<'info, FirstClassData>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = FcdHistory::space(capacity),
+        seeds = [b"history", fcd.key().as_ref()],
+        bump
+    )]
+    pub history: Account<'info, FcdHistory>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GetFcdObservation<'info> {
+    pub history: Account<'info, FcdHistory>,
+    #[account(seeds = [b"status"], bump)]
+    pub status: Account<'info, Status>,
+}
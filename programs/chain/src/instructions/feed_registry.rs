@@ -0,0 +1,136 @@
+use crate::errors::ChainError;
+use crate::events::{LogFeedRegistered, LogFeedRegistryPageInitialized, LogFeedUnregistered};
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::set_return_data;
+
+pub fn initialize_feed_registry_page(
+    ctx: Context<InitializeFeedRegistryPage>,
+    page: u32
+) -> Result<()> {
+    ctx.accounts.registry.page = page;
+    msg!(
+        "LogFeedRegistryPageInitialized: {:?} by {:?}",
+        page,
+        ctx.accounts.owner.key().to_string()
+    );
+    emit!(LogFeedRegistryPageInitialized {
+        page,
+        owner: ctx.accounts.owner.key()
+    });
+
+    Ok(())
+}
+
+pub fn register_first_class_data(
+    ctx: Context<RegisterFirstClassData>,
+    key: String
+) -> Result<()> {
+    require!(
+        ctx.accounts.fcd.key == key,
+        ChainError::WrongFCDKeyForAccount
+    );
+
+    // pages only see their own entries, the FCD knows whether any lists it
+    require!(
+        ctx.accounts.fcd.registry_page.is_none(),
+        ChainError::FeedAlreadyRegistered
+    );
+
+    let metadata = ctx.accounts.fcd.resolved_metadata();
+    add_feed(&mut ctx.accounts.registry, key, ctx.accounts.fcd.key(), metadata)?;
+    ctx.accounts.fcd.registry_page = Some(ctx.accounts.registry.page);
+
+    Ok(())
+}
+
+pub fn get_registered_feeds(
+    ctx: Context<GetRegisteredFeeds>,
+    start: u32,
+    limit: u32
+) -> Result<()> {
+    let entries = ctx.accounts.registry.returned_entries(start as usize, limit as usize);
+    set_return_data(&entries.try_to_vec()?);
+
+    Ok(())
+}
+
+pub(crate) fn add_feed(
+    registry: &mut FeedRegistry,
+    key: String,
+    fcd: Pubkey,
+    metadata: FeedMetadata
+) -> Result<()> {
+    require!(key.len() <= MAX_FEED_KEY_LENGTH, ChainError::FeedKeyTooLong);
+    require!(registry.entries.len() < FEEDS_PER_PAGE, ChainError::FeedRegistryPageFull);
+    require!(registry.position(&key).is_none(), ChainError::FeedAlreadyRegistered);
+
+    msg!("LogFeedRegistered: {:?} on page {:?}", key, registry.page);
+    emit!(LogFeedRegistered {
+        key: key.clone(),
+        fcd,
+        page: registry.page
+    });
+    registry.entries.push(FeedEntry { key, fcd, metadata });
+
+    Ok(())
+}
+
+pub(crate) fn remove_feed(
+    registry: &mut FeedRegistry,
+    key: &str
+) -> Result<()> {
+    let position = registry.position(key).ok_or(ChainError::FeedNotRegistered)?;
+    let entry = registry.entries.swap_remove(position);
+
+    msg!("LogFeedUnregistered: {:?} from page {:?}", key, registry.page);
+    emit!(LogFeedUnregistered {
+        key: entry.key,
+        fcd: entry.fcd,
+        page: registry.page
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(page: u32)]
+pub struct InitializeFeedRegistryPage<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner @ ChainError::OnlyOwnerViolation,
+    )]
+    pub authority: Account<'info, Authority>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = FeedRegistry::SPACE,
+        seeds = [b"feed_registry".as_ref(), &page.to_le_bytes()],
+        bump
+    )]
+    pub registry: Account<'info, FeedRegistry>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterFirstClassData<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner @ ChainError::OnlyOwnerViolation,
+    )]
+    pub authority: Account<'info, Authority>,
+
+    #[account(mut)]
+    pub fcd: Account<'info, FirstClassData>,
+    #[account(mut)]
+    pub registry: Account<'info, FeedRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct GetRegisteredFeeds<'info> {
+    pub registry: Account<'info, FeedRegistry>,
+}
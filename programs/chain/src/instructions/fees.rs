@@ -0,0 +1,323 @@
+use crate::errors::ChainError;
+use crate::events::{LogFeeScheduleSet, LogFeesWithdrawn, LogSubscribed};
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::solana_program::system_instruction;
+use anchor_lang::system_program;
+
+pub fn set_fee_schedule(
+    ctx: Context<SetFeeSchedule>,
+    schedule: FeeSchedule,
+    exempt: Vec<Pubkey>,
+    enabled: bool
+) -> Result<()> {
+    require!(
+        exempt.len() <= MAX_FEE_EXEMPT,
+        ChainError::TooManyFeeExempt
+    );
+
+    require!(
+        schedule.subscription_price == 0 || schedule.subscription_period > 0,
+        ChainError::InvalidSubscriptionPeriod
+    );
+
+    let fee_config = &mut ctx.accounts.fee_config;
+    fee_config.schedule = schedule.clone();
+    fee_config.exempt = exempt.clone();
+    ctx.accounts.status.fees_enabled = enabled;
+
+    msg!(
+        "LogFeeScheduleSet: enabled {:?} by {:?}",
+        enabled,
+        ctx.accounts.owner.key().to_string()
+    );
+    emit!(LogFeeScheduleSet {
+        schedule,
+        exempt,
+        enabled,
+        owner: ctx.accounts.owner.key()
+    });
+
+    Ok(())
+}
+
+/// Extends the subscription of `consumer` by `periods` subscription periods,
+/// from now if it has lapsed. Token fees take the payer's token account and a
+/// fee vault, then the token program, as remaining accounts.
+pub fn subscribe<'info>(
+    ctx: Context<'_, '_, '_, 'info, Subscribe<'info>>,
+    periods: u32
+) -> Result<()> {
+    let schedule = &ctx.accounts.fee_config.schedule;
+    require!(
+        schedule.subscription_price > 0,
+        ChainError::SubscriptionsUnavailable
+    );
+
+    let amount = schedule.subscription_price
+        .checked_mul(periods.into())
+        .filter(|amount| *amount > 0)
+        .ok_or(ChainError::InvalidSubscriptionPeriod)?;
+    let payment_accounts = if schedule.in_tokens() {
+        ctx.remaining_accounts.to_vec()
+    } else {
+        vec![ctx.accounts.system_program.to_account_info()]
+    };
+    pay(
+        schedule,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.fee_config.to_account_info(),
+        amount,
+        &payment_accounts
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let period = i64::from(schedule.subscription_period) * i64::from(periods);
+    let subscription = &mut ctx.accounts.subscription;
+    subscription.consumer = ctx.accounts.consumer.key();
+    subscription.paid_until = subscription.paid_until.max(now) + period;
+
+    msg!(
+        "LogSubscribed: {:?} until {:?}",
+        subscription.consumer.to_string(),
+        subscription.paid_until
+    );
+    emit!(LogSubscribed {
+        consumer: subscription.consumer,
+        paid_until: subscription.paid_until,
+        payer: ctx.accounts.payer.key()
+    });
+
+    Ok(())
+}
+
+/// Sends `amount` of the collected fees to `recipient`. Lamports are withdrawn down
+/// to the rent exemption of the config. Token fees are withdrawn from the fee vault
+/// passed as a remaining account, followed by the token program, to the `recipient`
+/// token account.
+pub fn withdraw_fees<'info>(
+    ctx: Context<'_, '_, '_, 'info, WithdrawFees<'info>>,
+    amount: u64
+) -> Result<()> {
+    let fee_config = ctx.accounts.fee_config.to_account_info();
+    let recipient = ctx.accounts.recipient.to_account_info();
+
+    if ctx.accounts.fee_config.schedule.in_tokens() {
+        let (vault, token_program) = match ctx.remaining_accounts {
+            [vault, token_program, ..] => (vault, token_program),
+            _ => return err!(ChainError::MissingFeeAccounts)
+        };
+        fee_vault(vault, token_program, &fee_config.key(), None)?;
+
+        let bump = *ctx.bumps.get("fee_config").ok_or(ChainError::InvalidFeeVault)?;
+        invoke_signed(
+            &spl_token::instruction::transfer(
+                token_program.key,
+                vault.key,
+                recipient.key,
+                fee_config.key,
+                &[],
+                amount
+            )?,
+            &[vault.clone(), recipient.clone(), fee_config.clone(), token_program.clone()],
+            &[&[b"fee_config", &[bump]]]
+        )?;
+    } else {
+        let rent = Rent::get()?.minimum_balance(fee_config.data_len());
+        require!(
+            fee_config.lamports().saturating_sub(rent) >= amount,
+            ChainError::InsufficientFees
+        );
+        **fee_config.try_borrow_mut_lamports()? -= amount;
+        **recipient.try_borrow_mut_lamports()? += amount;
+    }
+
+    msg!(
+        "LogFeesWithdrawn: {:?} to {:?}",
+        amount,
+        recipient.key().to_string()
+    );
+    emit!(LogFeesWithdrawn {
+        amount,
+        recipient: recipient.key(),
+        owner: ctx.accounts.owner.key()
+    });
+
+    Ok(())
+}
+
+/// Charges the fee of a `kind` call while fees are enabled. The call then takes
+/// the `FeeConfig` and the paying signer as its first remaining accounts,
+/// followed by
+/// - nothing for exempt payers,
+/// - the payer's `Subscription`, which has to be active, or
+/// - the system program for lamport fees, or the payer's token account, a fee
+///   vault and the token program for token fees.
+pub(crate) fn charge_fee(
+    status: &Status,
+    kind: FeeKind,
+    remaining_accounts: &[AccountInfo]
+) -> Result<()> {
+    if !status.fees_enabled {
+        return Ok(());
+    }
+
+    let (config_info, payer, payment_accounts) = match remaining_accounts {
+        [config_info, payer, payment_accounts @ ..] => (config_info, payer, payment_accounts),
+        _ => return err!(ChainError::MissingFeeAccounts)
+    };
+    // `FeeConfig` accounts are only created by `set_fee_schedule`, at `[b"fee_config"]`
+    require!(config_info.owner == &crate::ID, ChainError::MissingFeeAccounts);
+    let fee_config = FeeConfig::try_deserialize(&mut &config_info.try_borrow_data()?[..])?;
+    require!(payer.is_signer, ChainError::MissingFeeAccounts);
+
+    let amount = fee_config.schedule.fee(kind);
+    if amount == 0 || fee_config.is_exempt(payer.key) {
+        return Ok(());
+    }
+
+    match payment_accounts.first() {
+        Some(info) if info.owner == &crate::ID => {
+            let subscription = Subscription::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+            require!(
+                subscription.consumer == payer.key(),
+                ChainError::WrongSubscription
+            );
+            require!(
+                subscription.is_active(Clock::get()?.unix_timestamp),
+                ChainError::SubscriptionExpired
+            );
+            Ok(())
+        }
+        _ => pay(&fee_config.schedule, payer, config_info, amount, payment_accounts)
+    }
+}
+
+/// Moves `amount` from `payer` to the fees collected under `schedule`.
+fn pay<'info>(
+    schedule: &FeeSchedule,
+    payer: &AccountInfo<'info>,
+    fee_config: &AccountInfo<'info>,
+    amount: u64,
+    payment_accounts: &[AccountInfo<'info>]
+) -> Result<()> {
+    if schedule.in_tokens() {
+        let (source, vault, token_program) = match payment_accounts {
+            [source, vault, token_program, ..] => (source, vault, token_program),
+            _ => return err!(ChainError::MissingFeeAccounts)
+        };
+        fee_vault(vault, token_program, fee_config.key, Some(&schedule.mint))?;
+
+        invoke(
+            &spl_token::instruction::transfer(
+                token_program.key,
+                source.key,
+                vault.key,
+                payer.key,
+                &[],
+                amount
+            )?,
+            &[source.clone(), vault.clone(), payer.clone(), token_program.clone()]
+        )?;
+    } else {
+        let system_program = payment_accounts.first().ok_or(ChainError::MissingFeeAccounts)?;
+        require!(
+            system_program.key == &system_program::ID,
+            ChainError::MissingFeeAccounts
+        );
+
+        invoke(
+            &system_instruction::transfer(payer.key, fee_config.key, amount),
+            &[payer.clone(), fee_config.clone(), system_program.clone()]
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Checks `vault` is a token account of `mint`, if given, with the fee config
+/// as its authority.
+fn fee_vault(
+    vault: &AccountInfo,
+    token_program: &AccountInfo,
+    fee_config: &Pubkey,
+    mint: Option<&Pubkey>
+) -> Result<()> {
+    require!(
+        token_program.key == &spl_token::ID && vault.owner == &spl_token::ID,
+        ChainError::InvalidFeeVault
+    );
+
+    let account = spl_token::state::Account::unpack(&vault.try_borrow_data()?)?;
+    require!(account.owner == *fee_config, ChainError::InvalidFeeVault);
+    if let Some(mint) = mint {
+        require!(account.mint == *mint, ChainError::InvalidFeeVault);
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetFeeSchedule<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner @ ChainError::OnlyOwnerViolation,
+    )]
+    pub authority: Account<'info, Authority>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = FeeConfig::SPACE,
+        seeds = [b"fee_config"],
+        bump
+    )]
+    pub fee_config: Account<'info, FeeConfig>,
+    #[account(mut, seeds = [b"status"], bump)]
+    pub status: Account<'info, Status>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Subscribe<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: any key calling with fees, usually a program's signing PDA
+    pub consumer: UncheckedAccount<'info>,
+
+    #[account(mut, seeds = [b"fee_config"], bump)]
+    pub fee_config: Account<'info, FeeConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = Subscription::SPACE,
+        seeds = [b"subscription", consumer.key().as_ref()],
+        bump
+    )]
+    pub subscription: Account<'info, Subscription>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner @ ChainError::OnlyOwnerViolation,
+    )]
+    pub authority: Account<'info, Authority>,
+
+    #[account(mut, seeds = [b"fee_config"], bump)]
+    pub fee_config: Account<'info, FeeConfig>,
+
+    /// CHECK: receives the lamports, or is the token account receiving the tokens
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+}
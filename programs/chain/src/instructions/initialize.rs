@@ -1,4 +1,5 @@
 use crate::errors::ChainError;
+use crate::events::LogInitialization;
 use crate::state::chain::*;
 use anchor_lang::prelude::*;
 
@@ -18,9 +19,18 @@ pub fn initialize(
     status.last_id = 0;
     status.last_data_timestamp = 0;
     status.next_block_id = 0;
+    status.max_proof_length = 0;
+    status.paused = 0;
+    status.version = ACCOUNT_VERSION;
     let authority = &mut ctx.accounts.authority;
     authority.owner = ctx.accounts.initializer.key();
+    authority.guardian = Pubkey::default();
+    authority.version = ACCOUNT_VERSION;
     msg!("LogInitialization: by {:?}", INITIALIZER);
+    emit!(LogInitialization {
+        initializer: authority.owner,
+        padding
+    });
     Ok(())
 }
 
@@ -29,21 +39,19 @@ pub struct Initialize<'info> {
     #[account(mut)]
     pub initializer: Signer<'info>,
 
-    // space: 8 discriminator + 32 owner + 1 bump
     #[account(
         init,
         payer = initializer,
-        space = 8 + 32 + 1,
+        space = Authority::SPACE,
         seeds = [b"authority"],
         bump
     )]
     pub authority: Account<'info, Authority>,
 
-    // space: 8 discriminator + 4 padding + 4 last_id + 4 last_data_timestamp + 4 next_block_id + 1 bump
     #[account(
         init,
         payer = initializer,
-        space = 8 + 4 + 4 + 4 + 4 + 1,
+        space = Status::SPACE,
         seeds = [b"status"],
         bump
     )]
@@ -1,24 +1,74 @@
 use crate::errors::ChainError;
-use crate::state::chain::*;
+use crate::events::LogFCDInitialized;
+use crate::instructions::close_first_class_data::close_into;
+use crate::instructions::feed_registry::add_feed;
+use crate::state::*;
 use anchor_lang::prelude::*;
 
 pub fn initialize_first_class_data(
     ctx: Context<InitializeFirstClassData>,
-    _seed: Vec<u8>,
+    seed: Vec<u8>,
     key: String,
     value: [u8; 32],
     timestamp: u32
 ) -> Result<()> {
-    // needs error handling to check for max key length!
+    let metadata = FeedMetadata::umbrella(&key);
+    initialize_first_class_data_with_metadata(
+        ctx,
+        seed,
+        key,
+        value,
+        timestamp,
+        metadata
+    )
+}
+
+pub fn initialize_first_class_data_with_metadata(
+    ctx: Context<InitializeFirstClassData>,
+    _seed: Vec<u8>,
+    key: String,
+    value: [u8; 32],
+    timestamp: u32,
+    metadata: FeedMetadata
+) -> Result<()> {
+    metadata.validate()?;
+
+    let closed = &ctx.accounts.closed;
+    if closed.owner == &crate::ID && !closed.data_is_empty() {
+        let tombstone = ClosedFirstClassData::try_deserialize(&mut &closed.try_borrow_data()?[..])?;
+        require!(
+            Clock::get()?.unix_timestamp >= tombstone.reusable_at(),
+            ChainError::FCDKeyCoolingDown
+        );
+
+        // return the tombstone rent, so that a later close can leave a new one
+        close_into(closed, &ctx.accounts.owner.to_account_info())?;
+    }
+
+    add_feed(
+        &mut ctx.accounts.registry,
+        key.clone(),
+        ctx.accounts.fcd.key(),
+        metadata.clone()
+    )?;
+
     ctx.accounts.fcd.key = key;
     ctx.accounts.fcd.value = value;
     ctx.accounts.fcd.timestamp = timestamp;
+    ctx.accounts.fcd.metadata = metadata;
+    ctx.accounts.fcd.registry_page = Some(ctx.accounts.registry.page);
 
     msg!(
         "LogFCDInitialized: {:?} by {:?}",
         ctx.accounts.fcd.key,
         ctx.accounts.owner.key().to_string()
     );
+    emit!(LogFCDInitialized {
+        key: ctx.accounts.fcd.key.clone(),
+        value,
+        timestamp,
+        owner: ctx.accounts.owner.key()
+    });
 
     Ok(())
 }
@@ -35,14 +85,21 @@ pub struct InitializeFirstClassData<'info> {
     )]
     pub authority: Account<'info, Authority>,
 
-    // space: 8 discriminator + 4 key length + 200 key +  32 value + 4 timestamp + 1 bump
     #[account(
         init,
         payer = owner,
-        space = 8 + 4 + 200 + 32 + 4 + 1,
+        space = FirstClassData::SPACE,
         seeds = [&seed],
         bump
     )]
     pub fcd: Account<'info, FirstClassData>,
+
+    /// CHECK: `ClosedFirstClassData` if the FCD was closed before, empty otherwise
+    #[account(mut, seeds = [b"closed", fcd.key().as_ref()], bump)]
+    pub closed: UncheckedAccount<'info>,
+
+    // any `FeedRegistry` page with room
+    #[account(mut)]
+    pub registry: Account<'info, FeedRegistry>,
     pub system_program: Program<'info, System>,
 }
@@ -0,0 +1,82 @@
+use crate::errors::ChainError;
+use crate::events::LogMigrated;
+use crate::state::chain::*;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+pub fn migrate(ctx: Context<Migrate>) -> Result<()> {
+    let owner = ctx.accounts.owner.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+    let authority_info = ctx.accounts.authority.to_account_info();
+    let status_info = ctx.accounts.status.to_account_info();
+
+    // fields were only ever appended, so zero filling old accounts up to the
+    // current size reads missing fields as their defaults (and version 0)
+    grow(&authority_info, Authority::SPACE, &owner, &system_program)?;
+    grow(&status_info, Status::SPACE, &owner, &system_program)?;
+
+    let mut authority = Authority::try_deserialize(&mut &authority_info.try_borrow_data()?[..])?;
+    let mut status = Status::try_deserialize(&mut &status_info.try_borrow_data()?[..])?;
+    require!(authority.owner == owner.key(), ChainError::OnlyOwnerViolation);
+    require!(
+        authority.version < ACCOUNT_VERSION || status.version < ACCOUNT_VERSION,
+        ChainError::AlreadyMigrated
+    );
+
+    authority.version = ACCOUNT_VERSION;
+    status.version = ACCOUNT_VERSION;
+    authority.try_serialize(&mut &mut authority_info.try_borrow_mut_data()?[..])?;
+    status.try_serialize(&mut &mut status_info.try_borrow_mut_data()?[..])?;
+
+    msg!("LogMigrated: to version {:?} by {:?}", ACCOUNT_VERSION, owner.key().to_string());
+    emit!(LogMigrated {
+        version: ACCOUNT_VERSION,
+        owner: owner.key()
+    });
+
+    Ok(())
+}
+
+/// Reallocates `account` to `space` bytes, topping up rent from `payer`.
+fn grow<'info>(
+    account: &AccountInfo<'info>,
+    space: usize,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>
+) -> Result<()> {
+    if account.data_len() >= space {
+        return Ok(());
+    }
+
+    let rent = Rent::get()?.minimum_balance(space).saturating_sub(account.lamports());
+    if rent > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone()
+                }
+            ),
+            rent
+        )?;
+    }
+    account.realloc(space, true)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Migrate<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: may still have an older layout, deserialized after growing
+    #[account(mut, seeds = [b"authority"], bump, owner = crate::ID)]
+    pub authority: UncheckedAccount<'info>,
+
+    /// CHECK: may still have an older layout, deserialized after growing
+    #[account(mut, seeds = [b"status"], bump, owner = crate::ID)]
+    pub status: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
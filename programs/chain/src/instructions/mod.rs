@@ -2,14 +2,42 @@ pub use initialize::*;
 pub use submit::*;
 pub use initialize_first_class_data::*;
 pub use update_first_class_data::*;
+pub use close_first_class_data::*;
+pub use set_first_class_data_metadata::*;
+pub use feed_registry::*;
+pub use fcd_history::*;
+pub use twap::*;
+pub use cross_rate::*;
+pub use aggregation::*;
 pub use transfer_ownership::*;
 pub use set_padding::*;
+pub use fees::*;
+pub use rewards::*;
+pub use staking::*;
+pub use evm::*;
+pub use set_max_proof_length::*;
+pub use pause::*;
+pub use migrate::*;
 pub use verify::*;
 
 pub mod initialize;
 pub mod submit;
 pub mod initialize_first_class_data;
 pub mod update_first_class_data;
+pub mod close_first_class_data;
+pub mod set_first_class_data_metadata;
+pub mod feed_registry;
+pub mod fcd_history;
+pub mod twap;
+pub mod cross_rate;
+pub mod aggregation;
 pub mod transfer_ownership;
 pub mod set_padding;
+pub mod fees;
+pub mod rewards;
+pub mod staking;
+pub mod evm;
+pub mod set_max_proof_length;
+pub mod pause;
+pub mod migrate;
 pub mod verify;
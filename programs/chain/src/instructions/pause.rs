@@ -0,0 +1,96 @@
+use crate::errors::ChainError;
+use crate::events::{LogGuardianSet, LogPaused, LogUnpaused};
+use crate::state::chain::*;
+use anchor_lang::prelude::*;
+
+pub fn set_guardian(
+    ctx: Context<SetGuardian>,
+    guardian: Pubkey
+) -> Result<()> {
+    ctx.accounts.authority.guardian = guardian;
+    msg!(
+        "LogGuardianSet: {:?} by {:?}",
+        guardian.to_string(),
+        ctx.accounts.owner.key().to_string()
+    );
+    emit!(LogGuardianSet {
+        guardian,
+        owner: ctx.accounts.owner.key()
+    });
+
+    Ok(())
+}
+
+pub fn pause(
+    ctx: Context<Pause>,
+    flags: u8
+) -> Result<()> {
+    require!(flags & !PAUSE_ALL == 0, ChainError::UnknownPauseFlags);
+
+    let status = &mut ctx.accounts.status;
+    status.paused |= flags;
+    msg!(
+        "LogPaused: {:?} by {:?}, paused {:?}",
+        flags,
+        ctx.accounts.pauser.key().to_string(),
+        status.paused
+    );
+    emit!(LogPaused {
+        flags,
+        paused: status.paused,
+        by: ctx.accounts.pauser.key()
+    });
+
+    Ok(())
+}
+
+pub fn unpause(
+    ctx: Context<Pause>,
+    flags: u8
+) -> Result<()> {
+    require!(flags & !PAUSE_ALL == 0, ChainError::UnknownPauseFlags);
+
+    let status = &mut ctx.accounts.status;
+    status.paused &= !flags;
+    msg!(
+        "LogUnpaused: {:?} by {:?}, paused {:?}",
+        flags,
+        ctx.accounts.pauser.key().to_string(),
+        status.paused
+    );
+    emit!(LogUnpaused {
+        flags,
+        paused: status.paused,
+        by: ctx.accounts.pauser.key()
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetGuardian<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = owner @ ChainError::OnlyOwnerViolation,
+    )]
+    pub authority: Account<'info, Authority>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Pause<'info> {
+    pub pauser: Signer<'info>,
+
+    #[account(
+        seeds = [b"authority"],
+        bump,
+        constraint = authority.can_pause(&pauser.key()) @ ChainError::NotOwnerOrGuardian,
+    )]
+    pub authority: Account<'info, Authority>,
+
+    #[account(mut, seeds = [b"status"], bump)]
+    pub status: Account<'info, Status>,
+}
@@ -0,0 +1,152 @@
+use crate::errors::ChainError;
+use crate::events::{LogRewardsClaimed, LogRewardsDistributed, LogRewardsEnabledSet};
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+pub fn set_rewards_enabled(
+    ctx: Context<SetRewardsEnabled>,
+    enabled: bool
+) -> Result<()> {
+    ctx.accounts.status.rewards_enabled = enabled;
+
+    msg!(
+        "LogRewardsEnabledSet: {:?} by {:?}",
+        enabled,
+        ctx.accounts.owner.key().to_string()
+    );
+    emit!(LogRewardsEnabledSet {
+        enabled,
+        owner: ctx.accounts.owner.key()
+    });
+
+    Ok(())
+}
+
+/// Moves `amount` of the lamport fees collected on the `FeeConfig` to the
+/// `RewardVault` and shares it, with what is left undistributed, among the keys
+/// in proportion to their points.
+pub fn distribute_rewards(
+    ctx: Context<DistributeRewards>,
+    amount: u64
+) -> Result<()> {
+    let fee_config = ctx.accounts.fee_config.to_account_info();
+    let rent = Rent::get()?.minimum_balance(fee_config.data_len());
+    require!(
+        fee_config.lamports().saturating_sub(rent) >= amount,
+        ChainError::InsufficientFees
+    );
+    **fee_config.try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.reward_vault.to_account_info().try_borrow_mut_lamports()? += amount;
+
+    let reward_vault = &mut ctx.accounts.reward_vault;
+    let total_points = reward_vault.total_points;
+    reward_vault.distribute(amount);
+
+    msg!(
+        "LogRewardsDistributed: {:?} over {:?} points by {:?}",
+        amount,
+        total_points,
+        ctx.accounts.owner.key().to_string()
+    );
+    emit!(LogRewardsDistributed {
+        amount,
+        total_points,
+        owner: ctx.accounts.owner.key()
+    });
+
+    Ok(())
+}
+
+/// Sends the signer the lamports distributed to it.
+pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+    let key = ctx.accounts.claimant.key();
+    let amount = ctx.accounts.reward_vault.take_owed(&key);
+    require!(amount > 0, ChainError::NoRewards);
+
+    **ctx.accounts.reward_vault.to_account_info().try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.claimant.to_account_info().try_borrow_mut_lamports()? += amount;
+
+    msg!("LogRewardsClaimed: {:?} by {:?}", amount, key.to_string());
+    emit!(LogRewardsClaimed { key, amount });
+
+    Ok(())
+}
+
+/// Credits a point to each of `keys`, one per signature they contributed, while
+/// rewards are enabled. The call then takes the `RewardVault` as its last
+/// remaining account.
+pub(crate) fn accrue_rewards(
+    status: &Status,
+    keys: &[Pubkey],
+    remaining_accounts: &[AccountInfo]
+) -> Result<()> {
+    if !status.rewards_enabled {
+        return Ok(());
+    }
+
+    let info = remaining_accounts.last().ok_or(ChainError::MissingRewardVault)?;
+    // `RewardVault` accounts are only created by `set_rewards_enabled`, at `[b"rewards"]`
+    require!(
+        info.owner == &crate::ID && info.is_writable,
+        ChainError::MissingRewardVault
+    );
+    let mut reward_vault = RewardVault::try_deserialize(&mut &info.try_borrow_data()?[..])
+        .map_err(|_| ChainError::MissingRewardVault)?;
+
+    for key in keys {
+        if !reward_vault.accrue(*key) {
+            msg!("RewardVault full, no points for {:?}", key.to_string());
+        }
+    }
+    reward_vault.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetRewardsEnabled<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner @ ChainError::OnlyOwnerViolation,
+    )]
+    pub authority: Account<'info, Authority>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = RewardVault::SPACE,
+        seeds = [b"rewards"],
+        bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    #[account(mut, seeds = [b"status"], bump)]
+    pub status: Account<'info, Status>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DistributeRewards<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner @ ChainError::OnlyOwnerViolation,
+    )]
+    pub authority: Account<'info, Authority>,
+
+    #[account(mut, seeds = [b"fee_config"], bump)]
+    pub fee_config: Account<'info, FeeConfig>,
+
+    #[account(mut, seeds = [b"rewards"], bump)]
+    pub reward_vault: Account<'info, RewardVault>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+
+    #[account(mut, seeds = [b"rewards"], bump)]
+    pub reward_vault: Account<'info, RewardVault>,
+}
@@ -0,0 +1,54 @@
+use crate::errors::ChainError;
+use crate::events::LogFCDMetadataSet;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+pub fn set_first_class_data_metadata(
+    ctx: Context<SetFirstClassDataMetadata>,
+    key: String,
+    metadata: FeedMetadata
+) -> Result<()> {
+    require!(
+        ctx.accounts.fcd.key == key,
+        ChainError::WrongFCDKeyForAccount
+    );
+
+    metadata.validate()?;
+
+    let registry = &mut ctx.accounts.registry;
+    let index = registry.position(&key).ok_or(ChainError::FeedNotRegistered)?;
+    registry.entries[index].metadata = metadata.clone();
+    ctx.accounts.fcd.metadata = metadata.clone();
+
+    msg!(
+        "LogFCDMetadataSet: {:?} by {:?}",
+        key,
+        ctx.accounts.owner.key().to_string()
+    );
+    emit!(LogFCDMetadataSet {
+        key,
+        decimals: metadata.decimals,
+        kind: metadata.kind,
+        description: metadata.description,
+        owner: ctx.accounts.owner.key()
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetFirstClassDataMetadata<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner @ ChainError::OnlyOwnerViolation,
+    )]
+    pub authority: Account<'info, Authority>,
+
+    #[account(mut)]
+    pub fcd: Account<'info, FirstClassData>,
+
+    // the `FeedRegistry` page listing the FCD
+    #[account(mut)]
+    pub registry: Account<'info, FeedRegistry>,
+}
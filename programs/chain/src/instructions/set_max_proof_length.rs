@@ -0,0 +1,39 @@
+use crate::errors::ChainError;
+use crate::events::LogMaxProofLengthSet;
+use crate::state::chain::*;
+use anchor_lang::prelude::*;
+
+pub fn set_max_proof_length(
+    ctx: Context<SetMaxProofLength>,
+    max_proof_length: u8
+) -> Result<()> {
+    let status = &mut ctx.accounts.status;
+    status.max_proof_length = max_proof_length;
+    msg!(
+        "LogMaxProofLengthSet: {:?} by {:?}",
+        max_proof_length,
+        ctx.accounts.owner.key().to_string()
+    );
+    emit!(LogMaxProofLengthSet {
+        max_proof_length,
+        owner: ctx.accounts.owner.key()
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetMaxProofLength<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = owner @ ChainError::OnlyOwnerViolation,
+    )]
+    pub authority: Account<'info, Authority>,
+
+    #[account(mut)]
+    pub status: Account<'info, Status>,
+    pub system_program: Program<'info, System>,
+}
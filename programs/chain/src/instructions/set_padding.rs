@@ -1,4 +1,5 @@
 use crate::errors::ChainError;
+use crate::events::LogPaddingSet;
 use crate::state::chain::*;
 use anchor_lang::prelude::*;
 
@@ -9,6 +10,10 @@ pub fn set_padding(
     let status = &mut ctx.accounts.status;
     status.padding = padding;
     msg!("LogPaddingSet: {:?} by {:?}", padding, ctx.accounts.owner.key().to_string());
+    emit!(LogPaddingSet {
+        padding,
+        owner: ctx.accounts.owner.key()
+    });
 
     Ok(())
 }
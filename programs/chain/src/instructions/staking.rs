@@ -0,0 +1,424 @@
+use crate::errors::ChainError;
+use crate::events::{
+    LogEquivocationReported, LogStakeConfigSet, LogStakeWithdrawn, LogStaked, LogUnstakeRequested
+};
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::solana_program::sysvar::instructions::load_instruction_at_checked;
+
+// layout of ed25519 program instruction data: a signature count and a padding byte,
+// then per signature 7 little endian u16 offsets
+const ED25519_OFFSETS_START: usize = 2;
+const ED25519_OFFSETS_SIZE: usize = 14;
+
+/// Sets the slashing rate and unbonding period of stakes held in `vault`, which
+/// along with its mint can't change once set.
+pub fn set_stake_config(
+    ctx: Context<SetStakeConfig>,
+    slash_bps: u16,
+    unbonding_period: u32
+) -> Result<()> {
+    require!(slash_bps <= 10_000, ChainError::InvalidSlashRate);
+
+    let stake_config = &mut ctx.accounts.stake_config;
+    let vault = token_account(&ctx.accounts.vault)?;
+    require!(
+        vault.owner == stake_config.key(),
+        ChainError::InvalidStakeVault
+    );
+    if stake_config.vault != Pubkey::default() {
+        require!(
+            stake_config.vault == ctx.accounts.vault.key(),
+            ChainError::InvalidStakeVault
+        );
+    }
+    let treasury = token_account(&ctx.accounts.treasury)?;
+    require!(treasury.mint == vault.mint, ChainError::InvalidStakeVault);
+
+    stake_config.mint = vault.mint;
+    stake_config.vault = ctx.accounts.vault.key();
+    stake_config.treasury = ctx.accounts.treasury.key();
+    stake_config.slash_bps = slash_bps;
+    stake_config.unbonding_period = unbonding_period;
+
+    msg!(
+        "LogStakeConfigSet: slash {:?} bps by {:?}",
+        slash_bps,
+        ctx.accounts.owner.key().to_string()
+    );
+    emit!(LogStakeConfigSet {
+        mint: stake_config.mint,
+        vault: stake_config.vault,
+        treasury: stake_config.treasury,
+        slash_bps,
+        unbonding_period,
+        owner: ctx.accounts.owner.key()
+    });
+
+    Ok(())
+}
+
+/// Moves `amount` tokens of a validator in the `ValidatorSet` from its `source`
+/// token account to the stake vault.
+pub fn stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
+    let validator = ctx.accounts.validator.key();
+    require!(
+        ctx.accounts.validator_set.contains(&validator),
+        ChainError::NotValidator
+    );
+    require!(amount > 0, ChainError::InvalidStakeAmount);
+
+    invoke(
+        &spl_token::instruction::transfer(
+            ctx.accounts.token_program.key,
+            ctx.accounts.source.key,
+            ctx.accounts.vault.key,
+            &validator,
+            &[],
+            amount
+        )?,
+        &[
+            ctx.accounts.source.to_account_info(),
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.validator.to_account_info(),
+            ctx.accounts.token_program.to_account_info()
+        ]
+    )?;
+
+    let validator_stake = &mut ctx.accounts.validator_stake;
+    validator_stake.validator = validator;
+    validator_stake.amount += amount;
+
+    msg!("LogStaked: {:?} by {:?}", amount, validator.to_string());
+    emit!(LogStaked {
+        validator,
+        amount,
+        staked: validator_stake.amount
+    });
+
+    Ok(())
+}
+
+/// Starts unbonding `amount` staked tokens. They stay slashable, along with any
+/// unbonding before, until the unbonding period has passed from now.
+pub fn request_unstake(ctx: Context<RequestUnstake>, amount: u64) -> Result<()> {
+    let validator_stake = &mut ctx.accounts.validator_stake;
+    require!(amount > 0, ChainError::InvalidStakeAmount);
+    require!(
+        amount <= validator_stake.amount,
+        ChainError::InsufficientStake
+    );
+
+    validator_stake.amount -= amount;
+    validator_stake.unbonding += amount;
+    validator_stake.unbonding_until =
+        Clock::get()?.unix_timestamp + i64::from(ctx.accounts.stake_config.unbonding_period);
+
+    msg!(
+        "LogUnstakeRequested: {:?} by {:?}",
+        amount,
+        validator_stake.validator.to_string()
+    );
+    emit!(LogUnstakeRequested {
+        validator: validator_stake.validator,
+        amount,
+        unbonding_until: validator_stake.unbonding_until
+    });
+
+    Ok(())
+}
+
+/// Sends the unbonded tokens of the validator to its `destination` token account.
+pub fn withdraw_stake(ctx: Context<WithdrawStake>) -> Result<()> {
+    let amount = ctx.accounts.validator_stake.unbonding;
+    require!(amount > 0, ChainError::InvalidStakeAmount);
+    require!(
+        Clock::get()?.unix_timestamp >= ctx.accounts.validator_stake.unbonding_until,
+        ChainError::StakeUnbonding
+    );
+
+    let bump = *ctx.bumps.get("stake_config").ok_or(ChainError::InvalidStakeVault)?;
+    invoke_signed(
+        &spl_token::instruction::transfer(
+            ctx.accounts.token_program.key,
+            ctx.accounts.vault.key,
+            ctx.accounts.destination.key,
+            &ctx.accounts.stake_config.key(),
+            &[],
+            amount
+        )?,
+        &[
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.destination.to_account_info(),
+            ctx.accounts.stake_config.to_account_info(),
+            ctx.accounts.token_program.to_account_info()
+        ],
+        &[&[b"stake_config", &[bump]]]
+    )?;
+    ctx.accounts.validator_stake.unbonding = 0;
+
+    let validator = ctx.accounts.validator.key();
+    msg!("LogStakeWithdrawn: {:?} by {:?}", amount, validator.to_string());
+    emit!(LogStakeWithdrawn { validator, amount });
+
+    Ok(())
+}
+
+/// Slashes the stake of `validator` for attesting two different roots of `block_id`.
+/// Both attestations have to be verified by ed25519 program instructions of the same
+/// transaction, signed by the validator over `attestation_message`. The slashed tokens
+/// go to the treasury. Only submitted blocks can be reported, with one of the roots
+/// the block's stored one, each at most once per validator, which the `SlashedBlock`
+/// created by the reporter records.
+pub fn report_equivocation(
+    ctx: Context<ReportEquivocation>,
+    validator: Pubkey,
+    block_id: u32,
+    first_root: [u8; 32],
+    second_root: [u8; 32]
+) -> Result<()> {
+    require!(first_root != second_root, ChainError::NotEquivocation);
+    let root = ctx.accounts.block.root;
+    require!(
+        first_root == root || second_root == root,
+        ChainError::RootNotSubmitted
+    );
+
+    for root in [&first_root, &second_root] {
+        require!(
+            ed25519_verified(
+                &ctx.accounts.instructions,
+                &validator,
+                &attestation_message(block_id, root)
+            )?,
+            ChainError::MissingAttestationSignature
+        );
+    }
+
+    let slashed = ctx.accounts.validator_stake.slash(ctx.accounts.stake_config.slash_bps);
+    require!(slashed > 0, ChainError::NothingToSlash);
+    ctx.accounts.slashed_block.reporter = ctx.accounts.reporter.key();
+    ctx.accounts.slashed_block.slashed = slashed;
+
+    let bump = *ctx.bumps.get("stake_config").ok_or(ChainError::InvalidStakeVault)?;
+    invoke_signed(
+        &spl_token::instruction::transfer(
+            ctx.accounts.token_program.key,
+            ctx.accounts.vault.key,
+            ctx.accounts.treasury.key,
+            &ctx.accounts.stake_config.key(),
+            &[],
+            slashed
+        )?,
+        &[
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.treasury.to_account_info(),
+            ctx.accounts.stake_config.to_account_info(),
+            ctx.accounts.token_program.to_account_info()
+        ],
+        &[&[b"stake_config", &[bump]]]
+    )?;
+
+    msg!(
+        "LogEquivocationReported: {:?} at block {:?}, {:?} slashed",
+        validator.to_string(),
+        block_id,
+        slashed
+    );
+    emit!(LogEquivocationReported {
+        validator,
+        block_id,
+        first_root,
+        second_root,
+        slashed,
+        reporter: ctx.accounts.reporter.key()
+    });
+
+    Ok(())
+}
+
+fn token_account(info: &AccountInfo) -> Result<spl_token::state::Account> {
+    require!(info.owner == &spl_token::ID, ChainError::InvalidStakeVault);
+    Ok(spl_token::state::Account::unpack(&info.try_borrow_data()?)?)
+}
+
+/// Whether an ed25519 program instruction of the transaction checked a signature of
+/// `message` by `signer`. The runtime rejects the whole transaction when any of those
+/// signatures is invalid, so finding the key and message among the checked data is
+/// enough.
+fn ed25519_verified(instructions: &AccountInfo, signer: &Pubkey, message: &[u8]) -> Result<bool> {
+    let mut index = 0;
+    while let Ok(instruction) = load_instruction_at_checked(index, instructions) {
+        if instruction.program_id == ed25519_program::ID
+            && ed25519_checks(&instruction.data, signer, message)
+        {
+            return Ok(true);
+        }
+        index += 1;
+    }
+
+    Ok(false)
+}
+
+/// Whether ed25519 program instruction `data` holds a signature by `signer` over
+/// `message`, with the key and message in the instruction itself.
+fn ed25519_checks(data: &[u8], signer: &Pubkey, message: &[u8]) -> bool {
+    let count = data.first().copied().unwrap_or(0);
+    (0..usize::from(count)).any(|i| {
+        let start = ED25519_OFFSETS_START + i * ED25519_OFFSETS_SIZE;
+        let offsets = match data.get(start..start + ED25519_OFFSETS_SIZE) {
+            Some(offsets) => offsets,
+            None => return false,
+        };
+        let offset = |n: usize| usize::from(u16::from_le_bytes([offsets[2 * n], offsets[2 * n + 1]]));
+        // public key and message read from the instruction's own data
+        let own = usize::from(u16::MAX);
+        if offset(3) != own || offset(6) != own {
+            return false;
+        }
+
+        let (public_key, message_start, message_size) = (offset(2), offset(4), offset(5));
+        data.get(public_key..public_key + 32) == Some(signer.as_ref())
+            && data.get(message_start..message_start + message_size) == Some(message)
+    })
+}
+
+#[derive(Accounts)]
+pub struct SetStakeConfig<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner @ ChainError::OnlyOwnerViolation,
+    )]
+    pub authority: Account<'info, Authority>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = StakeConfig::SPACE,
+        seeds = [b"stake_config"],
+        bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    /// CHECK: token account with the stake config as its owner, checked by the handler
+    pub vault: UncheckedAccount<'info>,
+
+    /// CHECK: token account of the vault's mint, checked by the handler
+    pub treasury: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Stake<'info> {
+    #[account(mut)]
+    pub validator: Signer<'info>,
+
+    #[account(seeds = [b"validators"], bump)]
+    pub validator_set: Account<'info, ValidatorSet>,
+
+    #[account(seeds = [b"stake_config"], bump)]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = validator,
+        space = ValidatorStake::SPACE,
+        seeds = [b"stake", validator.key().as_ref()],
+        bump
+    )]
+    pub validator_stake: Account<'info, ValidatorStake>,
+
+    /// CHECK: token account of the validator, checked by the token program
+    #[account(mut)]
+    pub source: UncheckedAccount<'info>,
+
+    /// CHECK: the stake vault
+    #[account(mut, address = stake_config.vault @ ChainError::InvalidStakeVault)]
+    pub vault: UncheckedAccount<'info>,
+
+    /// CHECK: the SPL token program
+    #[account(address = spl_token::ID)]
+    pub token_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RequestUnstake<'info> {
+    pub validator: Signer<'info>,
+
+    #[account(seeds = [b"stake_config"], bump)]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    #[account(mut, seeds = [b"stake", validator.key().as_ref()], bump)]
+    pub validator_stake: Account<'info, ValidatorStake>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawStake<'info> {
+    pub validator: Signer<'info>,
+
+    #[account(seeds = [b"stake_config"], bump)]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    #[account(mut, seeds = [b"stake", validator.key().as_ref()], bump)]
+    pub validator_stake: Account<'info, ValidatorStake>,
+
+    /// CHECK: the stake vault
+    #[account(mut, address = stake_config.vault @ ChainError::InvalidStakeVault)]
+    pub vault: UncheckedAccount<'info>,
+
+    /// CHECK: token account receiving the stake, checked by the token program
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+
+    /// CHECK: the SPL token program
+    #[account(address = spl_token::ID)]
+    pub token_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(validator: Pubkey, block_id: u32)]
+pub struct ReportEquivocation<'info> {
+    #[account(mut)]
+    pub reporter: Signer<'info>,
+
+    #[account(seeds = [b"stake_config"], bump)]
+    pub stake_config: Account<'info, StakeConfig>,
+
+    #[account(mut, seeds = [b"stake", validator.as_ref()], bump)]
+    pub validator_stake: Account<'info, ValidatorStake>,
+
+    #[account(
+        init,
+        payer = reporter,
+        space = SlashedBlock::SPACE,
+        seeds = [b"slashed", validator.as_ref(), &block_id.to_le_bytes()],
+        bump
+    )]
+    pub slashed_block: Account<'info, SlashedBlock>,
+
+    #[account(seeds = [&Block::seed(block_id)], bump)]
+    pub block: Account<'info, Block>,
+
+    /// CHECK: the stake vault
+    #[account(mut, address = stake_config.vault @ ChainError::InvalidStakeVault)]
+    pub vault: UncheckedAccount<'info>,
+
+    /// CHECK: the treasury receiving slashed stake
+    #[account(mut, address = stake_config.treasury @ ChainError::InvalidStakeVault)]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// CHECK: the SPL token program
+    #[account(address = spl_token::ID)]
+    pub token_program: UncheckedAccount<'info>,
+
+    /// CHECK: the instructions sysvar, holding the ed25519 program instructions
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -1,33 +1,95 @@
 use crate::errors::ChainError;
+use crate::events::LogBlockSubmitted;
+use crate::instructions::rewards::accrue_rewards;
+use crate::merkle::HashScheme;
 use crate::state::chain::*;
 use anchor_lang::prelude::*;
 
 pub fn submit(
     ctx: Context<Submit>,
-    _seed: Vec<u8>,
+    seed: Vec<u8>,
     block_id: u32,
     root: [u8; 32],
     timestamp: u32
 ) -> Result<()> {
+    submit_with_hash_scheme(
+        ctx,
+        seed,
+        block_id,
+        root,
+        timestamp,
+        HashScheme::Legacy as u8
+    )
+}
+
+pub fn submit_with_hash_scheme(
+    ctx: Context<Submit>,
+    seed: Vec<u8>,
+    block_id: u32,
+    root: [u8; 32],
+    timestamp: u32,
+    hash_scheme: u8
+) -> Result<()> {
+    // `verify_against_latest` finds the latest block by this seed
+    require!(seed == Block::seed(block_id), ChainError::InvalidBlockSeed);
+
+    store_block(
+        &mut ctx.accounts.status,
+        &mut ctx.accounts.block,
+        block_id,
+        root,
+        timestamp,
+        hash_scheme
+    )?;
+    accrue_rewards(
+        &ctx.accounts.status,
+        &[ctx.accounts.owner.key()],
+        ctx.remaining_accounts
+    )?;
+    msg!("LogBlockSubmitted: {:?} by {:?}", block_id, ctx.accounts.owner.key().to_string());
+    emit!(LogBlockSubmitted {
+        block_id,
+        root,
+        timestamp,
+        owner: ctx.accounts.owner.key()
+    });
+
+    Ok(())
+}
+
+/// Checks a block against the status and stores it as the latest one.
+pub(crate) fn store_block(
+    status: &mut Status,
+    block: &mut Block,
+    block_id: u32,
+    root: [u8; 32],
+    timestamp: u32,
+    hash_scheme: u8
+) -> Result<()> {
+    require!(!status.is_paused(PAUSE_SUBMIT), ChainError::Paused);
+
     require!(
-        ctx.accounts.status.last_data_timestamp < timestamp,
+        HashScheme::from_u8(hash_scheme).is_some(),
+        ChainError::UnknownHashScheme
+    );
+
+    require!(
+        status.last_data_timestamp < timestamp,
         ChainError::CannotSubmitOlderData
     );
 
     require!(
-        i64::from(
-            ctx.accounts.status.last_data_timestamp + ctx.accounts.status.padding
-        ) < timestamp.into(),
+        i64::from(status.last_data_timestamp + status.padding) < timestamp.into(),
         ChainError::DoNotSpam
     );
 
-    ctx.accounts.block.block_id = block_id;
-    ctx.accounts.block.root = root;
-    ctx.accounts.block.timestamp = timestamp;
-    ctx.accounts.status.last_id = block_id;
-    ctx.accounts.status.last_data_timestamp = timestamp;
-    ctx.accounts.status.next_block_id = block_id + 1;
-    msg!("LogBlockSubmitted: {:?} by {:?}", block_id, ctx.accounts.owner.key().to_string());
+    block.block_id = block_id;
+    block.root = root;
+    block.timestamp = timestamp;
+    block.hash_scheme = hash_scheme;
+    status.last_id = block_id;
+    status.last_data_timestamp = timestamp;
+    status.next_block_id = block_id + 1;
 
     Ok(())
 }
@@ -44,7 +106,7 @@ pub struct Submit<'info> {
     )]
     pub authority: Account<'info, Authority>,
 
-    // space: 8 discriminator + 4 block_id + 32 root + 4 timestamp + 1 bump
+    // space: 8 discriminator + 4 block_id + 32 root + 4 timestamp + 1 hash_scheme
     #[account(
         init,
         payer = owner,
@@ -54,6 +116,7 @@ pub struct Submit<'info> {
     )]
     pub block: Account<'info, Block>,
 
+    // followed by the `RewardVault` as a remaining account while rewards are enabled
     #[account(mut)]
     pub status: Account<'info, Status>,
     pub system_program: Program<'info, System>
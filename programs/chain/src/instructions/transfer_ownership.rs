@@ -1,14 +1,19 @@
 use crate::errors::ChainError;
+use crate::events::LogOwnershipChanged;
 use crate::state::chain::*;
 use anchor_lang::prelude::*;
 
 pub fn transfer_ownership(
     ctx: Context<TransferOwnership>,
 ) -> Result<()> {
-    let old_owner = ctx.accounts.authority.owner.to_string();
+    let old_owner = ctx.accounts.authority.owner;
     ctx.accounts.authority.owner = ctx.accounts.new_owner.key();
-    let new_owner = ctx.accounts.authority.owner.to_string();
-    msg!("LogOwnershipChanged: From {:?} to {:?}", old_owner, new_owner);
+    let new_owner = ctx.accounts.authority.owner;
+    msg!("LogOwnershipChanged: From {:?} to {:?}", old_owner.to_string(), new_owner.to_string());
+    emit!(LogOwnershipChanged {
+        old_owner,
+        new_owner
+    });
 
     Ok(())
 }
@@ -0,0 +1,52 @@
+use crate::errors::ChainError;
+use crate::instructions::fees::charge_fee;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::set_return_data;
+use std::convert::TryFrom;
+
+/// Sets the time weighted average of the feed over the last `window_secs` seconds as
+/// return data, a 32 byte big endian value with the feed's decimals.
+pub fn get_twap(
+    ctx: Context<GetTwap>,
+    key: String,
+    window_secs: u32
+) -> Result<()> {
+    charge_fee(&ctx.accounts.status, FeeKind::Read, ctx.remaining_accounts)?;
+
+    let fcd = &ctx.accounts.fcd;
+    require!(
+        fcd.key == key,
+        ChainError::WrongFCDKeyForAccount
+    );
+
+    require!(window_secs > 0, ChainError::InvalidTwapWindow);
+
+    require!(
+        fcd.resolved_metadata().kind != ValueKind::Raw as u8,
+        ChainError::TwapUnavailable
+    );
+
+    // data timestamps can run slightly ahead of the cluster clock
+    let clock = u32::try_from(Clock::get()?.unix_timestamp).unwrap_or(u32::MAX);
+    let now = clock.max(fcd.timestamp);
+    let twap = ctx.accounts.history
+        .twap(fcd, now, window_secs)
+        .ok_or(ChainError::TwapWindowNotCovered)?;
+
+    let mut value = [0u8; 32];
+    value[16..].copy_from_slice(&twap.to_be_bytes());
+    set_return_data(&value);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct GetTwap<'info> {
+    pub fcd: Account<'info, FirstClassData>,
+
+    #[account(seeds = [b"history", fcd.key().as_ref()], bump)]
+    pub history: Account<'info, FcdHistory>,
+    #[account(seeds = [b"status"], bump)]
+    pub status: Account<'info, Status>,
+}
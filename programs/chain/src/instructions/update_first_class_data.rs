@@ -1,4 +1,7 @@
 use crate::errors::ChainError;
+use crate::events::{LogFCDConfidenceUpdated, LogFCDUpdated};
+use crate::instructions::fcd_history::record_observation;
+use crate::instructions::rewards::accrue_rewards;
 use crate::state::chain::*;
 use anchor_lang::prelude::*;
 
@@ -7,24 +10,44 @@ pub fn update_first_class_data(
     key: String,
     value: [u8; 32],
     timestamp: u32
+) -> Result<()> {
+    update(ctx, key, value, timestamp, None)
+}
+
+/// Updates the FCD with the `confidence` interval reported around `value`, zero
+/// when the sources agreed exactly.
+pub fn update_first_class_data_with_confidence(
+    ctx: Context<UpdateFirstClassData>,
+    key: String,
+    value: [u8; 32],
+    timestamp: u32,
+    confidence: [u8; 32]
+) -> Result<()> {
+    update(ctx, key, value, timestamp, Some(confidence))
+}
+
+fn update(
+    ctx: Context<UpdateFirstClassData>,
+    key: String,
+    value: [u8; 32],
+    timestamp: u32,
+    confidence: Option<[u8; 32]>
 ) -> Result<()> {
     require!(
         ctx.accounts.fcd.key == key,
         ChainError::WrongFCDKeyForAccount
     );
 
-    require!(
-        ctx.accounts.fcd.timestamp < timestamp,
-        ChainError::CannotSubmitOlderData
-    );
-
-    require!(
-        i64::from(ctx.accounts.fcd.timestamp + ctx.accounts.status.padding) < timestamp.into(),
-        ChainError::DoNotSpam
-    );
+    require!(!ctx.accounts.fcd.aggregated, ChainError::AggregatedFeed);
 
-    ctx.accounts.fcd.value = value;
-    ctx.accounts.fcd.timestamp = timestamp;
+    check_update(&ctx.accounts.fcd, &ctx.accounts.status, timestamp)?;
+    let address = ctx.accounts.fcd.key();
+    set_value(&mut ctx.accounts.fcd, address, value, timestamp, confidence, ctx.remaining_accounts)?;
+    accrue_rewards(
+        &ctx.accounts.status,
+        &[ctx.accounts.owner.key()],
+        ctx.remaining_accounts
+    )?;
 
     msg!(
         "LogFCDUpdated: {:?} by {:?} with timestamp {:?}",
@@ -32,10 +55,66 @@ pub fn update_first_class_data(
         ctx.accounts.owner.key().to_string(),
         timestamp
     );
+    emit!(LogFCDUpdated {
+        key,
+        value,
+        timestamp,
+        owner: ctx.accounts.owner.key()
+    });
+
+    if let Some(confidence) = confidence {
+        emit!(LogFCDConfidenceUpdated {
+            key: ctx.accounts.fcd.key.clone(),
+            confidence,
+            timestamp
+        });
+    }
 
     Ok(())
 }
 
+/// Checks every new value of `fcd` goes through, from the owner or a validator round.
+pub(crate) fn check_update(
+    fcd: &FirstClassData,
+    status: &Status,
+    timestamp: u32
+) -> Result<()> {
+    require!(
+        !status.is_paused(PAUSE_FCD_UPDATE),
+        ChainError::Paused
+    );
+
+    require!(
+        fcd.timestamp < timestamp,
+        ChainError::CannotSubmitOlderData
+    );
+
+    require!(
+        i64::from(fcd.timestamp + status.padding) < timestamp.into(),
+        ChainError::DoNotSpam
+    );
+
+    Ok(())
+}
+
+/// Sets the value and the confidence, if reported, of the FCD at `address`, accumulating
+/// the previous value and appending to the history passed in `remaining_accounts`.
+pub(crate) fn set_value(
+    fcd: &mut FirstClassData,
+    address: Pubkey,
+    value: [u8; 32],
+    timestamp: u32,
+    confidence: Option<[u8; 32]>,
+    remaining_accounts: &[AccountInfo]
+) -> Result<()> {
+    fcd.cumulative = fcd.cumulative_at(timestamp);
+    fcd.value = value;
+    fcd.timestamp = timestamp;
+    fcd.confidence = confidence.unwrap_or_default();
+    fcd.has_confidence = confidence.is_some();
+    record_observation(fcd, address, remaining_accounts)
+}
+
 #[derive(Accounts)]
 #[instruction(seed: Vec<u8>)]
 pub struct UpdateFirstClassData<'info> {
@@ -48,6 +127,8 @@ pub struct UpdateFirstClassData<'info> {
     )]
     pub authority: Account<'info, Authority>,
 
+    // followed by the feed's `FcdHistory` as a remaining account when it has one,
+    // then the `RewardVault` while rewards are enabled
     #[account(mut)]
     pub fcd: Account<'info, FirstClassData>,
     #[account(mut)]
@@ -1,6 +1,9 @@
+use crate::errors::ChainError;
+use crate::instructions::fees::charge_fee;
+use crate::merkle::{hash_message, HashScheme};
 use crate::state::chain::*;
+use crate::state::FeeKind;
 use anchor_lang::prelude::*;
-use sha3::{Digest, Keccak256};
 
 pub fn initialize_verify_result(
     ctx: Context<InitializeVerifyResult>
@@ -14,55 +17,76 @@ pub fn verify_proof_for_block(
     ctx: Context<Verify>, _seed: Vec<u8>,
     proof: Vec<[u8;32]>, key: [u8;32], value: [u8;32]
 ) -> Result<()> {
-    let squashed_root = ctx.accounts.block.root;
+    charge_fee(&ctx.accounts.status, FeeKind::Verify, ctx.remaining_accounts)?;
 
-    let mut hasher = Keccak256::new();
-    hasher.update([key, value].concat());
-    let leaf = hasher.finalize().into();
-
-    let verify_result = &mut ctx.accounts.verify_result;
-    verify_result.result = verify_squashed_root(squashed_root, proof, leaf);
-    msg!("The verification result is = {}", verify_result.result);
-    Ok(())
+    verify(
+        &ctx.accounts.block,
+        &ctx.accounts.status,
+        &mut ctx.accounts.verify_result,
+        proof,
+        |scheme| scheme.hash_leaf(key, value)
+    )
 }
 
-fn compute_root(proof: Vec<[u8; 32]>, leaf: [u8; 32]) -> [u8; 32] {
-    let mut computed_hash: [u8; 32] = leaf;
-    for proof_element in proof {
-        let mut hasher = Keccak256::new();
-        if compare_hashes(proof_element, computed_hash) {
-            hasher.update([proof_element, computed_hash].concat());
-        } else {
-            hasher.update([computed_hash, proof_element].concat());
-        }
-        computed_hash = hasher.finalize().into();
-    }
-    computed_hash
-}
+/// Verifies that `message`, an arbitrary payload such as a cross-chain message,
+/// is a leaf of the block, hashed with `hash_message`.
+pub fn verify_message_inclusion(
+    ctx: Context<Verify>, _seed: Vec<u8>,
+    message: Vec<u8>, proof: Vec<[u8;32]>
+) -> Result<()> {
+    charge_fee(&ctx.accounts.status, FeeKind::Verify, ctx.remaining_accounts)?;
 
-fn verify_squashed_root(
-    squashed_root: [u8;32], proof: Vec<[u8;32]>, leaf: [u8;32]
-) -> bool {
-    extract_root(compute_root(proof, leaf)) == extract_root(squashed_root)
+    verify(
+        &ctx.accounts.block,
+        &ctx.accounts.status,
+        &mut ctx.accounts.verify_result,
+        proof,
+        |_| hash_message(&message)
+    )
 }
 
-fn extract_root(root_with_timestamp: [u8;32]) -> [u8;32] {
-    let mut result = root_with_timestamp.clone();
-    for i in 28..32 {
-        result[i] = 0u8;
-    }
-    result
+pub fn verify_against_latest(
+    ctx: Context<VerifyAgainstLatest>,
+    proof: Vec<[u8;32]>, key: [u8;32], value: [u8;32], max_age: u32
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now - i64::from(ctx.accounts.block.timestamp) <= i64::from(max_age),
+        ChainError::StaleBlock
+    );
+
+    charge_fee(&ctx.accounts.status, FeeKind::Verify, ctx.remaining_accounts)?;
+
+    verify(
+        &ctx.accounts.block,
+        &ctx.accounts.status,
+        &mut ctx.accounts.verify_result,
+        proof,
+        |scheme| scheme.hash_leaf(key, value)
+    )
 }
 
-fn compare_hashes(a: [u8; 32], b: [u8; 32]) -> bool {
-    for (i, _) in a.iter().enumerate() {
-        if a[i] < b[i] {
-            return true;
-        } else if a[i] > b[i] {
-            return false;
-        }
-    }
-    true
+// `leaf` hashes the verified data with the scheme of the block
+fn verify(
+    block: &Block, status: &Status, verify_result: &mut VerifyResult,
+    proof: Vec<[u8;32]>, leaf: impl FnOnce(HashScheme) -> [u8;32]
+) -> Result<()> {
+    require!(!status.is_paused(PAUSE_VERIFY), ChainError::Paused);
+
+    require!(
+        proof.len() <= status.max_proof_length(),
+        ChainError::ProofTooLong
+    );
+
+    let squashed_root = block.root;
+    let scheme = HashScheme::from_u8(block.hash_scheme)
+        .ok_or(ChainError::UnknownHashScheme)?;
+
+    let leaf = leaf(scheme);
+
+    verify_result.result = scheme.verify_squashed_root(squashed_root, &proof, leaf);
+    msg!("The verification result is = {}", verify_result.result);
+    Ok(())
 }
 
 #[derive(Accounts)]
@@ -81,4 +105,17 @@ pub struct Verify<'info> {
     pub block: Account<'info, Block>,
     #[account(mut)]
     pub verify_result: Account<'info, VerifyResult>,
+    #[account(seeds = [b"status"], bump)]
+    pub status: Account<'info, Status>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyAgainstLatest<'info> {
+    #[account(seeds = [b"status"], bump)]
+    pub status: Account<'info, Status>,
+    // the block of `status.last_id`, fails with `ConstraintSeeds` once a newer one is submitted
+    #[account(seeds = [&Block::seed(status.last_id)], bump)]
+    pub block: Account<'info, Block>,
+    #[account(mut)]
+    pub verify_result: Account<'info, VerifyResult>,
 }
@@ -1,8 +1,12 @@
 use anchor_lang::prelude::*;
 use instructions::*;
+use state::{FeeSchedule, FeedMetadata};
 
+pub mod cross_rate;
 pub mod errors;
+pub mod events;
 pub mod instructions;
+pub mod merkle;
 pub mod state;
 
 declare_id!("4SPgs3L7Ey9VyRuZwx4X3y86LSAZXP2Hhpz9Sps4v3iT");
@@ -21,6 +25,12 @@ pub mod chain {
         )
     }
 
+    pub fn migrate(
+        ctx: Context<Migrate>,
+    ) -> Result<()> {
+        instructions::migrate::migrate(ctx)
+    }
+
     pub fn transfer_ownership(
         ctx: Context<TransferOwnership>,
     ) -> Result<()> {
@@ -34,6 +44,34 @@ pub mod chain {
         instructions::set_padding::set_padding(ctx, padding)
     }
 
+    pub fn set_max_proof_length(
+        ctx: Context<SetMaxProofLength>,
+        max_proof_length: u8
+    ) -> Result<()> {
+        instructions::set_max_proof_length::set_max_proof_length(ctx, max_proof_length)
+    }
+
+    pub fn set_guardian(
+        ctx: Context<SetGuardian>,
+        guardian: Pubkey
+    ) -> Result<()> {
+        instructions::pause::set_guardian(ctx, guardian)
+    }
+
+    pub fn pause(
+        ctx: Context<Pause>,
+        flags: u8
+    ) -> Result<()> {
+        instructions::pause::pause(ctx, flags)
+    }
+
+    pub fn unpause(
+        ctx: Context<Pause>,
+        flags: u8
+    ) -> Result<()> {
+        instructions::pause::unpause(ctx, flags)
+    }
+
     pub fn submit(
         ctx: Context<Submit>,
         seed: Vec<u8>,
@@ -50,6 +88,24 @@ pub mod chain {
         )
     }
 
+    pub fn submit_with_hash_scheme(
+        ctx: Context<Submit>,
+        seed: Vec<u8>,
+        block_id: u32,
+        root: [u8; 32],
+        timestamp: u32,
+        hash_scheme: u8
+    ) -> Result<()> {
+        instructions::submit::submit_with_hash_scheme(
+            ctx,
+            seed,
+            block_id,
+            root,
+            timestamp,
+            hash_scheme
+        )
+    }
+
     pub fn initialize_first_class_data(
         ctx: Context<InitializeFirstClassData>,
         seed: Vec<u8>,
@@ -66,6 +122,24 @@ pub mod chain {
         )
     }
 
+    pub fn initialize_first_class_data_with_metadata(
+        ctx: Context<InitializeFirstClassData>,
+        seed: Vec<u8>,
+        key: String,
+        value: [u8; 32],
+        timestamp: u32,
+        metadata: FeedMetadata
+    ) -> Result<()> {
+        instructions::initialize_first_class_data::initialize_first_class_data_with_metadata(
+            ctx,
+            seed,
+            key,
+            value,
+            timestamp,
+            metadata
+        )
+    }
+
     pub fn update_first_class_data(
         ctx: Context<UpdateFirstClassData>,
         key: String,
@@ -80,6 +154,211 @@ pub mod chain {
         )
     }
 
+    pub fn update_first_class_data_with_confidence(
+        ctx: Context<UpdateFirstClassData>,
+        key: String,
+        value: [u8; 32],
+        timestamp: u32,
+        confidence: [u8; 32]
+    ) -> Result<()> {
+        instructions::update_first_class_data::update_first_class_data_with_confidence(
+            ctx,
+            key,
+            value,
+            timestamp,
+            confidence
+        )
+    }
+
+    pub fn set_validators(
+        ctx: Context<SetValidators>,
+        validators: Vec<Pubkey>,
+        quorum: u8,
+        window: u32
+    ) -> Result<()> {
+        instructions::aggregation::set_validators(ctx, validators, quorum, window)
+    }
+
+    pub fn initialize_fcd_aggregation(
+        ctx: Context<InitializeFcdAggregation>,
+        key: String
+    ) -> Result<()> {
+        instructions::aggregation::initialize_fcd_aggregation(ctx, key)
+    }
+
+    pub fn submit_fcd_observation(
+        ctx: Context<SubmitFcdObservation>,
+        key: String,
+        value: [u8; 32],
+        timestamp: u32
+    ) -> Result<()> {
+        instructions::aggregation::submit_fcd_observation(ctx, key, value, timestamp)
+    }
+
+    pub fn close_first_class_data(
+        ctx: Context<CloseFirstClassData>,
+        key: String
+    ) -> Result<()> {
+        instructions::close_first_class_data::close_first_class_data(ctx, key)
+    }
+
+    pub fn set_first_class_data_metadata(
+        ctx: Context<SetFirstClassDataMetadata>,
+        key: String,
+        metadata: FeedMetadata
+    ) -> Result<()> {
+        instructions::set_first_class_data_metadata::set_first_class_data_metadata(
+            ctx,
+            key,
+            metadata
+        )
+    }
+
+    pub fn initialize_feed_registry_page(
+        ctx: Context<InitializeFeedRegistryPage>,
+        page: u32
+    ) -> Result<()> {
+        instructions::feed_registry::initialize_feed_registry_page(ctx, page)
+    }
+
+    pub fn register_first_class_data(
+        ctx: Context<RegisterFirstClassData>,
+        key: String
+    ) -> Result<()> {
+        instructions::feed_registry::register_first_class_data(ctx, key)
+    }
+
+    pub fn get_registered_feeds(
+        ctx: Context<GetRegisteredFeeds>,
+        start: u32,
+        limit: u32
+    ) -> Result<()> {
+        instructions::feed_registry::get_registered_feeds(ctx, start, limit)
+    }
+
+    pub fn initialize_fcd_history(
+        ctx: Context<InitializeFcdHistory>,
+        key: String,
+        capacity: u16
+    ) -> Result<()> {
+        instructions::fcd_history::initialize_fcd_history(ctx, key, capacity)
+    }
+
+    pub fn get_fcd_observation(
+        ctx: Context<GetFcdObservation>,
+        timestamp: u32
+    ) -> Result<()> {
+        instructions::fcd_history::get_fcd_observation(ctx, timestamp)
+    }
+
+    pub fn get_twap(
+        ctx: Context<GetTwap>,
+        key: String,
+        window_secs: u32
+    ) -> Result<()> {
+        instructions::twap::get_twap(ctx, key, window_secs)
+    }
+
+    pub fn get_cross_rate(
+        ctx: Context<GetCrossRate>,
+        operation: u8
+    ) -> Result<()> {
+        instructions::cross_rate::get_cross_rate(ctx, operation)
+    }
+
+    pub fn set_fee_schedule(
+        ctx: Context<SetFeeSchedule>,
+        schedule: FeeSchedule,
+        exempt: Vec<Pubkey>,
+        enabled: bool
+    ) -> Result<()> {
+        instructions::fees::set_fee_schedule(ctx, schedule, exempt, enabled)
+    }
+
+    pub fn subscribe<'info>(
+        ctx: Context<'_, '_, '_, 'info, Subscribe<'info>>,
+        periods: u32
+    ) -> Result<()> {
+        instructions::fees::subscribe(ctx, periods)
+    }
+
+    pub fn withdraw_fees<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawFees<'info>>,
+        amount: u64
+    ) -> Result<()> {
+        instructions::fees::withdraw_fees(ctx, amount)
+    }
+
+    pub fn set_rewards_enabled(
+        ctx: Context<SetRewardsEnabled>,
+        enabled: bool
+    ) -> Result<()> {
+        instructions::rewards::set_rewards_enabled(ctx, enabled)
+    }
+
+    pub fn distribute_rewards(
+        ctx: Context<DistributeRewards>,
+        amount: u64
+    ) -> Result<()> {
+        instructions::rewards::distribute_rewards(ctx, amount)
+    }
+
+    pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+        instructions::rewards::claim_rewards(ctx)
+    }
+
+    pub fn set_stake_config(
+        ctx: Context<SetStakeConfig>,
+        slash_bps: u16,
+        unbonding_period: u32
+    ) -> Result<()> {
+        instructions::staking::set_stake_config(ctx, slash_bps, unbonding_period)
+    }
+
+    pub fn stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
+        instructions::staking::stake(ctx, amount)
+    }
+
+    pub fn request_unstake(ctx: Context<RequestUnstake>, amount: u64) -> Result<()> {
+        instructions::staking::request_unstake(ctx, amount)
+    }
+
+    pub fn withdraw_stake(ctx: Context<WithdrawStake>) -> Result<()> {
+        instructions::staking::withdraw_stake(ctx)
+    }
+
+    pub fn report_equivocation(
+        ctx: Context<ReportEquivocation>,
+        validator: Pubkey,
+        block_id: u32,
+        first_root: [u8; 32],
+        second_root: [u8; 32]
+    ) -> Result<()> {
+        instructions::staking::report_equivocation(
+            ctx,
+            validator,
+            block_id,
+            first_root,
+            second_root
+        )
+    }
+
+    pub fn set_evm_validators(
+        ctx: Context<SetEvmValidators>,
+        validators: Vec<[u8; 20]>,
+        quorum: u8
+    ) -> Result<()> {
+        instructions::evm::set_evm_validators(ctx, validators, quorum)
+    }
+
+    pub fn import_evm_block(
+        ctx: Context<ImportEvmBlock>,
+        seed: Vec<u8>,
+        payload: Vec<u8>
+    ) -> Result<()> {
+        instructions::evm::import_evm_block(ctx, seed, payload)
+    }
+
     pub fn initialize_verify_result(
         ctx: Context<InitializeVerifyResult>
     ) -> Result<()> {
@@ -92,4 +371,18 @@ pub mod chain {
     ) -> Result<()> {
         instructions::verify::verify_proof_for_block(ctx, seed, proof, key, value)
     }
+
+    pub fn verify_message_inclusion(
+        ctx: Context<Verify>, seed: Vec<u8>, message: Vec<u8>,
+        proof: Vec<[u8;32]>
+    ) -> Result<()> {
+        instructions::verify::verify_message_inclusion(ctx, seed, message, proof)
+    }
+
+    pub fn verify_against_latest(
+        ctx: Context<VerifyAgainstLatest>, proof: Vec<[u8;32]>,
+        key: [u8;32], value: [u8;32], max_age: u32
+    ) -> Result<()> {
+        instructions::verify::verify_against_latest(ctx, proof, key, value, max_age)
+    }
 }
@@ -0,0 +1,124 @@
+//! Verification of Umbrella sorted merkle proofs.
+//!
+//! Pure functions without accounts or program state, shared by the `verify`
+//! instruction and usable off-chain (tests, fuzzing, clients). Hashing goes
+//! through `keccak::hashv`, the `sol_keccak256` syscall when running on-chain.
+
+use anchor_lang::solana_program::keccak::hashv;
+
+/// Number of trailing root bytes replaced by the block timestamp.
+pub const TIMESTAMP_BYTES: usize = 4;
+
+/// Proof length accepted while `Status.max_proof_length` is unset, enough for
+/// trees of 2^32 leaves.
+pub const DEFAULT_MAX_PROOF_LENGTH: u8 = 32;
+
+/// Byte prepended to leaves by `HashScheme::Prefixed`.
+pub const LEAF_PREFIX: u8 = 0x00;
+
+/// Byte prepended to inner nodes by `HashScheme::Prefixed`.
+pub const NODE_PREFIX: u8 = 0x01;
+
+/// Byte prepended to the hash of a message by `hash_message`, in every scheme.
+pub const MESSAGE_PREFIX: u8 = 0x02;
+
+/// How leaves and inner nodes of a block's tree are hashed, stored per `Block`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HashScheme {
+    /// `keccak(key || value)` and `keccak(a || b)`, as in Umbrella sidechain roots.
+    /// Both inputs are 64 bytes, so an inner node can be passed off as a leaf.
+    Legacy = 0,
+    /// `keccak(0x00 || key || value)` and `keccak(0x01 || a || b)`, which keeps
+    /// leaves and inner nodes apart.
+    Prefixed = 1,
+}
+
+impl HashScheme {
+    pub fn from_u8(scheme: u8) -> Option<Self> {
+        match scheme {
+            0 => Some(HashScheme::Legacy),
+            1 => Some(HashScheme::Prefixed),
+            _ => None,
+        }
+    }
+
+    pub fn hash_leaf(self, key: [u8; 32], value: [u8; 32]) -> [u8; 32] {
+        self.hash(LEAF_PREFIX, key, value)
+    }
+
+    /// Hashes two nodes smallest first.
+    pub fn hash_node(self, a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+        if compare_hashes(&a, &b) {
+            self.hash(NODE_PREFIX, a, b)
+        } else {
+            self.hash(NODE_PREFIX, b, a)
+        }
+    }
+
+    /// Folds `proof` into `leaf`.
+    pub fn compute_root(self, proof: &[[u8; 32]], leaf: [u8; 32]) -> [u8; 32] {
+        proof.iter().fold(leaf, |computed_hash, proof_element| {
+            self.hash_node(*proof_element, computed_hash)
+        })
+    }
+
+    /// Checks `proof` for `leaf` against a root whose last bytes hold a timestamp.
+    pub fn verify_squashed_root(
+        self, squashed_root: [u8; 32], proof: &[[u8; 32]], leaf: [u8; 32]
+    ) -> bool {
+        extract_root(self.compute_root(proof, leaf)) == extract_root(squashed_root)
+    }
+
+    fn hash(self, prefix: u8, a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+        match self {
+            HashScheme::Legacy => hashv(&[&a, &b]).to_bytes(),
+            HashScheme::Prefixed => hashv(&[&[prefix], &a, &b]).to_bytes(),
+        }
+    }
+}
+
+/// Leaf hash of the legacy scheme.
+pub fn hash_leaf(key: [u8; 32], value: [u8; 32]) -> [u8; 32] {
+    HashScheme::Legacy.hash_leaf(key, value)
+}
+
+/// `compute_root` of the legacy scheme.
+pub fn compute_root(proof: &[[u8; 32]], leaf: [u8; 32]) -> [u8; 32] {
+    HashScheme::Legacy.compute_root(proof, leaf)
+}
+
+/// `verify_squashed_root` of the legacy scheme.
+pub fn verify_squashed_root(
+    squashed_root: [u8; 32], proof: &[[u8; 32]], leaf: [u8; 32]
+) -> bool {
+    HashScheme::Legacy.verify_squashed_root(squashed_root, proof, leaf)
+}
+
+/// Leaf of an arbitrary-length message, `keccak(0x02 || keccak(message))`.
+///
+/// The hashed 33 bytes can't be mistaken for a key/value leaf or an inner node,
+/// which hash 64 bytes in the legacy scheme and 65 in the prefixed one, so a
+/// message leaf is proven with the nodes of either scheme.
+pub fn hash_message(message: &[u8]) -> [u8; 32] {
+    hashv(&[&[MESSAGE_PREFIX], &hashv(&[message]).to_bytes()]).to_bytes()
+}
+
+/// Zeroes the timestamp bytes of a squashed root.
+pub fn extract_root(root_with_timestamp: [u8; 32]) -> [u8; 32] {
+    let mut result = root_with_timestamp;
+    result[32 - TIMESTAMP_BYTES..].fill(0);
+    result
+}
+
+/// `a <= b`, comparing bytes in order.
+pub fn compare_hashes(a: &[u8; 32], b: &[u8; 32]) -> bool {
+    for (x, y) in a.iter().zip(b.iter()) {
+        if x < y {
+            return true;
+        } else if x > y {
+            return false;
+        }
+    }
+
+    true
+}
@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+
+pub const MAX_VALIDATORS: usize = 16;
+
+/// Keys allowed to submit observations for aggregated feeds, at `[b"validators"]`.
+#[account]
+#[derive(Default)]
+pub struct ValidatorSet {
+    pub validators: Vec<Pubkey>,
+    // submissions needed to finalize a round
+    pub quorum: u8,
+    // seconds after its first submission during which a round can reach the quorum
+    pub window: u32
+}
+
+impl ValidatorSet {
+    // space: 8 discriminator + 4 validators length + validators + 1 quorum + 4 window
+    pub const SPACE: usize = 8 + 4 + 32 * MAX_VALIDATORS + 1 + 4;
+
+    pub fn contains(&self, validator: &Pubkey) -> bool {
+        self.validators.contains(validator)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Submission {
+    pub validator: Pubkey,
+    pub value: [u8; 32]
+}
+
+impl Submission {
+    // space: 32 validator + 32 value
+    pub const SPACE: usize = 32 + 32;
+}
+
+/// Observations submitted for the next value of an aggregated feed, at `[b"round", fcd]`.
+#[account]
+#[derive(Default)]
+pub struct FcdRound {
+    pub fcd: Pubkey,
+    // data timestamp the submissions are for
+    pub timestamp: u32,
+    // cluster time of the first submission
+    pub started_at: i64,
+    pub submissions: Vec<Submission>
+}
+
+impl FcdRound {
+    // space: 8 discriminator + 32 fcd + 4 timestamp + 8 started_at + 4 submissions length + submissions
+    pub const SPACE: usize = 8 + 32 + 4 + 8 + 4 + MAX_VALIDATORS * Submission::SPACE;
+
+    /// Whether the round has submissions that can still reach the quorum at `now`.
+    pub fn is_open(&self, now: i64, window: u32) -> bool {
+        !self.submissions.is_empty() && now <= self.started_at + i64::from(window)
+    }
+
+    /// Lower median of the submitted values, compared as big endian numbers. Always
+    /// one of the submitted values, so it also applies to raw `FIXED_` feeds.
+    pub fn median(&self) -> Option<[u8; 32]> {
+        let mut values: Vec<[u8; 32]> = self.submissions.iter().map(|submission| submission.value).collect();
+        values.sort_unstable();
+        values.get(values.len().checked_sub(1)? / 2).copied()
+    }
+
+    /// Half the distance between the lowest and highest submitted values, as a
+    /// big endian number. Published as the confidence of aggregated numeric feeds.
+    pub fn half_spread(&self) -> Option<[u8; 32]> {
+        let min = self.submissions.iter().map(|submission| submission.value).min()?;
+        let max = self.submissions.iter().map(|submission| submission.value).max()?;
+
+        let mut spread = [0u8; 32];
+        let mut borrow = 0u16;
+        for i in (0..32).rev() {
+            let difference = 0x100 + u16::from(max[i]) - u16::from(min[i]) - borrow;
+            spread[i] = difference as u8;
+            borrow = if difference < 0x100 { 1 } else { 0 };
+        }
+
+        let mut carry = 0u8;
+        for byte in spread.iter_mut() {
+            let next = *byte & 1;
+            *byte = (*byte >> 1) | (carry << 7);
+            carry = next;
+        }
+
+        Some(spread)
+    }
+}
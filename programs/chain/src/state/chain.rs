@@ -1,9 +1,40 @@
+use crate::merkle::DEFAULT_MAX_PROOF_LENGTH;
+use crate::state::metadata::{FeedMetadata, ValueKind};
 use anchor_lang::prelude::*;
 
+/// `Status.paused` flag stopping `submit`, `submit_with_hash_scheme` and `import_evm_block`.
+pub const PAUSE_SUBMIT: u8 = 1 << 0;
+/// `Status.paused` flag stopping `update_first_class_data`.
+pub const PAUSE_FCD_UPDATE: u8 = 1 << 1;
+/// `Status.paused` flag stopping `verify_proof_for_block`, `verify_against_latest` and
+/// `verify_message_inclusion`.
+pub const PAUSE_VERIFY: u8 = 1 << 2;
+pub const PAUSE_ALL: u8 = PAUSE_SUBMIT | PAUSE_FCD_UPDATE | PAUSE_VERIFY;
+
+/// Layout version of `Authority` and `Status`, bumped by `migrate`. Accounts
+/// created before the field existed read as version 0 once reallocated.
+pub const ACCOUNT_VERSION: u8 = 1;
+
+/// Zeroed bytes allocated after `Authority` and `Status`. New fields are appended
+/// into them and read as zero from existing accounts, without a realloc.
+pub const RESERVED_SPACE: usize = 64;
+
 #[account]
 #[derive(Default)]
 pub struct Authority {
-    pub owner: Pubkey
+    pub owner: Pubkey,
+    // key allowed to pause and unpause besides the owner, `Pubkey::default()` for none
+    pub guardian: Pubkey,
+    pub version: u8
+}
+
+impl Authority {
+    // space: 8 discriminator + 32 owner + 32 guardian + 1 version + reserved
+    pub const SPACE: usize = 8 + 32 + 32 + 1 + RESERVED_SPACE;
+
+    pub fn can_pause(&self, key: &Pubkey) -> bool {
+        *key == self.owner || (self.guardian != Pubkey::default() && *key == self.guardian)
+    }
 }
 
 #[account]
@@ -11,7 +42,21 @@ pub struct Authority {
 pub struct Block {
     pub block_id: u32,
     pub root: [u8; 32],
-    pub timestamp: u32
+    pub timestamp: u32,
+    // `merkle::HashScheme` of the root, 0 (legacy) for blocks submitted before it existed
+    pub hash_scheme: u8
+}
+
+impl Block {
+    /// Seed of the block account, as used by the replicator:
+    /// `block_id * 10^18` as a big endian uint256.
+    pub fn seed(block_id: u32) -> [u8; 32] {
+        let mut seed = [0u8; 32];
+        seed[16..].copy_from_slice(
+            &(u128::from(block_id) * 10u128.pow(18)).to_be_bytes()
+        );
+        seed
+    }
 }
 
 #[account]
@@ -20,7 +65,34 @@ pub struct Status {
     pub padding: u32,
     pub last_id: u32,
     pub last_data_timestamp: u32,
-    pub next_block_id: u32
+    pub next_block_id: u32,
+    // longest proof accepted by `verify_proof_for_block`, 0 for `DEFAULT_MAX_PROOF_LENGTH`
+    pub max_proof_length: u8,
+    // `PAUSE_*` flags of the stopped instructions
+    pub paused: u8,
+    pub version: u8,
+    // set by `set_fee_schedule`; verification and reads then charge the `FeeConfig` fees
+    pub fees_enabled: bool,
+    // set by `set_rewards_enabled`; submissions then accrue to the `RewardVault`
+    pub rewards_enabled: bool
+}
+
+impl Status {
+    // space: 8 discriminator + 4 padding + 4 last_id + 4 last_data_timestamp
+    // + 4 next_block_id + 1 max_proof_length + 1 paused + 1 version + reserved,
+    // which `fees_enabled` and `rewards_enabled` were appended into
+    pub const SPACE: usize = 8 + 4 + 4 + 4 + 4 + 1 + 1 + 1 + RESERVED_SPACE;
+
+    pub fn max_proof_length(&self) -> usize {
+        match self.max_proof_length {
+            0 => DEFAULT_MAX_PROOF_LENGTH.into(),
+            max_proof_length => max_proof_length.into(),
+        }
+    }
+
+    pub fn is_paused(&self, flag: u8) -> bool {
+        self.paused & flag != 0
+    }
 }
 
 #[account]
@@ -28,7 +100,82 @@ pub struct Status {
 pub struct FirstClassData {
     pub key: String,
     pub value: [u8; 32],
-    pub timestamp: u32
+    pub timestamp: u32,
+    // zeroed (`ValueKind::Unspecified`) for feeds initialized before it existed
+    pub metadata: FeedMetadata,
+    // set by `initialize_fcd_history`; updates then have to append to the history
+    pub has_history: bool,
+    // sum of `value * seconds held` up to `timestamp`, wrapping
+    pub cumulative: u128,
+    // set by `initialize_fcd_aggregation`; the value then comes from validator rounds
+    pub aggregated: bool,
+    // half width of the interval around `value` reported by the sources, in the units
+    // of `value`; zeroed when the last update did not report one
+    pub confidence: [u8; 32],
+    // whether the last update reported `confidence`, which is zero for unanimous rounds
+    pub has_confidence: bool,
+    // `FeedRegistry` page listing the feed; `None` for feeds initialized before the
+    // registry existed until `register_first_class_data` lists them
+    pub registry_page: Option<u32>
+}
+
+impl FirstClassData {
+    // space: 8 discriminator + 4 key length + 200 key + 32 value + 4 timestamp + 1 bump,
+    // as allocated since the first release. Keys are now capped at `MAX_FEED_KEY_LENGTH`,
+    // which leaves the rest of the key allowance to the fields appended after `timestamp`.
+    pub const SPACE: usize = 8 + 4 + 200 + 32 + 4 + 1;
+
+    /// `cumulative` extended to `timestamp` with the current value.
+    pub fn cumulative_at(&self, timestamp: u32) -> u128 {
+        cumulative_at(self.cumulative, &self.value, self.timestamp, timestamp)
+    }
+
+    /// Metadata of the feed, with Umbrella conventions for feeds that predate it.
+    pub fn resolved_metadata(&self) -> FeedMetadata {
+        if self.metadata.kind == ValueKind::Unspecified as u8 {
+            FeedMetadata::umbrella(&self.key)
+        } else {
+            self.metadata.clone()
+        }
+    }
+}
+
+/// `value` as an integer for time weighted averages, saturating at `u128::MAX`.
+pub fn numeric_value(value: &[u8; 32]) -> u128 {
+    if value[..16].iter().any(|byte| *byte != 0) {
+        return u128::MAX;
+    }
+    let mut low = [0u8; 16];
+    low.copy_from_slice(&value[16..]);
+    u128::from_be_bytes(low)
+}
+
+/// Accumulator `cumulative` at `since`, extended to `until` with `value`. Wraps like
+/// the Uniswap v2 accumulators: differences stay exact while the sum over the
+/// window fits in a `u128`.
+pub fn cumulative_at(cumulative: u128, value: &[u8; 32], since: u32, until: u32) -> u128 {
+    let elapsed = u128::from(until.saturating_sub(since));
+    cumulative.wrapping_add(numeric_value(value).wrapping_mul(elapsed))
+}
+
+/// Seconds after `close_first_class_data` before the same FCD account can be initialized again.
+pub const FCD_REUSE_COOLDOWN: i64 = 7 * 24 * 60 * 60;
+
+/// Left at `[b"closed", fcd]` by `close_first_class_data` and closed again when
+/// the FCD is re-initialized after `FCD_REUSE_COOLDOWN`.
+#[account]
+#[derive(Default)]
+pub struct ClosedFirstClassData {
+    pub closed_at: i64
+}
+
+impl ClosedFirstClassData {
+    // space: 8 discriminator + 8 closed_at
+    pub const SPACE: usize = 8 + 8;
+
+    pub fn reusable_at(&self) -> i64 {
+        self.closed_at + FCD_REUSE_COOLDOWN
+    }
 }
 
 #[account]
@@ -0,0 +1,77 @@
+use crate::state::aggregation::MAX_VALIDATORS;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+use std::convert::TryFrom;
+
+/// Size of an ABI-encoded block header: three 32 byte words.
+pub const EVM_HEADER_SIZE: usize = 3 * 32;
+
+/// Prefix `eth_sign` puts in front of a 32 byte hash before signing it.
+pub const ETH_SIGNED_MESSAGE_PREFIX: &[u8] = b"\x19Ethereum Signed Message:\n32";
+
+/// Ethereum addresses of the validators of the Umbrella sidechain, whose signatures
+/// `import_evm_block` counts, at `[b"evm_validators"]`.
+#[account]
+#[derive(Default)]
+pub struct EvmValidatorSet {
+    pub validators: Vec<[u8; 20]>,
+    // distinct validator signatures needed to import a block
+    pub quorum: u8
+}
+
+impl EvmValidatorSet {
+    // space: 8 discriminator + 4 validators length + validators + 1 quorum
+    pub const SPACE: usize = 8 + 4 + 20 * MAX_VALIDATORS + 1;
+
+    pub fn contains(&self, validator: &[u8; 20]) -> bool {
+        self.validators.contains(validator)
+    }
+}
+
+/// Block as signed on the sidechain by the Chain contract validators:
+/// `abi.encode(uint32 blockId, bytes32 root, uint32 timestamp)`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct EvmBlockHeader {
+    pub block_id: u32,
+    pub root: [u8; 32],
+    pub timestamp: u32
+}
+
+impl EvmBlockHeader {
+    /// Decodes an ABI-encoded header, `None` unless it is exactly three words with
+    /// `blockId` and `timestamp` fitting their `uint32`s.
+    pub fn decode(payload: &[u8]) -> Option<Self> {
+        if payload.len() != EVM_HEADER_SIZE {
+            return None;
+        }
+
+        let uint32 = |word: &[u8]| {
+            if word[..28].iter().any(|byte| *byte != 0) {
+                return None;
+            }
+            Some(u32::from_be_bytes(<[u8; 4]>::try_from(&word[28..]).ok()?))
+        };
+        Some(EvmBlockHeader {
+            block_id: uint32(&payload[..32])?,
+            root: <[u8; 32]>::try_from(&payload[32..64]).ok()?,
+            timestamp: uint32(&payload[64..])?
+        })
+    }
+
+    pub fn encode(&self) -> [u8; EVM_HEADER_SIZE] {
+        let mut payload = [0u8; EVM_HEADER_SIZE];
+        payload[28..32].copy_from_slice(&self.block_id.to_be_bytes());
+        payload[32..64].copy_from_slice(&self.root);
+        payload[92..].copy_from_slice(&self.timestamp.to_be_bytes());
+        payload
+    }
+}
+
+/// Message the secp256k1 program checks a validator signature of `payload` over:
+/// the `eth_sign` prefix and the keccak256 hash of the payload. The program hashes
+/// it again, so it recovers the signer of an `eth_sign` of the payload hash.
+pub fn evm_signed_message(payload: &[u8]) -> Vec<u8> {
+    let mut message = ETH_SIGNED_MESSAGE_PREFIX.to_vec();
+    message.extend_from_slice(&keccak::hash(payload).to_bytes());
+    message
+}
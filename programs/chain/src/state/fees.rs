@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+
+pub const MAX_FEE_EXEMPT: usize = 16;
+
+/// Calls charged under a `FeeSchedule`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeeKind {
+    /// `verify_proof_for_block`, `verify_against_latest` and `verify_message_inclusion`
+    Verify,
+    /// `get_fcd_observation`, `get_twap` and `get_cross_rate`
+    Read,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct FeeSchedule {
+    // SPL mint the fees are paid in, `Pubkey::default()` for lamports
+    pub mint: Pubkey,
+    pub verify_fee: u64,
+    pub read_fee: u64,
+    // price of `subscription_period` seconds without per call fees, 0 to offer no subscriptions
+    pub subscription_price: u64,
+    pub subscription_period: u32
+}
+
+impl FeeSchedule {
+    // space: 32 mint + 8 verify_fee + 8 read_fee + 8 subscription_price + 4 subscription_period
+    pub const SPACE: usize = 32 + 8 + 8 + 8 + 4;
+
+    pub fn in_tokens(&self) -> bool {
+        self.mint != Pubkey::default()
+    }
+
+    pub fn fee(&self, kind: FeeKind) -> u64 {
+        match kind {
+            FeeKind::Verify => self.verify_fee,
+            FeeKind::Read => self.read_fee,
+        }
+    }
+}
+
+/// Fees of verification and reads at `[b"fee_config"]`, charged while
+/// `Status.fees_enabled`. Lamport fees are collected on the account itself,
+/// token fees on token accounts it is the authority of.
+#[account]
+#[derive(Default)]
+pub struct FeeConfig {
+    pub schedule: FeeSchedule,
+    // keys calling for free, such as the owner's own programs
+    pub exempt: Vec<Pubkey>
+}
+
+impl FeeConfig {
+    // space: 8 discriminator + schedule + 4 exempt length + exempt
+    pub const SPACE: usize = 8 + FeeSchedule::SPACE + 4 + 32 * MAX_FEE_EXEMPT;
+
+    pub fn is_exempt(&self, key: &Pubkey) -> bool {
+        self.exempt.contains(key)
+    }
+}
+
+/// Prepaid calls of `consumer` free of per call fees, at `[b"subscription", consumer]`.
+#[account]
+#[derive(Default)]
+pub struct Subscription {
+    pub consumer: Pubkey,
+    pub paid_until: i64
+}
+
+impl Subscription {
+    // space: 8 discriminator + 32 consumer + 8 paid_until
+    pub const SPACE: usize = 8 + 32 + 8;
+
+    pub fn is_active(&self, now: i64) -> bool {
+        now < self.paid_until
+    }
+}
@@ -0,0 +1,83 @@
+use crate::state::chain::{cumulative_at, FirstClassData};
+use anchor_lang::prelude::*;
+
+/// Largest `FcdHistory` capacity, keeping the account within what `init` can allocate
+/// and the cost of (de)serializing it on every update reasonable.
+pub const MAX_HISTORY_LENGTH: u16 = 128;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Observation {
+    pub value: [u8; 32],
+    pub timestamp: u32,
+    // `FirstClassData.cumulative` when the value was set
+    pub cumulative: u128
+}
+
+impl Observation {
+    // space: 32 value + 4 timestamp + 16 cumulative
+    pub const SPACE: usize = 32 + 4 + 16;
+
+    /// The feed's accumulator at `timestamp`, if this observation was its value then.
+    pub fn cumulative_at(&self, timestamp: u32) -> u128 {
+        cumulative_at(self.cumulative, &self.value, self.timestamp, timestamp)
+    }
+}
+
+/// Last `capacity` values of a first class data feed, oldest first from `next` once full.
+#[account]
+#[derive(Default)]
+pub struct FcdHistory {
+    pub fcd: Pubkey,
+    pub capacity: u16,
+    // index the next observation is written to
+    pub next: u16,
+    pub observations: Vec<Observation>
+}
+
+impl FcdHistory {
+    // space: 8 discriminator + 32 fcd + 2 capacity + 2 next + 4 observations length + observations
+    pub fn space(capacity: u16) -> usize {
+        8 + 32 + 2 + 2 + 4 + usize::from(capacity) * Observation::SPACE
+    }
+
+    pub fn push(&mut self, observation: Observation) {
+        let next = usize::from(self.next);
+        if next < self.observations.len() {
+            self.observations[next] = observation;
+        } else {
+            self.observations.push(observation);
+        }
+        self.next = ((next + 1) % usize::from(self.capacity)) as u16;
+    }
+
+    /// Observations from oldest to newest.
+    pub fn ordered(&self) -> impl Iterator<Item = &Observation> {
+        let oldest = if self.observations.len() < usize::from(self.capacity) {
+            0
+        } else {
+            usize::from(self.next)
+        };
+        self.observations[oldest..].iter().chain(self.observations[..oldest].iter())
+    }
+
+    /// Newest observation whose timestamp is at or before `timestamp`.
+    pub fn at_or_before(&self, timestamp: u32) -> Option<Observation> {
+        let ordered: Vec<&Observation> = self.ordered().collect();
+        let count = ordered.partition_point(|observation| observation.timestamp <= timestamp);
+        count.checked_sub(1).map(|index| *ordered[index])
+    }
+
+    pub fn latest(&self) -> Option<Observation> {
+        self.ordered().last().copied()
+    }
+
+    /// Average value of `fcd` over the `window` seconds up to `now`, weighted by the
+    /// time each value was held and rounded down. `None` when the history doesn't
+    /// reach back to the start of the window.
+    pub fn twap(&self, fcd: &FirstClassData, now: u32, window: u32) -> Option<u128> {
+        let start = now.checked_sub(window)?;
+        let cumulative_start = self.at_or_before(start)?.cumulative_at(start);
+        let cumulative_end = fcd.cumulative_at(now);
+        Some(cumulative_end.wrapping_sub(cumulative_start) / u128::from(window))
+    }
+}
@@ -0,0 +1,65 @@
+use crate::errors::ChainError;
+use anchor_lang::prelude::*;
+
+pub const MAX_FEED_DESCRIPTION_LENGTH: usize = 64;
+
+/// Decimals of Umbrella numeric values; `FIXED_` keys hold raw bytes.
+pub const UMBRELLA_DECIMALS: u8 = 18;
+pub const FIXED_KEY_PREFIX: &str = "FIXED_";
+
+/// What the value of a feed measures, stored as `FeedMetadata.kind`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValueKind {
+    /// Feeds initialized before metadata existed, read with Umbrella conventions.
+    Unspecified = 0,
+    Price = 1,
+    Volume = 2,
+    /// 32 bytes without numeric meaning, like roots or hashes.
+    Raw = 3,
+}
+
+impl ValueKind {
+    pub fn from_u8(kind: u8) -> Option<Self> {
+        match kind {
+            0 => Some(ValueKind::Unspecified),
+            1 => Some(ValueKind::Price),
+            2 => Some(ValueKind::Volume),
+            3 => Some(ValueKind::Raw),
+            _ => None,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct FeedMetadata {
+    pub decimals: u8,
+    // `ValueKind`
+    pub kind: u8,
+    pub description: String
+}
+
+impl FeedMetadata {
+    // space: 1 decimals + 1 kind + 4 description length + 64 description
+    pub const SPACE: usize = 1 + 1 + 4 + MAX_FEED_DESCRIPTION_LENGTH;
+
+    /// Metadata of Umbrella feeds: 18 decimal prices, raw bytes for `FIXED_` keys.
+    pub fn umbrella(key: &str) -> Self {
+        if key.starts_with(FIXED_KEY_PREFIX) {
+            FeedMetadata { decimals: 0, kind: ValueKind::Raw as u8, description: String::new() }
+        } else {
+            FeedMetadata { decimals: UMBRELLA_DECIMALS, kind: ValueKind::Price as u8, description: String::new() }
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            matches!(ValueKind::from_u8(self.kind), Some(kind) if kind != ValueKind::Unspecified),
+            ChainError::UnknownValueKind
+        );
+        require!(
+            self.description.len() <= MAX_FEED_DESCRIPTION_LENGTH,
+            ChainError::FeedDescriptionTooLong
+        );
+        Ok(())
+    }
+}
@@ -1,3 +1,19 @@
+pub use aggregation::*;
 pub use chain::*;
+pub use evm::*;
+pub use fees::*;
+pub use history::*;
+pub use metadata::*;
+pub use registry::*;
+pub use rewards::*;
+pub use staking::*;
 
+pub mod aggregation;
 pub mod chain;
+pub mod evm;
+pub mod fees;
+pub mod history;
+pub mod metadata;
+pub mod registry;
+pub mod rewards;
+pub mod staking;
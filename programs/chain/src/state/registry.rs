@@ -0,0 +1,51 @@
+use crate::state::metadata::FeedMetadata;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::MAX_RETURN_DATA;
+
+/// Entries held by one `FeedRegistry` page.
+pub const FEEDS_PER_PAGE: usize = 32;
+pub const MAX_FEED_KEY_LENGTH: usize = 32;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct FeedEntry {
+    pub key: String,
+    pub fcd: Pubkey,
+    pub metadata: FeedMetadata
+}
+
+impl FeedEntry {
+    // space: 4 key length + 32 key + 32 fcd + metadata
+    pub const SPACE: usize = 4 + MAX_FEED_KEY_LENGTH + 32 + FeedMetadata::SPACE;
+}
+
+/// One page of registered feeds, at `[b"feed_registry", page as u32 le]`.
+#[account]
+#[derive(Default)]
+pub struct FeedRegistry {
+    pub page: u32,
+    pub entries: Vec<FeedEntry>
+}
+
+impl FeedRegistry {
+    // space: 8 discriminator + 4 page + 4 entries length + entries
+    pub const SPACE: usize = 8 + 4 + 4 + FEEDS_PER_PAGE * FeedEntry::SPACE;
+
+    pub fn position(&self, key: &str) -> Option<usize> {
+        self.entries.iter().position(|entry| entry.key == key)
+    }
+
+    /// Up to `limit` entries from `start` whose serialized `Vec` fits in return data.
+    pub fn returned_entries(&self, start: usize, limit: usize) -> Vec<FeedEntry> {
+        let mut size = 4;
+        self.entries
+            .iter()
+            .skip(start)
+            .take(limit)
+            .take_while(|entry| {
+                size += entry.try_to_vec().map(|bytes| bytes.len()).unwrap_or(MAX_RETURN_DATA);
+                size <= MAX_RETURN_DATA
+            })
+            .cloned()
+            .collect()
+    }
+}
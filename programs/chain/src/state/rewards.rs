@@ -0,0 +1,96 @@
+use anchor_lang::prelude::*;
+
+/// Keys a `RewardVault` tracks, enough for every validator besides the owner.
+pub const MAX_REWARDED: usize = 32;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RewardEntry {
+    pub key: Pubkey,
+    // signatures contributed since the last distribution
+    pub points: u64,
+    // lamports distributed to the key and not claimed yet
+    pub owed: u64
+}
+
+impl RewardEntry {
+    // space: 32 key + 8 points + 8 owed
+    pub const SPACE: usize = 32 + 8 + 8;
+}
+
+/// Lamports set aside for the keys submitting data, at `[b"rewards"]`. Every
+/// signature behind a block, an owner update or a finalized aggregation round
+/// earns its key a point, and `distribute_rewards` shares lamports out in
+/// proportion to the points since the last distribution. The account holds its
+/// rent plus `undistributed` plus the `owed` of every entry.
+#[account]
+#[derive(Default)]
+pub struct RewardVault {
+    // sum of the points of all entries
+    pub total_points: u64,
+    // lamports carried to the next distribution, such as rounding remainders
+    pub undistributed: u64,
+    pub entries: Vec<RewardEntry>
+}
+
+impl RewardVault {
+    // space: 8 discriminator + 8 total_points + 8 undistributed + 4 entries length + entries
+    pub const SPACE: usize = 8 + 8 + 8 + 4 + MAX_REWARDED * RewardEntry::SPACE;
+
+    /// Credits `key` with a point, returning false when the vault has no room left
+    /// for a key it doesn't track yet.
+    pub fn accrue(&mut self, key: Pubkey) -> bool {
+        match self.entries.iter().position(|entry| entry.key == key) {
+            Some(index) => self.entries[index].points += 1,
+            None if self.entries.len() < MAX_REWARDED => self.entries.push(RewardEntry {
+                key,
+                points: 1,
+                owed: 0
+            }),
+            None => return false,
+        }
+        self.total_points += 1;
+        true
+    }
+
+    /// Shares `amount` plus the undistributed lamports out in proportion to the
+    /// points of each entry, rounding down, and starts counting points afresh.
+    /// Without points everything stays undistributed.
+    pub fn distribute(&mut self, amount: u64) {
+        let pot = self.undistributed + amount;
+        if self.total_points == 0 {
+            self.undistributed = pot;
+            return;
+        }
+
+        let mut distributed = 0;
+        for entry in self.entries.iter_mut() {
+            let share = (u128::from(pot) * u128::from(entry.points) / u128::from(self.total_points)) as u64;
+            entry.owed += share;
+            entry.points = 0;
+            distributed += share;
+        }
+        self.undistributed = pot - distributed;
+        self.total_points = 0;
+    }
+
+    pub fn owed(&self, key: &Pubkey) -> u64 {
+        self.entries
+            .iter()
+            .find(|entry| entry.key == *key)
+            .map_or(0, |entry| entry.owed)
+    }
+
+    /// Clears and returns what is owed to `key`, dropping its entry when it has
+    /// no points either so the room goes to other keys.
+    pub fn take_owed(&mut self, key: &Pubkey) -> u64 {
+        let index = match self.entries.iter().position(|entry| entry.key == *key) {
+            Some(index) => index,
+            None => return 0,
+        };
+        let owed = std::mem::take(&mut self.entries[index].owed);
+        if self.entries[index].points == 0 {
+            self.entries.swap_remove(index);
+        }
+        owed
+    }
+}
@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+
+/// Prefix of the message a validator signs to attest the root of a block, so the
+/// signature can't be replayed as one over anything else.
+pub const ATTESTATION_DOMAIN: &[u8] = b"umbrella-solana:block-attestation";
+
+/// Bytes a validator signs with its key to attest `root` as the root of `block_id`:
+/// the domain followed by the little endian block id and the root.
+pub fn attestation_message(block_id: u32, root: &[u8; 32]) -> Vec<u8> {
+    let mut message = Vec::with_capacity(ATTESTATION_DOMAIN.len() + 4 + 32);
+    message.extend_from_slice(ATTESTATION_DOMAIN);
+    message.extend_from_slice(&block_id.to_le_bytes());
+    message.extend_from_slice(root);
+    message
+}
+
+/// Staking of validators in an SPL token, at `[b"stake_config"]`. The stakes are held
+/// in `vault`, a token account of `mint` the config is the authority of.
+#[account]
+#[derive(Default)]
+pub struct StakeConfig {
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    // token account receiving slashed stake
+    pub treasury: Pubkey,
+    // share of a validator's stake taken per equivocation, in basis points
+    pub slash_bps: u16,
+    // seconds unstaked tokens stay slashable before they can be withdrawn
+    pub unbonding_period: u32
+}
+
+impl StakeConfig {
+    // space: 8 discriminator + 32 mint + 32 vault + 32 treasury + 2 slash_bps + 4 unbonding_period
+    pub const SPACE: usize = 8 + 32 + 32 + 32 + 2 + 4;
+}
+
+/// Tokens a validator staked, at `[b"stake", validator]`.
+#[account]
+#[derive(Default)]
+pub struct ValidatorStake {
+    pub validator: Pubkey,
+    pub amount: u64,
+    // requested to be unstaked, withdrawable from `unbonding_until`
+    pub unbonding: u64,
+    pub unbonding_until: i64
+}
+
+impl ValidatorStake {
+    // space: 8 discriminator + 32 validator + 8 amount + 8 unbonding + 8 unbonding_until
+    pub const SPACE: usize = 8 + 32 + 8 + 8 + 8;
+
+    /// Staked and unbonding tokens, all of which can be slashed.
+    pub fn slashable(&self) -> u64 {
+        self.amount + self.unbonding
+    }
+
+    /// Takes `slash_bps` of the slashable tokens, from the staked ones first, and
+    /// returns how many were taken.
+    pub fn slash(&mut self, slash_bps: u16) -> u64 {
+        let penalty = (u128::from(self.slashable()) * u128::from(slash_bps) / 10_000) as u64;
+        let from_amount = penalty.min(self.amount);
+        self.amount -= from_amount;
+        self.unbonding -= penalty - from_amount;
+        penalty
+    }
+}
+
+/// Equivocation of a validator at a block that was slashed, at
+/// `[b"slashed", validator, block_id]`. Its existence keeps the same evidence from
+/// being reported twice.
+#[account]
+#[derive(Default)]
+pub struct SlashedBlock {
+    pub reporter: Pubkey,
+    pub slashed: u64
+}
+
+impl SlashedBlock {
+    // space: 8 discriminator + 32 reporter + 8 slashed
+    pub const SPACE: usize = 8 + 32 + 8;
+}
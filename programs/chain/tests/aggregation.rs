@@ -0,0 +1,592 @@
+#![cfg(any(feature = "test-bpf", feature = "test-sbf"))]
+
+mod common;
+
+use anchor_lang::prelude::*;
+use chain::errors::ChainError;
+use chain::state::{
+    FcdRound, FeedMetadata, FirstClassData, Submission, ValidatorSet, FCD_REUSE_COOLDOWN,
+    MAX_VALIDATORS, PAUSE_FCD_UPDATE,
+};
+use common::*;
+use solana_program_test::ProgramTest;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transport::TransportError;
+
+const START: u32 = 1651640000;
+// cluster clock of the tests, after the data timestamps submitted
+const NOW: u32 = START + 600;
+const WINDOW: u32 = 60;
+
+struct Aggregation {
+    harness: Harness,
+    fcd: Pubkey,
+    validators: Vec<Keypair>,
+}
+
+fn add_aggregated_fcd(program_test: &mut ProgramTest, key: &str, aggregated: bool) -> Pubkey {
+    let fcd = fcd_address(key).0;
+    add_program_account(
+        program_test,
+        fcd,
+        &FirstClassData {
+            key: key.to_string(),
+            value: value(1),
+            timestamp: START,
+            metadata: FeedMetadata::umbrella(key),
+            has_history: false,
+            cumulative: 0,
+            aggregated,
+            confidence: [0u8; 32],
+            has_confidence: false,
+            registry_page: None,
+        },
+        FirstClassData::SPACE,
+    );
+    if aggregated {
+        add_program_account(
+            program_test,
+            fcd_round_address(&fcd),
+            &FcdRound {
+                fcd,
+                ..FcdRound::default()
+            },
+            FcdRound::SPACE,
+        );
+    }
+    fcd
+}
+
+/// `ETH-USD` aggregated from `count` validators, the way `set_validators` and
+/// `initialize_fcd_aggregation` leave it.
+async fn aggregation(count: usize, quorum: u8) -> Aggregation {
+    let validators: Vec<Keypair> = (0..count).map(|_| Keypair::new()).collect();
+    let keys = validators
+        .iter()
+        .map(|validator| validator.pubkey())
+        .collect();
+    let mut fcd = Pubkey::default();
+    let harness = Harness::initialized_with(|program_test| {
+        add_program_account(
+            program_test,
+            validator_set_address(),
+            &ValidatorSet {
+                validators: keys,
+                quorum,
+                window: WINDOW,
+            },
+            ValidatorSet::SPACE,
+        );
+        fcd = add_aggregated_fcd(program_test, "ETH-USD", true);
+    })
+    .await;
+    let mut aggregation = Aggregation {
+        harness,
+        fcd,
+        validators,
+    };
+    aggregation.harness.set_time(NOW.into()).await;
+    aggregation
+}
+
+impl Aggregation {
+    async fn submit(
+        &mut self,
+        validator: usize,
+        n: u128,
+        timestamp: u32,
+    ) -> std::result::Result<(), TransportError> {
+        let validator = clone_keypair(&self.validators[validator]);
+        let ix = submit_fcd_observation_ix(
+            &validator.pubkey(),
+            self.fcd,
+            "ETH-USD",
+            value(n),
+            timestamp,
+        );
+        self.harness.process(&[ix], &[&validator]).await
+    }
+
+    async fn fcd(&mut self) -> FirstClassData {
+        self.harness.account(self.fcd).await
+    }
+
+    async fn round(&mut self) -> FcdRound {
+        self.harness.account(fcd_round_address(&self.fcd)).await
+    }
+}
+
+#[tokio::test]
+async fn sets_validators() {
+    let mut harness = Harness::initialized().await;
+    let owner = harness.owner();
+    let validators = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+
+    harness
+        .process(
+            &[set_validators_ix(
+                &owner.pubkey(),
+                validators.clone(),
+                2,
+                60,
+            )],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+    harness
+        .process(
+            &[set_validators_ix(
+                &owner.pubkey(),
+                validators[..1].to_vec(),
+                1,
+                30,
+            )],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+
+    let validator_set: ValidatorSet = harness.account(validator_set_address()).await;
+    assert_eq!(validator_set.validators, validators[..1].to_vec());
+    assert_eq!(validator_set.quorum, 1);
+    assert_eq!(validator_set.window, 30);
+}
+
+#[tokio::test]
+async fn fails_to_set_an_invalid_validator_set() {
+    let Aggregation { mut harness, .. } = aggregation(1, 1).await;
+    let owner = harness.owner();
+    let validator = Pubkey::new_unique();
+    let cases = [
+        (
+            vec![Pubkey::new_unique(); MAX_VALIDATORS + 1],
+            1,
+            WINDOW,
+            ChainError::TooManyValidators,
+        ),
+        (
+            vec![validator, validator],
+            1,
+            WINDOW,
+            ChainError::DuplicateValidator,
+        ),
+        (vec![validator], 0, WINDOW, ChainError::InvalidQuorum),
+        (vec![validator], 2, WINDOW, ChainError::InvalidQuorum),
+        (vec![validator], 1, 0, ChainError::InvalidRoundWindow),
+    ];
+
+    for (validators, quorum, window, error) in cases {
+        let result = harness
+            .process(
+                &[set_validators_ix(
+                    &owner.pubkey(),
+                    validators,
+                    quorum,
+                    window,
+                )],
+                &[&owner],
+            )
+            .await;
+
+        assert_chain_error(result, error);
+    }
+}
+
+#[tokio::test]
+async fn fails_to_set_validators_by_unauthorized_user() {
+    let Aggregation { mut harness, .. } = aggregation(1, 1).await;
+    let intruder = harness.funded_keypair().await;
+
+    let result = harness
+        .process(
+            &[set_validators_ix(
+                &intruder.pubkey(),
+                vec![intruder.pubkey()],
+                1,
+                WINDOW,
+            )],
+            &[&intruder],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::OnlyOwnerViolation);
+}
+
+#[tokio::test]
+async fn initializes_aggregation_of_a_feed() {
+    let mut fcd = Pubkey::default();
+    let mut harness = Harness::initialized_with(|program_test| {
+        fcd = add_aggregated_fcd(program_test, "ETH-USD", false)
+    })
+    .await;
+    let owner = harness.owner();
+
+    harness
+        .process(
+            &[initialize_fcd_aggregation_ix(
+                &owner.pubkey(),
+                fcd,
+                "ETH-USD",
+            )],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+
+    let stored: FirstClassData = harness.account(fcd).await;
+    assert!(stored.aggregated);
+    let round: FcdRound = harness.account(fcd_round_address(&fcd)).await;
+    assert_eq!(round.fcd, fcd);
+    assert!(round.submissions.is_empty());
+}
+
+#[tokio::test]
+async fn finalizes_to_the_median_once_the_quorum_is_reached() {
+    let mut aggregation = aggregation(5, 3).await;
+    let timestamp = START + 60;
+
+    aggregation.submit(0, 100, timestamp).await.unwrap();
+    aggregation.submit(1, 300, timestamp).await.unwrap();
+    assert_eq!(aggregation.fcd().await.value, value(1));
+    assert_eq!(aggregation.round().await.submissions.len(), 2);
+
+    aggregation.submit(2, 200, timestamp).await.unwrap();
+
+    let fcd = aggregation.fcd().await;
+    assert_eq!(fcd.value, value(200));
+    assert_eq!(fcd.timestamp, timestamp);
+    assert_eq!(fcd.cumulative, 60);
+    assert_eq!(fcd.confidence, value(100));
+    assert!(fcd.has_confidence);
+    assert!(aggregation.round().await.submissions.is_empty());
+
+    // the round is over, late validators are too late
+    let result = aggregation.submit(3, 400, timestamp).await;
+    assert_chain_error(result, ChainError::CannotSubmitOlderData);
+}
+
+#[tokio::test]
+async fn reports_a_zero_confidence_for_a_unanimous_round() {
+    let mut aggregation = aggregation(3, 2).await;
+    let timestamp = START + 60;
+
+    aggregation.submit(0, 200, timestamp).await.unwrap();
+    aggregation.submit(1, 200, timestamp).await.unwrap();
+
+    let fcd = aggregation.fcd().await;
+    assert_eq!(fcd.value, value(200));
+    assert_eq!(fcd.confidence, [0u8; 32]);
+    assert!(fcd.has_confidence);
+}
+
+#[tokio::test]
+async fn drops_a_round_that_misses_the_window() {
+    let mut aggregation = aggregation(3, 2).await;
+    let timestamp = START + 60;
+
+    aggregation.submit(0, 100, timestamp).await.unwrap();
+    aggregation
+        .harness
+        .set_time((NOW + WINDOW + 1).into())
+        .await;
+    aggregation.submit(1, 300, timestamp).await.unwrap();
+
+    assert_eq!(aggregation.fcd().await.value, value(1));
+    let round = aggregation.round().await;
+    assert_eq!(round.submissions.len(), 1);
+    assert_eq!(round.submissions[0].value, value(300));
+
+    aggregation.submit(2, 200, timestamp).await.unwrap();
+    assert_eq!(aggregation.fcd().await.value, value(200));
+}
+
+#[tokio::test]
+async fn keeps_an_open_round_against_other_timestamps() {
+    let mut aggregation = aggregation(3, 2).await;
+    let timestamp = START + 60;
+
+    aggregation.submit(0, 100, timestamp).await.unwrap();
+    let result = aggregation.submit(1, 300, timestamp + 1).await;
+    assert_chain_error(result, ChainError::RoundInProgress);
+    let result = aggregation.submit(1, 300, timestamp - 1).await;
+    assert_chain_error(result, ChainError::RoundInProgress);
+
+    aggregation.submit(2, 200, timestamp).await.unwrap();
+    let fcd = aggregation.fcd().await;
+    assert_eq!(fcd.value, value(100));
+    assert_eq!(fcd.timestamp, timestamp);
+}
+
+#[tokio::test]
+async fn starts_over_for_another_timestamp_once_the_round_expired() {
+    let mut aggregation = aggregation(3, 2).await;
+
+    aggregation.submit(0, 100, START + 60).await.unwrap();
+    aggregation
+        .harness
+        .set_time((NOW + WINDOW + 1).into())
+        .await;
+    aggregation.submit(1, 300, START + 120).await.unwrap();
+
+    let round = aggregation.round().await;
+    assert_eq!(round.timestamp, START + 120);
+    assert_eq!(round.submissions.len(), 1);
+    let result = aggregation.submit(2, 100, START + 60).await;
+    assert_chain_error(result, ChainError::RoundInProgress);
+}
+
+#[tokio::test]
+async fn fails_to_submit_a_timestamp_ahead_of_the_clock() {
+    let mut aggregation = aggregation(3, 2).await;
+
+    let result = aggregation.submit(0, 100, NOW + 1).await;
+
+    assert_chain_error(result, ChainError::FutureTimestamp);
+    assert!(aggregation.round().await.submissions.is_empty());
+}
+
+#[tokio::test]
+async fn fails_to_submit_twice_in_a_round() {
+    let mut aggregation = aggregation(3, 2).await;
+
+    aggregation.submit(0, 100, START + 60).await.unwrap();
+    let result = aggregation.submit(0, 101, START + 60).await;
+
+    assert_chain_error(result, ChainError::AlreadySubmitted);
+}
+
+#[tokio::test]
+async fn fails_to_submit_from_outside_the_validator_set() {
+    let mut aggregation = aggregation(3, 2).await;
+    let intruder = Keypair::new();
+
+    let result = aggregation
+        .harness
+        .process(
+            &[submit_fcd_observation_ix(
+                &intruder.pubkey(),
+                aggregation.fcd,
+                "ETH-USD",
+                value(100),
+                START + 60,
+            )],
+            &[&intruder],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::NotValidator);
+}
+
+#[tokio::test]
+async fn fails_to_submit_within_the_padding() {
+    let mut aggregation = aggregation(3, 2).await;
+
+    let result = aggregation.submit(0, 100, START + PADDING).await;
+
+    assert_chain_error(result, ChainError::DoNotSpam);
+}
+
+#[tokio::test]
+async fn fails_to_submit_while_fcd_updates_are_paused() {
+    let mut aggregation = aggregation(3, 2).await;
+    let owner = aggregation.harness.owner();
+    aggregation
+        .harness
+        .process(&[pause_ix(&owner.pubkey(), PAUSE_FCD_UPDATE)], &[&owner])
+        .await
+        .unwrap();
+
+    let result = aggregation.submit(0, 100, START + 60).await;
+
+    assert_chain_error(result, ChainError::Paused);
+}
+
+#[tokio::test]
+async fn fails_to_update_an_aggregated_feed_directly() {
+    let mut aggregation = aggregation(3, 2).await;
+    let owner = aggregation.harness.owner();
+
+    let result = aggregation
+        .harness
+        .process(
+            &[update_fcd_ix(
+                &owner.pubkey(),
+                aggregation.fcd,
+                "ETH-USD",
+                value(100),
+                START + 60,
+            )],
+            &[&owner],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::AggregatedFeed);
+}
+
+#[tokio::test]
+async fn fails_to_submit_to_a_feed_updated_by_the_owner() {
+    let validator = Keypair::new();
+    let mut fcd = Pubkey::default();
+    let mut harness = Harness::initialized_with(|program_test| {
+        add_program_account(
+            program_test,
+            validator_set_address(),
+            &ValidatorSet {
+                validators: vec![validator.pubkey()],
+                quorum: 1,
+                window: WINDOW,
+            },
+            ValidatorSet::SPACE,
+        );
+        fcd = add_aggregated_fcd(program_test, "ETH-USD", false);
+        // a round left over from before the feed was closed and initialized again
+        add_program_account(
+            program_test,
+            fcd_round_address(&fcd),
+            &FcdRound {
+                fcd,
+                ..FcdRound::default()
+            },
+            FcdRound::SPACE,
+        );
+    })
+    .await;
+    harness.set_time(NOW.into()).await;
+
+    let result = harness
+        .process(
+            &[submit_fcd_observation_ix(
+                &validator.pubkey(),
+                fcd,
+                "ETH-USD",
+                value(100),
+                START + 60,
+            )],
+            &[&validator],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::FeedNotAggregated);
+}
+
+#[tokio::test]
+async fn closes_the_round_with_the_feed() {
+    let validator = Keypair::new();
+    let mut harness = Harness::initialized_with(|program_test| {
+        add_program_account(
+            program_test,
+            validator_set_address(),
+            &ValidatorSet {
+                validators: vec![validator.pubkey()],
+                quorum: 2,
+                window: WINDOW,
+            },
+            ValidatorSet::SPACE,
+        );
+    })
+    .await;
+    let owner = harness.owner();
+    let fcd = fcd_address("ETH-USD").0;
+    let submit = |timestamp| {
+        submit_fcd_observation_ix(&validator.pubkey(), fcd, "ETH-USD", value(100), timestamp)
+    };
+    harness.set_time(NOW.into()).await;
+    harness
+        .process(
+            &[
+                initialize_fcd_ix(&owner.pubkey(), "ETH-USD", value(1), START),
+                initialize_fcd_aggregation_ix(&owner.pubkey(), fcd, "ETH-USD"),
+            ],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+    harness
+        .process(&[submit(START + 60)], &[&validator])
+        .await
+        .unwrap();
+
+    harness
+        .process(
+            &[close_fcd_ix(
+                &owner.pubkey(),
+                fcd,
+                "ETH-USD",
+                &owner.pubkey(),
+            )],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+    assert!(!harness.exists(fcd_round_address(&fcd)).await);
+
+    // the feed initialized again is the owner's, until aggregation is set up anew
+    let reopened = i64::from(NOW) + FCD_REUSE_COOLDOWN;
+    harness.set_time(reopened).await;
+    harness
+        .process(
+            &[initialize_fcd_ix(
+                &owner.pubkey(),
+                "ETH-USD",
+                value(2),
+                reopened as u32,
+            )],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+    // with the round gone, there is nothing to submit to
+    harness.set_time(reopened + 60).await;
+    let result = harness
+        .process(&[submit(reopened as u32 + 60)], &[&validator])
+        .await;
+    assert!(result.is_err());
+
+    harness
+        .process(
+            &[initialize_fcd_aggregation_ix(
+                &owner.pubkey(),
+                fcd,
+                "ETH-USD",
+            )],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+    let round: FcdRound = harness.account(fcd_round_address(&fcd)).await;
+    assert!(round.submissions.is_empty());
+}
+
+fn round(values: &[u128]) -> FcdRound {
+    FcdRound {
+        submissions: values
+            .iter()
+            .map(|n| Submission {
+                validator: Pubkey::new_unique(),
+                value: value(*n),
+            })
+            .collect(),
+        ..FcdRound::default()
+    }
+}
+
+#[test]
+fn takes_the_lower_median() {
+    assert_eq!(round(&[]).median(), None);
+    assert_eq!(round(&[7]).median(), Some(value(7)));
+    assert_eq!(round(&[4, 1, 3, 2]).median(), Some(value(2)));
+    assert_eq!(round(&[300, 100, 1 << 100]).median(), Some(value(300)));
+}
+
+#[test]
+fn takes_half_the_spread_as_confidence() {
+    assert_eq!(round(&[]).half_spread(), None);
+    assert_eq!(round(&[7]).half_spread(), Some(value(0)));
+    assert_eq!(round(&[4, 1, 3, 2]).half_spread(), Some(value(1)));
+    assert_eq!(round(&[100, 301, 200]).half_spread(), Some(value(100)));
+    assert_eq!(
+        round(&[1 << 64, (1 << 100) + (1 << 64)]).half_spread(),
+        Some(value(1 << 99))
+    );
+}
@@ -0,0 +1,727 @@
+#![cfg(any(feature = "test-bpf", feature = "test-sbf"))]
+
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::Pubkey;
+use chain::errors::ChainError;
+use chain::merkle::HashScheme;
+use chain::state::{
+    Authority, Block, ClosedFirstClassData, FirstClassData, Status, FCD_REUSE_COOLDOWN,
+};
+use common::*;
+use solana_sdk::signature::{Keypair, Signer};
+
+// The initializer keypair is not available here, so only the rejecting path of
+// `initialize` is exercised; other tests start from `Harness::initialized`.
+#[tokio::test]
+async fn fails_to_initialize_from_an_unauthorized_account() {
+    let mut harness = Harness::start(program_test(), Keypair::new()).await;
+    let owner = harness.owner();
+
+    let result = harness
+        .process(
+            &[instruction(
+                chain::accounts::Initialize {
+                    initializer: owner.pubkey(),
+                    authority: authority_address(),
+                    status: status_address(),
+                    system_program: anchor_lang::system_program::ID,
+                },
+                chain::instruction::Initialize { padding: PADDING },
+            )],
+            &[&owner],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::NotInitializer);
+    assert!(!harness.exists(authority_address()).await);
+}
+
+#[tokio::test]
+async fn fails_to_initialize_again() {
+    let mut harness = Harness::initialized().await;
+    let owner = harness.owner();
+
+    let result = harness
+        .process(
+            &[instruction(
+                chain::accounts::Initialize {
+                    initializer: owner.pubkey(),
+                    authority: authority_address(),
+                    status: status_address(),
+                    system_program: anchor_lang::system_program::ID,
+                },
+                chain::instruction::Initialize { padding: 0 },
+            )],
+            &[&owner],
+        )
+        .await;
+
+    // system program: account already in use
+    assert_eq!(error_code(result), 0);
+    let status: Status = harness.account(status_address()).await;
+    assert_eq!(status.padding, PADDING);
+}
+
+#[tokio::test]
+async fn fails_to_submit_before_initialized() {
+    let mut harness = Harness::start(program_test(), Keypair::new()).await;
+
+    let result = harness.submit(1, [1; 32], 1651640000).await;
+
+    assert_eq!(
+        error_code(result),
+        u32::from(ErrorCode::AccountNotInitialized)
+    );
+}
+
+#[tokio::test]
+async fn transfers_ownership() {
+    let mut harness = Harness::initialized().await;
+    let owner = harness.owner();
+    let new_owner = Keypair::new();
+
+    harness
+        .process(
+            &[transfer_ownership_ix(&owner.pubkey(), &new_owner.pubkey())],
+            &[&owner, &new_owner],
+        )
+        .await
+        .unwrap();
+
+    let authority: Authority = harness.account(authority_address()).await;
+    assert_eq!(authority.owner, new_owner.pubkey());
+
+    let result = harness.submit(1, [1; 32], 1651640000).await;
+    assert_chain_error(result, ChainError::OnlyOwnerViolation);
+
+    harness
+        .process(
+            &[submit_ix(&new_owner.pubkey(), 1, [1; 32], 1651640000)],
+            &[&new_owner],
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn fails_to_transfer_ownership_without_new_owner_signature() {
+    let mut harness = Harness::initialized().await;
+    let owner = harness.owner();
+    let new_owner = Keypair::new();
+
+    let mut ix = transfer_ownership_ix(&owner.pubkey(), &new_owner.pubkey());
+    ix.accounts[2].is_signer = false;
+    let result = harness.process(&[ix], &[&owner]).await;
+
+    assert_eq!(error_code(result), u32::from(ErrorCode::AccountNotSigner));
+    let authority: Authority = harness.account(authority_address()).await;
+    assert_eq!(authority.owner, owner.pubkey());
+}
+
+#[tokio::test]
+async fn fails_to_transfer_ownership_by_unauthorized_user() {
+    let mut harness = Harness::initialized().await;
+    let intruder = Keypair::new();
+
+    let result = harness
+        .process(
+            &[transfer_ownership_ix(
+                &intruder.pubkey(),
+                &intruder.pubkey(),
+            )],
+            &[&intruder],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::OnlyOwnerViolation);
+}
+
+#[tokio::test]
+async fn sets_padding() {
+    let mut harness = Harness::initialized().await;
+    let owner = harness.owner();
+
+    harness
+        .process(&[set_padding_ix(&owner.pubkey(), 60)], &[&owner])
+        .await
+        .unwrap();
+
+    let status: Status = harness.account(status_address()).await;
+    assert_eq!(status.padding, 60);
+}
+
+#[tokio::test]
+async fn fails_to_set_padding_by_unauthorized_user() {
+    let mut harness = Harness::initialized().await;
+    let intruder = Keypair::new();
+
+    let result = harness
+        .process(&[set_padding_ix(&intruder.pubkey(), 0)], &[&intruder])
+        .await;
+
+    assert_chain_error(result, ChainError::OnlyOwnerViolation);
+}
+
+#[tokio::test]
+async fn sets_max_proof_length() {
+    let mut harness = Harness::initialized().await;
+    let owner = harness.owner();
+    let status: Status = harness.account(status_address()).await;
+    assert_eq!(status.max_proof_length(), 32);
+
+    harness
+        .process(&[set_max_proof_length_ix(&owner.pubkey(), 20)], &[&owner])
+        .await
+        .unwrap();
+
+    let status: Status = harness.account(status_address()).await;
+    assert_eq!(status.max_proof_length, 20);
+    assert_eq!(status.max_proof_length(), 20);
+}
+
+#[tokio::test]
+async fn fails_to_set_max_proof_length_by_unauthorized_user() {
+    let mut harness = Harness::initialized().await;
+    let intruder = Keypair::new();
+
+    let result = harness
+        .process(
+            &[set_max_proof_length_ix(&intruder.pubkey(), 255)],
+            &[&intruder],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::OnlyOwnerViolation);
+}
+
+#[tokio::test]
+async fn submits_blocks() {
+    let mut harness = Harness::initialized().await;
+    let root = [7; 32];
+
+    harness.submit(1335, root, 1651641200).await.unwrap();
+
+    let block: Block = harness.account(block_address(1335).0).await;
+    assert_eq!(block.block_id, 1335);
+    assert_eq!(block.root, root);
+    assert_eq!(block.timestamp, 1651641200);
+
+    let status: Status = harness.account(status_address()).await;
+    assert_eq!(status.last_id, 1335);
+    assert_eq!(status.last_data_timestamp, 1651641200);
+    assert_eq!(status.next_block_id, 1336);
+
+    harness
+        .submit(1336, root, 1651641200 + PADDING + 1)
+        .await
+        .unwrap();
+    let status: Status = harness.account(status_address()).await;
+    assert_eq!(status.last_id, 1336);
+}
+
+#[tokio::test]
+async fn submits_blocks_with_a_hash_scheme() {
+    let mut harness = Harness::initialized().await;
+    let owner = harness.owner();
+
+    harness.submit(1, [1; 32], 1651640000).await.unwrap();
+    harness
+        .process(
+            &[submit_with_hash_scheme_ix(
+                &owner.pubkey(),
+                2,
+                [2; 32],
+                1651650000,
+                HashScheme::Prefixed as u8,
+            )],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+
+    let legacy: Block = harness.account(block_address(1).0).await;
+    assert_eq!(legacy.hash_scheme, HashScheme::Legacy as u8);
+    let prefixed: Block = harness.account(block_address(2).0).await;
+    assert_eq!(prefixed.hash_scheme, HashScheme::Prefixed as u8);
+}
+
+#[tokio::test]
+async fn fails_to_submit_an_unknown_hash_scheme() {
+    let mut harness = Harness::initialized().await;
+    let owner = harness.owner();
+
+    let result = harness
+        .process(
+            &[submit_with_hash_scheme_ix(
+                &owner.pubkey(),
+                1,
+                [1; 32],
+                1651640000,
+                2,
+            )],
+            &[&owner],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::UnknownHashScheme);
+}
+
+#[tokio::test]
+async fn fails_to_submit_a_block_under_another_seed() {
+    let mut harness = Harness::initialized().await;
+    let owner = harness.owner();
+    // the account of block 2 initialized with block 1
+    let (block, seed) = block_address(2);
+    let ix = instruction(
+        chain::accounts::Submit {
+            owner: owner.pubkey(),
+            authority: authority_address(),
+            block,
+            status: status_address(),
+            system_program: anchor_lang::system_program::ID,
+        },
+        chain::instruction::Submit {
+            seed,
+            block_id: 1,
+            root: [1; 32],
+            timestamp: 1651640000,
+        },
+    );
+
+    let result = harness.process(&[ix], &[&owner]).await;
+
+    assert_chain_error(result, ChainError::InvalidBlockSeed);
+    assert!(!harness.exists(block).await);
+}
+
+#[tokio::test]
+async fn fails_to_resubmit_a_block() {
+    let mut harness = Harness::initialized().await;
+    harness.submit(1, [1; 32], 1651640000).await.unwrap();
+
+    let result = harness.submit(1, [2; 32], 1651650000).await;
+
+    // system program: account already in use
+    assert_eq!(error_code(result), 0);
+    let block: Block = harness.account(block_address(1).0).await;
+    assert_eq!(block.root, [1; 32]);
+}
+
+#[tokio::test]
+async fn fails_to_submit_older_data() {
+    let mut harness = Harness::initialized().await;
+    harness.submit(1, [1; 32], 1651640000).await.unwrap();
+
+    assert_chain_error(
+        harness.submit(2, [2; 32], 1651640000).await,
+        ChainError::CannotSubmitOlderData,
+    );
+    assert_chain_error(
+        harness.submit(2, [2; 32], 1651630000).await,
+        ChainError::CannotSubmitOlderData,
+    );
+    assert!(!harness.exists(block_address(2).0).await);
+}
+
+#[tokio::test]
+async fn fails_to_submit_within_padding() {
+    let mut harness = Harness::initialized().await;
+    harness.submit(1, [1; 32], 1651640000).await.unwrap();
+
+    assert_chain_error(
+        harness.submit(2, [2; 32], 1651640000 + PADDING).await,
+        ChainError::DoNotSpam,
+    );
+    harness
+        .submit(2, [2; 32], 1651640000 + PADDING + 1)
+        .await
+        .unwrap();
+}
+
+// `ChainError::NotReplicator` is declared but never returned: non-owners are rejected
+// with `OnlyOwnerViolation` by the `has_one` constraint.
+#[tokio::test]
+async fn fails_to_submit_by_unauthorized_user() {
+    let mut harness = Harness::initialized().await;
+    let intruder = harness.funded_keypair().await;
+
+    let result = harness
+        .process(
+            &[submit_ix(&intruder.pubkey(), 1, [1; 32], 1651640000)],
+            &[&intruder],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::OnlyOwnerViolation);
+}
+
+#[tokio::test]
+async fn initializes_first_class_data() {
+    let mut harness = Harness::initialized().await;
+    let owner = harness.owner();
+
+    harness
+        .process(
+            &[initialize_fcd_ix(
+                &owner.pubkey(),
+                "ETH-USD",
+                value(3000),
+                1651640000,
+            )],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+
+    let fcd: FirstClassData = harness.account(fcd_address("ETH-USD").0).await;
+    assert_eq!(fcd.key, "ETH-USD");
+    assert_eq!(fcd.value, value(3000));
+    assert_eq!(fcd.timestamp, 1651640000);
+
+    let result = harness
+        .process(
+            &[initialize_fcd_ix(
+                &owner.pubkey(),
+                "ETH-USD",
+                value(1),
+                1651650000,
+            )],
+            &[&owner],
+        )
+        .await;
+    // system program: account already in use
+    assert_eq!(error_code(result), 0);
+}
+
+#[tokio::test]
+async fn fails_to_initialize_first_class_data_by_unauthorized_user() {
+    let mut harness = Harness::initialized().await;
+    let intruder = harness.funded_keypair().await;
+
+    let result = harness
+        .process(
+            &[initialize_fcd_ix(
+                &intruder.pubkey(),
+                "ETH-USD",
+                value(1),
+                1651640000,
+            )],
+            &[&intruder],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::OnlyOwnerViolation);
+}
+
+#[tokio::test]
+async fn updates_first_class_data() {
+    let (mut harness, fcd) = with_fcd("BTC-USD").await;
+    let owner = harness.owner();
+
+    harness
+        .process(
+            &[update_fcd_ix(
+                &owner.pubkey(),
+                fcd,
+                "BTC-USD",
+                value(40000),
+                1651640000 + PADDING + 1,
+            )],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+
+    let stored: FirstClassData = harness.account(fcd).await;
+    assert_eq!(stored.value, value(40000));
+    assert_eq!(stored.timestamp, 1651640000 + PADDING + 1);
+}
+
+#[tokio::test]
+async fn updates_many_first_class_data_in_one_transaction() {
+    let mut harness = Harness::initialized().await;
+    let owner = harness.owner();
+    let keys = ["BTC-USD", "ETH-USD", "SOL-USD"];
+
+    let init = keys
+        .iter()
+        .map(|key| initialize_fcd_ix(&owner.pubkey(), key, value(1), 1651640000))
+        .collect::<Vec<_>>();
+    harness.process(&init, &[&owner]).await.unwrap();
+
+    let update = keys
+        .iter()
+        .enumerate()
+        .map(|(i, key)| {
+            update_fcd_ix(
+                &owner.pubkey(),
+                fcd_address(key).0,
+                key,
+                value(i as u128 + 2),
+                1651650000,
+            )
+        })
+        .collect::<Vec<_>>();
+    harness.process(&update, &[&owner]).await.unwrap();
+
+    for (i, key) in keys.iter().enumerate() {
+        let fcd: FirstClassData = harness.account(fcd_address(key).0).await;
+        assert_eq!(fcd.value, value(i as u128 + 2));
+        assert_eq!(fcd.timestamp, 1651650000);
+    }
+}
+
+#[tokio::test]
+async fn fails_to_update_first_class_data_with_older_data() {
+    let (mut harness, fcd) = with_fcd("BTC-USD").await;
+    let owner = harness.owner();
+
+    let result = harness
+        .process(
+            &[update_fcd_ix(
+                &owner.pubkey(),
+                fcd,
+                "BTC-USD",
+                value(2),
+                1651640000,
+            )],
+            &[&owner],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::CannotSubmitOlderData);
+}
+
+#[tokio::test]
+async fn fails_to_update_first_class_data_within_padding() {
+    let (mut harness, fcd) = with_fcd("BTC-USD").await;
+    let owner = harness.owner();
+
+    let result = harness
+        .process(
+            &[update_fcd_ix(
+                &owner.pubkey(),
+                fcd,
+                "BTC-USD",
+                value(2),
+                1651640000 + PADDING,
+            )],
+            &[&owner],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::DoNotSpam);
+}
+
+#[tokio::test]
+async fn fails_to_update_first_class_data_with_wrong_key() {
+    let (mut harness, fcd) = with_fcd("BTC-USD").await;
+    let owner = harness.owner();
+
+    let result = harness
+        .process(
+            &[update_fcd_ix(
+                &owner.pubkey(),
+                fcd,
+                "ETH-USD",
+                value(2),
+                1651650000,
+            )],
+            &[&owner],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::WrongFCDKeyForAccount);
+}
+
+#[tokio::test]
+async fn fails_to_update_first_class_data_by_unauthorized_user() {
+    let (mut harness, fcd) = with_fcd("BTC-USD").await;
+    let intruder = Keypair::new();
+
+    let result = harness
+        .process(
+            &[update_fcd_ix(
+                &intruder.pubkey(),
+                fcd,
+                "BTC-USD",
+                value(2),
+                1651650000,
+            )],
+            &[&intruder],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::OnlyOwnerViolation);
+}
+
+#[tokio::test]
+async fn closes_first_class_data() {
+    let (mut harness, fcd) = with_fcd("BTC-USD").await;
+    let owner = harness.owner();
+    let recipient = Keypair::new();
+    harness.set_time(1651650000).await;
+    let rent = harness.context.banks_client.get_balance(fcd).await.unwrap();
+
+    harness
+        .process(
+            &[close_fcd_ix(
+                &owner.pubkey(),
+                fcd,
+                "BTC-USD",
+                &recipient.pubkey(),
+            )],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+
+    assert!(!harness.exists(fcd).await);
+    let received = harness
+        .context
+        .banks_client
+        .get_balance(recipient.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(received, rent);
+    let closed: ClosedFirstClassData = harness.account(closed_fcd_address(&fcd)).await;
+    assert_eq!(closed.closed_at, 1651650000);
+}
+
+#[tokio::test]
+async fn fails_to_close_first_class_data_with_wrong_key() {
+    let (mut harness, fcd) = with_fcd("BTC-USD").await;
+    let owner = harness.owner();
+
+    let result = harness
+        .process(
+            &[close_fcd_ix(
+                &owner.pubkey(),
+                fcd,
+                "ETH-USD",
+                &owner.pubkey(),
+            )],
+            &[&owner],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::WrongFCDKeyForAccount);
+}
+
+#[tokio::test]
+async fn fails_to_close_first_class_data_by_unauthorized_user() {
+    let (mut harness, fcd) = with_fcd("BTC-USD").await;
+    let intruder = harness.funded_keypair().await;
+
+    let result = harness
+        .process(
+            &[close_fcd_ix(
+                &intruder.pubkey(),
+                fcd,
+                "BTC-USD",
+                &intruder.pubkey(),
+            )],
+            &[&intruder],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::OnlyOwnerViolation);
+}
+
+#[tokio::test]
+async fn reinitializes_closed_first_class_data_only_after_the_cooldown() {
+    let (mut harness, fcd) = with_fcd("BTC-USD").await;
+    let owner = harness.owner();
+    harness.set_time(1651650000).await;
+    harness
+        .process(
+            &[close_fcd_ix(
+                &owner.pubkey(),
+                fcd,
+                "BTC-USD",
+                &owner.pubkey(),
+            )],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+
+    harness.set_time(1651650000 + FCD_REUSE_COOLDOWN - 1).await;
+    let result = harness
+        .process(
+            &[initialize_fcd_ix(
+                &owner.pubkey(),
+                "BTC-USD",
+                value(2),
+                1651660000,
+            )],
+            &[&owner],
+        )
+        .await;
+    assert_chain_error(result, ChainError::FCDKeyCoolingDown);
+
+    harness.set_time(1651650000 + FCD_REUSE_COOLDOWN).await;
+    harness
+        .process(
+            &[initialize_fcd_ix(
+                &owner.pubkey(),
+                "BTC-USD",
+                value(2),
+                1651660001,
+            )],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+
+    let data: FirstClassData = harness.account(fcd).await;
+    assert_eq!(data.value, value(2));
+    assert!(!harness.exists(closed_fcd_address(&fcd)).await);
+}
+
+async fn with_fcd(key: &str) -> (Harness, Pubkey) {
+    let mut harness = Harness::initialized().await;
+    let owner = harness.owner();
+    harness
+        .process(
+            &[initialize_fcd_ix(
+                &owner.pubkey(),
+                key,
+                value(1),
+                1651640000,
+            )],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+    (harness, fcd_address(key).0)
+}
+
+fn transfer_ownership_ix(
+    owner: &Pubkey,
+    new_owner: &Pubkey,
+) -> solana_sdk::instruction::Instruction {
+    instruction(
+        chain::accounts::TransferOwnership {
+            owner: *owner,
+            authority: authority_address(),
+            new_owner: *new_owner,
+            system_program: anchor_lang::system_program::ID,
+        },
+        chain::instruction::TransferOwnership {},
+    )
+}
+
+fn set_padding_ix(owner: &Pubkey, padding: u32) -> solana_sdk::instruction::Instruction {
+    instruction(
+        chain::accounts::SetPadding {
+            owner: *owner,
+            authority: authority_address(),
+            status: status_address(),
+            system_program: anchor_lang::system_program::ID,
+        },
+        chain::instruction::SetPadding { padding },
+    )
+}
@@ -0,0 +1,1121 @@
+#![allow(dead_code)]
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction, InstructionError};
+use anchor_lang::solana_program::program_option::COption;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use chain::errors::ChainError;
+use chain::state::{
+    attestation_message, evm_signed_message, Authority, EvmBlockHeader, FeeSchedule, FeedMetadata,
+    FeedRegistry, Status, ACCOUNT_VERSION,
+};
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::account::Account;
+use solana_sdk::ed25519_instruction::new_ed25519_instruction;
+use solana_sdk::secp256k1_instruction::construct_eth_pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::{Transaction, TransactionError};
+use solana_sdk::transport::TransportError;
+use spl_token::state::{Account as TokenAccount, AccountState, Mint};
+
+pub const PADDING: u32 = 10;
+
+pub fn authority_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"authority"], &chain::ID).0
+}
+
+pub fn status_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"status"], &chain::ID).0
+}
+
+/// Block seed used by the replicator: `block_id * 10^18` as a big endian uint256.
+pub fn block_seed(block_id: u32) -> Vec<u8> {
+    let mut seed = [0u8; 32];
+    seed[16..].copy_from_slice(&(u128::from(block_id) * 10u128.pow(18)).to_be_bytes());
+    seed.to_vec()
+}
+
+pub fn block_address(block_id: u32) -> (Pubkey, Vec<u8>) {
+    let seed = block_seed(block_id);
+    (Pubkey::find_program_address(&[&seed], &chain::ID).0, seed)
+}
+
+/// FCD seed: the key left padded with zeros to 32 bytes.
+pub fn fcd_address(key: &str) -> (Pubkey, Vec<u8>) {
+    let mut seed = [0u8; 32];
+    seed[32 - key.len()..].copy_from_slice(key.as_bytes());
+    (
+        Pubkey::find_program_address(&[&seed], &chain::ID).0,
+        seed.to_vec(),
+    )
+}
+
+pub fn closed_fcd_address(fcd: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"closed", fcd.as_ref()], &chain::ID).0
+}
+
+pub fn fcd_history_address(fcd: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"history", fcd.as_ref()], &chain::ID).0
+}
+
+pub fn validator_set_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"validators"], &chain::ID).0
+}
+
+pub fn fcd_round_address(fcd: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"round", fcd.as_ref()], &chain::ID).0
+}
+
+pub fn fee_config_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"fee_config"], &chain::ID).0
+}
+
+pub fn subscription_address(consumer: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"subscription", consumer.as_ref()], &chain::ID).0
+}
+
+pub fn reward_vault_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"rewards"], &chain::ID).0
+}
+
+pub fn stake_config_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"stake_config"], &chain::ID).0
+}
+
+pub fn validator_stake_address(validator: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"stake", validator.as_ref()], &chain::ID).0
+}
+
+pub fn slashed_block_address(validator: &Pubkey, block_id: u32) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"slashed", validator.as_ref(), &block_id.to_le_bytes()],
+        &chain::ID,
+    )
+    .0
+}
+
+pub fn evm_validator_set_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"evm_validators"], &chain::ID).0
+}
+
+pub fn feed_registry_address(page: u32) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"feed_registry".as_ref(), &page.to_le_bytes()],
+        &chain::ID,
+    )
+    .0
+}
+
+pub fn value(n: u128) -> [u8; 32] {
+    let mut value = [0u8; 32];
+    value[16..].copy_from_slice(&n.to_be_bytes());
+    value
+}
+
+/// Runs `chain.so` from `BPF_OUT_DIR` under `cargo test-bpf`. The native processor
+/// cannot create accounts through a CPI in this version, hence the feature gate.
+pub fn program_test() -> ProgramTest {
+    ProgramTest::new("chain", chain::ID, processor!(chain::entry))
+}
+
+/// Adds an anchor account owned by the chain program, the way `initialize` would have created it.
+pub fn add_program_account<T: AccountSerialize>(
+    program_test: &mut ProgramTest,
+    address: Pubkey,
+    account: &T,
+    space: usize,
+) {
+    let mut data = Vec::with_capacity(space);
+    account.try_serialize(&mut data).unwrap();
+    data.resize(space, 0);
+    program_test.add_account(
+        address,
+        Account {
+            lamports: Rent::default().minimum_balance(space),
+            data,
+            owner: chain::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+}
+
+/// Runs the SPL token program natively, as the bundled BPF build can't be invoked
+/// from the natively run chain program.
+pub fn add_token_program(program_test: &mut ProgramTest) {
+    program_test.add_program(
+        "spl_token",
+        spl_token::ID,
+        processor!(spl_token::processor::Processor::process),
+    );
+}
+
+/// Adds an initialized token account of `mint` holding `amount`.
+pub fn add_token_account(
+    program_test: &mut ProgramTest,
+    mint: Pubkey,
+    owner: Pubkey,
+    amount: u64,
+) -> Pubkey {
+    let address = Pubkey::new_unique();
+    let mut data = vec![0u8; TokenAccount::LEN];
+    TokenAccount {
+        mint,
+        owner,
+        amount,
+        state: AccountState::Initialized,
+        ..TokenAccount::default()
+    }
+    .pack_into_slice(&mut data);
+    program_test.add_account(
+        address,
+        Account {
+            lamports: Rent::default().minimum_balance(TokenAccount::LEN),
+            data,
+            owner: spl_token::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    address
+}
+
+/// Adds an initialized mint with a supply of 1,000,000 and 6 decimals.
+pub fn add_mint(program_test: &mut ProgramTest) -> Pubkey {
+    let address = Pubkey::new_unique();
+    let mut data = vec![0u8; Mint::LEN];
+    Mint {
+        mint_authority: COption::None,
+        supply: 1_000_000,
+        decimals: 6,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    }
+    .pack_into_slice(&mut data);
+    program_test.add_account(
+        address,
+        Account {
+            lamports: Rent::default().minimum_balance(Mint::LEN),
+            data,
+            owner: spl_token::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    address
+}
+
+pub fn clone_keypair(keypair: &Keypair) -> Keypair {
+    Keypair::from_bytes(&keypair.to_bytes()).unwrap()
+}
+
+pub struct Harness {
+    pub context: ProgramTestContext,
+    pub owner: Keypair,
+}
+
+impl Harness {
+    /// Starts a bank where the program is already initialized with `owner` as authority
+    /// and the first `FeedRegistry` page exists.
+    ///
+    /// `initialize` only accepts the hardcoded initializer key, which cannot sign here,
+    /// so `Authority` and `Status` are written directly instead.
+    pub async fn initialized() -> Self {
+        Self::initialized_with(|_| {}).await
+    }
+
+    /// Like `initialized`, with more accounts added by `setup`.
+    pub async fn initialized_with(setup: impl FnOnce(&mut ProgramTest)) -> Self {
+        let owner = Keypair::new();
+        let mut program_test = program_test();
+        add_program_account(
+            &mut program_test,
+            authority_address(),
+            &Authority {
+                owner: owner.pubkey(),
+                version: ACCOUNT_VERSION,
+                ..Authority::default()
+            },
+            Authority::SPACE,
+        );
+        add_program_account(
+            &mut program_test,
+            status_address(),
+            &Status {
+                padding: PADDING,
+                version: ACCOUNT_VERSION,
+                ..Status::default()
+            },
+            Status::SPACE,
+        );
+        add_program_account(
+            &mut program_test,
+            feed_registry_address(0),
+            &FeedRegistry::default(),
+            FeedRegistry::SPACE,
+        );
+        setup(&mut program_test);
+        Self::start(program_test, owner).await
+    }
+
+    pub async fn start(mut program_test: ProgramTest, owner: Keypair) -> Self {
+        program_test.add_account(
+            owner.pubkey(),
+            Account {
+                lamports: 10_000_000_000,
+                ..Account::default()
+            },
+        );
+        Harness {
+            context: program_test.start_with_context().await,
+            owner,
+        }
+    }
+
+    /// Copy of the owner keypair, to sign while the harness is borrowed mutably.
+    pub fn owner(&self) -> Keypair {
+        clone_keypair(&self.owner)
+    }
+
+    pub async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> std::result::Result<(), TransportError> {
+        let mut signers = signers.to_vec();
+        signers.insert(0, &self.context.payer);
+        let blockhash = self
+            .context
+            .banks_client
+            .get_latest_blockhash()
+            .await
+            .unwrap();
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &signers,
+            blockhash,
+        );
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+    }
+
+    /// New keypair holding enough lamports to pay for accounts it creates.
+    pub async fn funded_keypair(&mut self) -> Keypair {
+        let keypair = Keypair::new();
+        let payer = self.context.payer.pubkey();
+        self.process(
+            &[system_instruction::transfer(
+                &payer,
+                &keypair.pubkey(),
+                1_000_000_000,
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+        keypair
+    }
+
+    pub async fn account<T: AccountDeserialize>(&mut self, address: Pubkey) -> T {
+        let account = self
+            .context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .expect("account not found");
+        T::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    /// Balance of the token account at `address`.
+    pub async fn tokens(&mut self, address: Pubkey) -> u64 {
+        let account = self
+            .context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .unwrap();
+        TokenAccount::unpack(&account.data).unwrap().amount
+    }
+
+    pub async fn exists(&mut self, address: Pubkey) -> bool {
+        self.context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .is_some()
+    }
+
+    /// Moves the cluster clock to `unix_timestamp`, keeping the current slot.
+    pub async fn set_time(&mut self, unix_timestamp: i64) {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp = unix_timestamp;
+        self.context.set_sysvar(&clock);
+    }
+
+    pub async fn submit(
+        &mut self,
+        block_id: u32,
+        root: [u8; 32],
+        timestamp: u32,
+    ) -> std::result::Result<(), TransportError> {
+        let owner = self.owner();
+        let ix = submit_ix(&owner.pubkey(), block_id, root, timestamp);
+        self.process(&[ix], &[&owner]).await
+    }
+}
+
+pub fn submit_ix(owner: &Pubkey, block_id: u32, root: [u8; 32], timestamp: u32) -> Instruction {
+    let (block, seed) = block_address(block_id);
+    instruction(
+        chain::accounts::Submit {
+            owner: *owner,
+            authority: authority_address(),
+            block,
+            status: status_address(),
+            system_program: anchor_lang::system_program::ID,
+        },
+        chain::instruction::Submit {
+            seed,
+            block_id,
+            root,
+            timestamp,
+        },
+    )
+}
+
+pub fn submit_with_hash_scheme_ix(
+    owner: &Pubkey,
+    block_id: u32,
+    root: [u8; 32],
+    timestamp: u32,
+    hash_scheme: u8,
+) -> Instruction {
+    let (block, seed) = block_address(block_id);
+    instruction(
+        chain::accounts::Submit {
+            owner: *owner,
+            authority: authority_address(),
+            block,
+            status: status_address(),
+            system_program: anchor_lang::system_program::ID,
+        },
+        chain::instruction::SubmitWithHashScheme {
+            seed,
+            block_id,
+            root,
+            timestamp,
+            hash_scheme,
+        },
+    )
+}
+
+pub fn set_max_proof_length_ix(owner: &Pubkey, max_proof_length: u8) -> Instruction {
+    instruction(
+        chain::accounts::SetMaxProofLength {
+            owner: *owner,
+            authority: authority_address(),
+            status: status_address(),
+            system_program: anchor_lang::system_program::ID,
+        },
+        chain::instruction::SetMaxProofLength { max_proof_length },
+    )
+}
+
+pub fn set_guardian_ix(owner: &Pubkey, guardian: Pubkey) -> Instruction {
+    instruction(
+        chain::accounts::SetGuardian {
+            owner: *owner,
+            authority: authority_address(),
+            system_program: anchor_lang::system_program::ID,
+        },
+        chain::instruction::SetGuardian { guardian },
+    )
+}
+
+pub fn pause_ix(pauser: &Pubkey, flags: u8) -> Instruction {
+    instruction(
+        chain::accounts::Pause {
+            pauser: *pauser,
+            authority: authority_address(),
+            status: status_address(),
+        },
+        chain::instruction::Pause { flags },
+    )
+}
+
+pub fn unpause_ix(pauser: &Pubkey, flags: u8) -> Instruction {
+    instruction(
+        chain::accounts::Pause {
+            pauser: *pauser,
+            authority: authority_address(),
+            status: status_address(),
+        },
+        chain::instruction::Unpause { flags },
+    )
+}
+
+pub fn migrate_ix(owner: &Pubkey) -> Instruction {
+    instruction(
+        chain::accounts::Migrate {
+            owner: *owner,
+            authority: authority_address(),
+            status: status_address(),
+            system_program: anchor_lang::system_program::ID,
+        },
+        chain::instruction::Migrate {},
+    )
+}
+
+pub fn initialize_verify_result_ix(user: &Pubkey, verify_result: &Pubkey) -> Instruction {
+    instruction(
+        chain::accounts::InitializeVerifyResult {
+            verify_result: *verify_result,
+            user: *user,
+            system_program: anchor_lang::system_program::ID,
+        },
+        chain::instruction::InitializeVerifyResult {},
+    )
+}
+
+pub fn verify_ix(
+    verify_result: &Pubkey,
+    block_id: u32,
+    proof: Vec<[u8; 32]>,
+    key: [u8; 32],
+    value: [u8; 32],
+) -> Instruction {
+    let (block, seed) = block_address(block_id);
+    instruction(
+        chain::accounts::Verify {
+            block,
+            verify_result: *verify_result,
+            status: status_address(),
+        },
+        chain::instruction::VerifyProofForBlock {
+            seed,
+            proof,
+            key,
+            value,
+        },
+    )
+}
+
+pub fn verify_message_ix(
+    verify_result: &Pubkey,
+    block_id: u32,
+    message: Vec<u8>,
+    proof: Vec<[u8; 32]>,
+) -> Instruction {
+    let (block, seed) = block_address(block_id);
+    instruction(
+        chain::accounts::Verify {
+            block,
+            verify_result: *verify_result,
+            status: status_address(),
+        },
+        chain::instruction::VerifyMessageInclusion {
+            seed,
+            message,
+            proof,
+        },
+    )
+}
+
+pub fn verify_against_latest_ix(
+    verify_result: &Pubkey,
+    block_id: u32,
+    proof: Vec<[u8; 32]>,
+    key: [u8; 32],
+    value: [u8; 32],
+    max_age: u32,
+) -> Instruction {
+    instruction(
+        chain::accounts::VerifyAgainstLatest {
+            status: status_address(),
+            block: block_address(block_id).0,
+            verify_result: *verify_result,
+        },
+        chain::instruction::VerifyAgainstLatest {
+            proof,
+            key,
+            value,
+            max_age,
+        },
+    )
+}
+
+pub fn initialize_fcd_ix(
+    owner: &Pubkey,
+    key: &str,
+    value: [u8; 32],
+    timestamp: u32,
+) -> Instruction {
+    let (fcd, seed) = fcd_address(key);
+    instruction(
+        chain::accounts::InitializeFirstClassData {
+            owner: *owner,
+            authority: authority_address(),
+            fcd,
+            closed: closed_fcd_address(&fcd),
+            registry: feed_registry_address(0),
+            system_program: anchor_lang::system_program::ID,
+        },
+        chain::instruction::InitializeFirstClassData {
+            seed,
+            key: key.to_string(),
+            value,
+            timestamp,
+        },
+    )
+}
+
+pub fn initialize_fcd_with_metadata_ix(
+    owner: &Pubkey,
+    key: &str,
+    value: [u8; 32],
+    timestamp: u32,
+    metadata: &FeedMetadata,
+) -> Instruction {
+    let (fcd, seed) = fcd_address(key);
+    instruction(
+        chain::accounts::InitializeFirstClassData {
+            owner: *owner,
+            authority: authority_address(),
+            fcd,
+            closed: closed_fcd_address(&fcd),
+            registry: feed_registry_address(0),
+            system_program: anchor_lang::system_program::ID,
+        },
+        chain::instruction::InitializeFirstClassDataWithMetadata {
+            seed,
+            key: key.to_string(),
+            value,
+            timestamp,
+            metadata: metadata.clone(),
+        },
+    )
+}
+
+pub fn update_fcd_ix(
+    owner: &Pubkey,
+    fcd: Pubkey,
+    key: &str,
+    value: [u8; 32],
+    timestamp: u32,
+) -> Instruction {
+    instruction(
+        chain::accounts::UpdateFirstClassData {
+            owner: *owner,
+            authority: authority_address(),
+            fcd,
+            status: status_address(),
+            system_program: anchor_lang::system_program::ID,
+        },
+        chain::instruction::UpdateFirstClassData {
+            key: key.to_string(),
+            value,
+            timestamp,
+        },
+    )
+}
+
+pub fn update_fcd_with_confidence_ix(
+    owner: &Pubkey,
+    fcd: Pubkey,
+    key: &str,
+    value: [u8; 32],
+    timestamp: u32,
+    confidence: [u8; 32],
+) -> Instruction {
+    instruction(
+        chain::accounts::UpdateFirstClassData {
+            owner: *owner,
+            authority: authority_address(),
+            fcd,
+            status: status_address(),
+            system_program: anchor_lang::system_program::ID,
+        },
+        chain::instruction::UpdateFirstClassDataWithConfidence {
+            key: key.to_string(),
+            value,
+            timestamp,
+            confidence,
+        },
+    )
+}
+
+pub fn set_fcd_metadata_ix(
+    owner: &Pubkey,
+    fcd: Pubkey,
+    key: &str,
+    metadata: &FeedMetadata,
+) -> Instruction {
+    instruction(
+        chain::accounts::SetFirstClassDataMetadata {
+            owner: *owner,
+            authority: authority_address(),
+            fcd,
+            registry: feed_registry_address(0),
+        },
+        chain::instruction::SetFirstClassDataMetadata {
+            key: key.to_string(),
+            metadata: metadata.clone(),
+        },
+    )
+}
+
+pub fn close_fcd_ix(owner: &Pubkey, fcd: Pubkey, key: &str, recipient: &Pubkey) -> Instruction {
+    instruction(
+        chain::accounts::CloseFirstClassData {
+            owner: *owner,
+            authority: authority_address(),
+            fcd,
+            closed: closed_fcd_address(&fcd),
+            registry: feed_registry_address(0),
+            round: fcd_round_address(&fcd),
+            recipient: *recipient,
+            system_program: anchor_lang::system_program::ID,
+        },
+        chain::instruction::CloseFirstClassData {
+            key: key.to_string(),
+        },
+    )
+}
+
+pub fn initialize_feed_registry_page_ix(owner: &Pubkey, page: u32) -> Instruction {
+    instruction(
+        chain::accounts::InitializeFeedRegistryPage {
+            owner: *owner,
+            authority: authority_address(),
+            registry: feed_registry_address(page),
+            system_program: anchor_lang::system_program::ID,
+        },
+        chain::instruction::InitializeFeedRegistryPage { page },
+    )
+}
+
+pub fn register_fcd_ix(owner: &Pubkey, fcd: Pubkey, key: &str, page: u32) -> Instruction {
+    instruction(
+        chain::accounts::RegisterFirstClassData {
+            owner: *owner,
+            authority: authority_address(),
+            fcd,
+            registry: feed_registry_address(page),
+        },
+        chain::instruction::RegisterFirstClassData {
+            key: key.to_string(),
+        },
+    )
+}
+
+pub fn initialize_fcd_history_ix(
+    owner: &Pubkey,
+    fcd: Pubkey,
+    key: &str,
+    capacity: u16,
+) -> Instruction {
+    instruction(
+        chain::accounts::InitializeFcdHistory {
+            owner: *owner,
+            authority: authority_address(),
+            fcd,
+            history: fcd_history_address(&fcd),
+            system_program: anchor_lang::system_program::ID,
+        },
+        chain::instruction::InitializeFcdHistory {
+            key: key.to_string(),
+            capacity,
+        },
+    )
+}
+
+pub fn get_fcd_observation_ix(fcd: &Pubkey, timestamp: u32) -> Instruction {
+    instruction(
+        chain::accounts::GetFcdObservation {
+            history: fcd_history_address(fcd),
+            status: status_address(),
+        },
+        chain::instruction::GetFcdObservation { timestamp },
+    )
+}
+
+pub fn set_validators_ix(
+    owner: &Pubkey,
+    validators: Vec<Pubkey>,
+    quorum: u8,
+    window: u32,
+) -> Instruction {
+    instruction(
+        chain::accounts::SetValidators {
+            owner: *owner,
+            authority: authority_address(),
+            validator_set: validator_set_address(),
+            system_program: anchor_lang::system_program::ID,
+        },
+        chain::instruction::SetValidators {
+            validators,
+            quorum,
+            window,
+        },
+    )
+}
+
+pub fn initialize_fcd_aggregation_ix(owner: &Pubkey, fcd: Pubkey, key: &str) -> Instruction {
+    instruction(
+        chain::accounts::InitializeFcdAggregation {
+            owner: *owner,
+            authority: authority_address(),
+            fcd,
+            round: fcd_round_address(&fcd),
+            system_program: anchor_lang::system_program::ID,
+        },
+        chain::instruction::InitializeFcdAggregation {
+            key: key.to_string(),
+        },
+    )
+}
+
+pub fn submit_fcd_observation_ix(
+    validator: &Pubkey,
+    fcd: Pubkey,
+    key: &str,
+    value: [u8; 32],
+    timestamp: u32,
+) -> Instruction {
+    instruction(
+        chain::accounts::SubmitFcdObservation {
+            validator: *validator,
+            validator_set: validator_set_address(),
+            status: status_address(),
+            fcd,
+            round: fcd_round_address(&fcd),
+        },
+        chain::instruction::SubmitFcdObservation {
+            key: key.to_string(),
+            value,
+            timestamp,
+        },
+    )
+}
+
+pub fn set_fee_schedule_ix(
+    owner: &Pubkey,
+    schedule: FeeSchedule,
+    exempt: Vec<Pubkey>,
+    enabled: bool,
+) -> Instruction {
+    instruction(
+        chain::accounts::SetFeeSchedule {
+            owner: *owner,
+            authority: authority_address(),
+            fee_config: fee_config_address(),
+            status: status_address(),
+            system_program: anchor_lang::system_program::ID,
+        },
+        chain::instruction::SetFeeSchedule {
+            schedule,
+            exempt,
+            enabled,
+        },
+    )
+}
+
+pub fn subscribe_ix(payer: &Pubkey, consumer: &Pubkey, periods: u32) -> Instruction {
+    instruction(
+        chain::accounts::Subscribe {
+            payer: *payer,
+            consumer: *consumer,
+            fee_config: fee_config_address(),
+            subscription: subscription_address(consumer),
+            system_program: anchor_lang::system_program::ID,
+        },
+        chain::instruction::Subscribe { periods },
+    )
+}
+
+pub fn withdraw_fees_ix(owner: &Pubkey, recipient: &Pubkey, amount: u64) -> Instruction {
+    instruction(
+        chain::accounts::WithdrawFees {
+            owner: *owner,
+            authority: authority_address(),
+            fee_config: fee_config_address(),
+            recipient: *recipient,
+        },
+        chain::instruction::WithdrawFees { amount },
+    )
+}
+
+/// Appends the fee accounts of a call paid by `payer`: the fee config, the
+/// payer and then `payment`.
+pub fn with_fees(
+    mut instruction: Instruction,
+    payer: &Pubkey,
+    payment: &[AccountMeta],
+) -> Instruction {
+    instruction
+        .accounts
+        .push(AccountMeta::new(fee_config_address(), false));
+    instruction.accounts.push(AccountMeta::new(*payer, true));
+    instruction.accounts.extend_from_slice(payment);
+    instruction
+}
+
+pub fn set_rewards_enabled_ix(owner: &Pubkey, enabled: bool) -> Instruction {
+    instruction(
+        chain::accounts::SetRewardsEnabled {
+            owner: *owner,
+            authority: authority_address(),
+            reward_vault: reward_vault_address(),
+            status: status_address(),
+            system_program: anchor_lang::system_program::ID,
+        },
+        chain::instruction::SetRewardsEnabled { enabled },
+    )
+}
+
+pub fn distribute_rewards_ix(owner: &Pubkey, amount: u64) -> Instruction {
+    instruction(
+        chain::accounts::DistributeRewards {
+            owner: *owner,
+            authority: authority_address(),
+            fee_config: fee_config_address(),
+            reward_vault: reward_vault_address(),
+        },
+        chain::instruction::DistributeRewards { amount },
+    )
+}
+
+pub fn claim_rewards_ix(claimant: &Pubkey) -> Instruction {
+    instruction(
+        chain::accounts::ClaimRewards {
+            claimant: *claimant,
+            reward_vault: reward_vault_address(),
+        },
+        chain::instruction::ClaimRewards {},
+    )
+}
+
+pub fn set_stake_config_ix(
+    owner: &Pubkey,
+    vault: Pubkey,
+    treasury: Pubkey,
+    slash_bps: u16,
+    unbonding_period: u32,
+) -> Instruction {
+    instruction(
+        chain::accounts::SetStakeConfig {
+            owner: *owner,
+            authority: authority_address(),
+            stake_config: stake_config_address(),
+            vault,
+            treasury,
+            system_program: anchor_lang::system_program::ID,
+        },
+        chain::instruction::SetStakeConfig {
+            slash_bps,
+            unbonding_period,
+        },
+    )
+}
+
+pub fn stake_ix(validator: &Pubkey, source: Pubkey, vault: Pubkey, amount: u64) -> Instruction {
+    instruction(
+        chain::accounts::Stake {
+            validator: *validator,
+            validator_set: validator_set_address(),
+            stake_config: stake_config_address(),
+            validator_stake: validator_stake_address(validator),
+            source,
+            vault,
+            token_program: spl_token::ID,
+            system_program: anchor_lang::system_program::ID,
+        },
+        chain::instruction::Stake { amount },
+    )
+}
+
+pub fn request_unstake_ix(validator: &Pubkey, amount: u64) -> Instruction {
+    instruction(
+        chain::accounts::RequestUnstake {
+            validator: *validator,
+            stake_config: stake_config_address(),
+            validator_stake: validator_stake_address(validator),
+        },
+        chain::instruction::RequestUnstake { amount },
+    )
+}
+
+pub fn withdraw_stake_ix(validator: &Pubkey, vault: Pubkey, destination: Pubkey) -> Instruction {
+    instruction(
+        chain::accounts::WithdrawStake {
+            validator: *validator,
+            stake_config: stake_config_address(),
+            validator_stake: validator_stake_address(validator),
+            vault,
+            destination,
+            token_program: spl_token::ID,
+        },
+        chain::instruction::WithdrawStake {},
+    )
+}
+
+/// Reports `validator` for attesting both roots of `block_id`, to be preceded by
+/// the `attestation_ix` of each.
+pub fn report_equivocation_ix(
+    reporter: &Pubkey,
+    validator: Pubkey,
+    vault: Pubkey,
+    treasury: Pubkey,
+    block_id: u32,
+    roots: ([u8; 32], [u8; 32]),
+) -> Instruction {
+    instruction(
+        chain::accounts::ReportEquivocation {
+            reporter: *reporter,
+            stake_config: stake_config_address(),
+            validator_stake: validator_stake_address(&validator),
+            slashed_block: slashed_block_address(&validator, block_id),
+            block: block_address(block_id).0,
+            vault,
+            treasury,
+            token_program: spl_token::ID,
+            instructions: anchor_lang::solana_program::sysvar::instructions::ID,
+            system_program: anchor_lang::system_program::ID,
+        },
+        chain::instruction::ReportEquivocation {
+            validator,
+            block_id,
+            first_root: roots.0,
+            second_root: roots.1,
+        },
+    )
+}
+
+/// Ed25519 program instruction checking the attestation of `root` as the root of
+/// `block_id` by `validator`.
+pub fn attestation_ix(validator: &Keypair, block_id: u32, root: [u8; 32]) -> Instruction {
+    let keypair = ed25519_dalek::Keypair::from_bytes(&validator.to_bytes()).unwrap();
+    new_ed25519_instruction(&keypair, &attestation_message(block_id, &root))
+}
+
+pub fn set_evm_validators_ix(owner: &Pubkey, validators: Vec<[u8; 20]>, quorum: u8) -> Instruction {
+    instruction(
+        chain::accounts::SetEvmValidators {
+            owner: *owner,
+            authority: authority_address(),
+            evm_validator_set: evm_validator_set_address(),
+            system_program: anchor_lang::system_program::ID,
+        },
+        chain::instruction::SetEvmValidators { validators, quorum },
+    )
+}
+
+/// Imports the block of `header`, to be preceded by `evm_signatures_ix`.
+pub fn import_evm_block_ix(importer: &Pubkey, header: &EvmBlockHeader) -> Instruction {
+    import_evm_payload_ix(importer, header.block_id, header.encode().to_vec())
+}
+
+pub fn import_evm_payload_ix(importer: &Pubkey, block_id: u32, payload: Vec<u8>) -> Instruction {
+    let (block, seed) = block_address(block_id);
+    instruction(
+        chain::accounts::ImportEvmBlock {
+            importer: *importer,
+            evm_validator_set: evm_validator_set_address(),
+            block,
+            status: status_address(),
+            instructions: anchor_lang::solana_program::sysvar::instructions::ID,
+            system_program: anchor_lang::system_program::ID,
+        },
+        chain::instruction::ImportEvmBlock { seed, payload },
+    )
+}
+
+pub fn eth_address(key: &libsecp256k1::SecretKey) -> [u8; 20] {
+    construct_eth_pubkey(&libsecp256k1::PublicKey::from_secret_key(key))
+}
+
+/// Secp256k1 program instruction at `index` of its transaction checking a signature
+/// of `payload` by each of `keys`, the way the sidechain validators sign headers.
+pub fn evm_signatures_ix(
+    index: u8,
+    payload: &[u8],
+    keys: &[libsecp256k1::SecretKey],
+) -> Instruction {
+    let message = evm_signed_message(payload);
+    let hash = solana_sdk::keccak::hash(&message).to_bytes();
+    let data_start = 1 + 11 * keys.len();
+    let message_offset = data_start + 85 * keys.len();
+
+    let mut offsets = vec![keys.len() as u8];
+    let mut signatures = vec![];
+    for (i, key) in keys.iter().enumerate() {
+        let address_offset = data_start + 85 * i;
+        offsets.extend_from_slice(&((address_offset + 20) as u16).to_le_bytes());
+        offsets.push(index);
+        offsets.extend_from_slice(&(address_offset as u16).to_le_bytes());
+        offsets.push(index);
+        offsets.extend_from_slice(&(message_offset as u16).to_le_bytes());
+        offsets.extend_from_slice(&(message.len() as u16).to_le_bytes());
+        offsets.push(index);
+
+        let (signature, recovery_id) =
+            libsecp256k1::sign(&libsecp256k1::Message::parse(&hash), key);
+        signatures.extend_from_slice(&eth_address(key));
+        signatures.extend_from_slice(&signature.serialize());
+        signatures.push(recovery_id.serialize());
+    }
+
+    let mut data = offsets;
+    data.extend(signatures);
+    data.extend(message);
+    Instruction {
+        program_id: solana_sdk::secp256k1_program::ID,
+        accounts: vec![],
+        data,
+    }
+}
+
+/// Passes the `RewardVault` as the last remaining account of a submission.
+pub fn with_reward_vault(mut instruction: Instruction) -> Instruction {
+    instruction
+        .accounts
+        .push(AccountMeta::new(reward_vault_address(), false));
+    instruction
+}
+
+/// Passes `history` as the remaining account of an FCD update or close.
+pub fn with_history(mut instruction: Instruction, history: Pubkey) -> Instruction {
+    instruction.accounts.push(AccountMeta::new(history, false));
+    instruction
+}
+
+pub fn instruction(accounts: impl ToAccountMetas, args: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: chain::ID,
+        accounts: accounts.to_account_metas(None),
+        data: args.data(),
+    }
+}
+
+/// Custom error code returned by the first instruction of a failed transaction.
+pub fn error_code(result: std::result::Result<(), TransportError>) -> u32 {
+    match result {
+        Err(TransportError::TransactionError(TransactionError::InstructionError(
+            _,
+            InstructionError::Custom(code),
+        ))) => code,
+        other => panic!("expected a custom program error, got {:?}", other),
+    }
+}
+
+pub fn assert_chain_error(result: std::result::Result<(), TransportError>, error: ChainError) {
+    assert_eq!(error_code(result), u32::from(error));
+}
@@ -0,0 +1,80 @@
+#![cfg(any(feature = "test-bpf", feature = "test-sbf"))]
+
+//! Compute units used by the `verify_proof_for_block` instruction alone, per proof length.
+//!
+//! The result account is initialized beforehand in its own transaction, and the units a
+//! transaction holding only the compute budget request needs are subtracted, so the
+//! figures leave out setup and transaction overhead.
+//!
+//! Run with `cargo test-bpf --test compute_units -- --nocapture` to print the table.
+
+mod common;
+
+use chain::merkle::DEFAULT_MAX_PROOF_LENGTH;
+use common::*;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::signature::{Keypair, Signer};
+
+const MAX_UNITS: u32 = 1_400_000;
+
+/// Smallest compute budget under which a transaction of the budget request followed by
+/// `instructions(units)` succeeds. The instructions may depend on the budget tried, to
+/// keep the transactions of the search distinct.
+async fn units_for(harness: &mut Harness, instructions: impl Fn(u32) -> Vec<Instruction>) -> u32 {
+    let (mut low, mut high) = (0, MAX_UNITS);
+    while low < high {
+        let units = (low + high) / 2;
+        let mut transaction = vec![ComputeBudgetInstruction::request_units(units, 0)];
+        transaction.extend(instructions(units));
+        if harness.process(&transaction, &[]).await.is_ok() {
+            high = units;
+        } else {
+            low = units + 1;
+        }
+    }
+    low
+}
+
+#[tokio::test]
+async fn verification_fits_the_default_compute_budget() {
+    let mut harness = Harness::initialized().await;
+    harness.submit(1, [0; 32], 1651641200).await.unwrap();
+    let verify_result = Keypair::new();
+    let payer = harness.context.payer.pubkey();
+    harness
+        .process(
+            &[initialize_verify_result_ix(&payer, &verify_result.pubkey())],
+            &[&verify_result],
+        )
+        .await
+        .unwrap();
+    let baseline = units_for(&mut harness, |_| vec![]).await;
+
+    println!("proof length | compute units");
+    let mut previous = 0;
+    for length in [0, 1, 2, 4, 8, 16, 32] {
+        let proof = vec![[length as u8; 32]; length];
+        let total = units_for(&mut harness, |units| {
+            vec![verify_ix(
+                &verify_result.pubkey(),
+                1,
+                proof.clone(),
+                [1; 32],
+                value(units.into()),
+            )]
+        })
+        .await;
+        let units = total - baseline;
+        println!("{:>12} | {:>13}", length, units);
+        assert!(units >= previous);
+        previous = units;
+    }
+
+    assert_eq!(usize::from(DEFAULT_MAX_PROOF_LENGTH), 32);
+    assert!(
+        previous < 200_000,
+        "default maximum proof uses {} units",
+        previous
+    );
+}
@@ -0,0 +1,101 @@
+#![cfg(any(feature = "test-bpf", feature = "test-sbf"))]
+
+mod common;
+
+use anchor_lang::prelude::*;
+use chain::state::{FeedMetadata, FirstClassData};
+use common::*;
+use solana_sdk::signature::Signer;
+
+const START: u32 = 1651640000;
+
+/// Harness with an `ETH-USD` feed updated at `START`.
+async fn harness() -> (Harness, Pubkey) {
+    let fcd = fcd_address("ETH-USD").0;
+    let harness = Harness::initialized_with(|program_test| {
+        add_program_account(
+            program_test,
+            fcd,
+            &FirstClassData {
+                key: "ETH-USD".to_string(),
+                value: value(1),
+                timestamp: START,
+                metadata: FeedMetadata::umbrella("ETH-USD"),
+                has_history: false,
+                cumulative: 0,
+                aggregated: false,
+                confidence: [0u8; 32],
+                has_confidence: false,
+                registry_page: None,
+            },
+            FirstClassData::SPACE,
+        )
+    })
+    .await;
+    (harness, fcd)
+}
+
+#[tokio::test]
+async fn updates_first_class_data_with_confidence() {
+    let (mut harness, fcd) = harness().await;
+    let owner = harness.owner();
+
+    harness
+        .process(
+            &[update_fcd_with_confidence_ix(
+                &owner.pubkey(),
+                fcd,
+                "ETH-USD",
+                value(2000),
+                START + 60,
+                value(5),
+            )],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+
+    let stored: FirstClassData = harness.account(fcd).await;
+    assert_eq!(stored.value, value(2000));
+    assert_eq!(stored.timestamp, START + 60);
+    assert_eq!(stored.confidence, value(5));
+    assert!(stored.has_confidence);
+}
+
+#[tokio::test]
+async fn clears_the_confidence_on_a_plain_update() {
+    let (mut harness, fcd) = harness().await;
+    let owner = harness.owner();
+
+    harness
+        .process(
+            &[update_fcd_with_confidence_ix(
+                &owner.pubkey(),
+                fcd,
+                "ETH-USD",
+                value(2000),
+                START + 60,
+                value(5),
+            )],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+    harness
+        .process(
+            &[update_fcd_ix(
+                &owner.pubkey(),
+                fcd,
+                "ETH-USD",
+                value(2010),
+                START + 120,
+            )],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+
+    let stored: FirstClassData = harness.account(fcd).await;
+    assert_eq!(stored.value, value(2010));
+    assert!(!stored.has_confidence);
+}
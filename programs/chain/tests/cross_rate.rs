@@ -0,0 +1,234 @@
+#![cfg(any(feature = "test-bpf", feature = "test-sbf"))]
+
+mod common;
+
+use anchor_lang::prelude::*;
+use chain::cross_rate::{cross_rate, CrossRateOperation};
+use chain::errors::ChainError;
+use chain::state::{FeedMetadata, FirstClassData, ValueKind};
+use common::*;
+use solana_program_test::ProgramTest;
+use solana_sdk::instruction::Instruction;
+
+const START: u32 = 1651640000;
+const E18: u128 = 1_000_000_000_000_000_000;
+
+fn add_fcd(
+    program_test: &mut ProgramTest,
+    key: &str,
+    n: u128,
+    timestamp: u32,
+    metadata: FeedMetadata,
+) -> Pubkey {
+    let fcd = fcd_address(key).0;
+    add_program_account(
+        program_test,
+        fcd,
+        &FirstClassData {
+            key: key.to_string(),
+            value: value(n),
+            timestamp,
+            metadata,
+            has_history: false,
+            cumulative: 0,
+            aggregated: false,
+            confidence: [0u8; 32],
+            has_confidence: false,
+            registry_page: None,
+        },
+        FirstClassData::SPACE,
+    );
+    fcd
+}
+
+fn volume(decimals: u8) -> FeedMetadata {
+    FeedMetadata {
+        decimals,
+        kind: ValueKind::Volume as u8,
+        description: String::new(),
+    }
+}
+
+fn get_cross_rate_ix(base: Pubkey, quote: Pubkey, operation: u8) -> Instruction {
+    instruction(
+        chain::accounts::GetCrossRate {
+            base,
+            quote,
+            status: status_address(),
+        },
+        chain::instruction::GetCrossRate { operation },
+    )
+}
+
+struct Feeds {
+    harness: Harness,
+    sol: Pubkey,
+    eth: Pubkey,
+    zero: Pubkey,
+    root: Pubkey,
+}
+
+async fn feeds() -> Feeds {
+    let (mut sol, mut eth, mut zero, mut root) = Default::default();
+    let harness = Harness::initialized_with(|program_test| {
+        sol = add_fcd(
+            program_test,
+            "SOL-USD",
+            40 * E18,
+            START,
+            FeedMetadata::umbrella("SOL-USD"),
+        );
+        eth = add_fcd(
+            program_test,
+            "ETH-USD",
+            2000 * E18,
+            START + 10,
+            FeedMetadata::umbrella("ETH-USD"),
+        );
+        zero = add_fcd(program_test, "ZERO-USD", 0, START, volume(6));
+        root = add_fcd(
+            program_test,
+            "FIXED_ROOT",
+            1,
+            START,
+            FeedMetadata::umbrella("FIXED_ROOT"),
+        );
+    })
+    .await;
+    Feeds {
+        harness,
+        sol,
+        eth,
+        zero,
+        root,
+    }
+}
+
+fn rate(
+    operation: CrossRateOperation,
+    base: u128,
+    base_decimals: u8,
+    quote: u128,
+    quote_decimals: u8,
+) -> Option<[u8; 32]> {
+    cross_rate(
+        operation,
+        &value(base),
+        base_decimals,
+        &value(quote),
+        quote_decimals,
+    )
+}
+
+#[tokio::test]
+async fn derives_cross_rates() {
+    let mut feeds = feeds().await;
+
+    for operation in [0, 1, 2] {
+        feeds
+            .harness
+            .process(&[get_cross_rate_ix(feeds.sol, feeds.eth, operation)], &[])
+            .await
+            .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn fails_to_derive_with_an_unknown_operation() {
+    let mut feeds = feeds().await;
+    let result = feeds
+        .harness
+        .process(&[get_cross_rate_ix(feeds.sol, feeds.eth, 3)], &[])
+        .await;
+    assert_chain_error(result, ChainError::UnknownCrossRateOperation);
+}
+
+#[tokio::test]
+async fn fails_to_derive_from_raw_feeds() {
+    let mut feeds = feeds().await;
+    let result = feeds
+        .harness
+        .process(&[get_cross_rate_ix(feeds.sol, feeds.root, 1)], &[])
+        .await;
+    assert_chain_error(result, ChainError::CrossRateUnavailable);
+}
+
+#[tokio::test]
+async fn fails_to_divide_by_zero() {
+    let mut feeds = feeds().await;
+
+    let result = feeds
+        .harness
+        .process(&[get_cross_rate_ix(feeds.sol, feeds.zero, 0)], &[])
+        .await;
+    assert_chain_error(result, ChainError::CrossRateDivisionByZero);
+
+    let result = feeds
+        .harness
+        .process(&[get_cross_rate_ix(feeds.zero, feeds.zero, 2)], &[])
+        .await;
+    assert_chain_error(result, ChainError::CrossRateDivisionByZero);
+
+    // a zero quote is fine to multiply by
+    feeds
+        .harness
+        .process(&[get_cross_rate_ix(feeds.sol, feeds.zero, 1)], &[])
+        .await
+        .unwrap();
+}
+
+#[test]
+fn divides_multiplies_and_inverts() {
+    use CrossRateOperation::*;
+
+    // SOL-ETH from SOL-USD 40 and ETH-USD 2000
+    assert_eq!(
+        rate(Divide, 40 * E18, 18, 2000 * E18, 18),
+        Some(value(E18 / 50))
+    );
+    // SOL-EUR from SOL-USD 40 and USD-EUR 0.9
+    assert_eq!(
+        rate(Multiply, 40 * E18, 18, 9 * E18 / 10, 18),
+        Some(value(36 * E18))
+    );
+    // USD-ETH from ETH-USD 2000
+    assert_eq!(
+        rate(Invert, 2000 * E18, 18, 2000 * E18, 18),
+        Some(value(E18 / 2000))
+    );
+    // rounded down
+    assert_eq!(rate(Divide, E18, 18, 3 * E18, 18), Some(value(E18 / 3)));
+}
+
+#[test]
+fn converts_between_decimals() {
+    use CrossRateOperation::*;
+
+    // 1.5 with 6 decimals over 0.5 with 8 decimals
+    assert_eq!(
+        rate(Divide, 1_500_000, 6, 50_000_000, 8),
+        Some(value(3 * E18))
+    );
+    assert_eq!(
+        rate(Multiply, 1_500_000, 6, 50_000_000, 8),
+        Some(value(3 * E18 / 4))
+    );
+    assert_eq!(rate(Invert, 50_000_000, 8, 0, 0), Some(value(2 * E18)));
+    // decimals beyond what 512 bits can scale by
+    assert_eq!(rate(Divide, E18, 200, 1, 0), Some(value(0)));
+    assert_eq!(rate(Divide, 1, 0, 1, 200), None);
+}
+
+#[test]
+fn rejects_rates_that_do_not_fit() {
+    use CrossRateOperation::*;
+
+    let max = [0xff; 32];
+    // intermediate products past 256 bits are fine
+    assert_eq!(cross_rate(Multiply, &max, 18, &value(E18), 18), Some(max));
+    assert_eq!(cross_rate(Divide, &max, 18, &value(E18), 18), Some(max));
+    assert_eq!(cross_rate(Multiply, &max, 18, &value(2 * E18), 18), None);
+    assert_eq!(cross_rate(Divide, &max, 18, &value(1), 18), None);
+    assert_eq!(rate(Divide, 1, 18, 0, 18), None);
+    assert_eq!(rate(Invert, 0, 18, 1, 18), None);
+}
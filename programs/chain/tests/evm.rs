@@ -0,0 +1,337 @@
+#![cfg(any(feature = "test-bpf", feature = "test-sbf"))]
+
+mod common;
+
+use chain::errors::ChainError;
+use chain::state::{Block, EvmBlockHeader, EvmValidatorSet, RewardVault, Status, MAX_VALIDATORS};
+use common::*;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transport::TransportError;
+
+const START: u32 = 1651640000;
+
+struct Evm {
+    harness: Harness,
+    validators: Vec<libsecp256k1::SecretKey>,
+}
+
+fn secret_key(n: u8) -> libsecp256k1::SecretKey {
+    libsecp256k1::SecretKey::parse(&[n; 32]).unwrap()
+}
+
+fn header(block_id: u32, timestamp: u32) -> EvmBlockHeader {
+    EvmBlockHeader {
+        block_id,
+        root: value(u128::from(block_id)),
+        timestamp,
+    }
+}
+
+/// Harness with three EVM validators and a quorum of two, the way
+/// `set_evm_validators` leaves them.
+async fn evm() -> Evm {
+    let validators: Vec<libsecp256k1::SecretKey> = (1..=3).map(secret_key).collect();
+    let addresses = validators.iter().map(eth_address).collect();
+    let harness = Harness::initialized_with(|program_test| {
+        add_program_account(
+            program_test,
+            evm_validator_set_address(),
+            &EvmValidatorSet {
+                validators: addresses,
+                quorum: 2,
+            },
+            EvmValidatorSet::SPACE,
+        );
+    })
+    .await;
+    Evm {
+        harness,
+        validators,
+    }
+}
+
+impl Evm {
+    /// Imports `header` with the signatures of `signers`, sent by a new importer.
+    async fn import(
+        &mut self,
+        header: &EvmBlockHeader,
+        signers: &[libsecp256k1::SecretKey],
+    ) -> std::result::Result<(), TransportError> {
+        let importer = self.harness.funded_keypair().await;
+        self.harness
+            .process(
+                &[
+                    evm_signatures_ix(0, &header.encode(), signers),
+                    import_evm_block_ix(&importer.pubkey(), header),
+                ],
+                &[&importer],
+            )
+            .await
+    }
+}
+
+#[test]
+fn decodes_abi_encoded_headers() {
+    let header = header(7, START);
+    let payload = header.encode();
+    assert_eq!(&payload[28..32], &7u32.to_be_bytes());
+    assert_eq!(&payload[92..], &START.to_be_bytes());
+    assert_eq!(EvmBlockHeader::decode(&payload), Some(header));
+
+    assert_eq!(EvmBlockHeader::decode(&payload[..64]), None);
+    let mut too_large = payload;
+    too_large[27] = 1;
+    assert_eq!(EvmBlockHeader::decode(&too_large), None);
+}
+
+#[tokio::test]
+async fn sets_evm_validators() {
+    let mut evm = evm().await;
+    let owner = evm.harness.owner();
+    let validators = vec![eth_address(&secret_key(4)), eth_address(&secret_key(5))];
+
+    evm.harness
+        .process(
+            &[set_evm_validators_ix(
+                &owner.pubkey(),
+                validators.clone(),
+                1,
+            )],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+
+    let set: EvmValidatorSet = evm.harness.account(evm_validator_set_address()).await;
+    assert_eq!(set.validators, validators);
+    assert_eq!(set.quorum, 1);
+}
+
+#[tokio::test]
+async fn fails_to_set_invalid_evm_validators() {
+    let mut evm = evm().await;
+    let owner = evm.harness.owner();
+    let address = eth_address(&secret_key(4));
+
+    let result = evm
+        .harness
+        .process(
+            &[set_evm_validators_ix(&owner.pubkey(), vec![address], 2)],
+            &[&owner],
+        )
+        .await;
+    assert_chain_error(result, ChainError::InvalidQuorum);
+
+    let result = evm
+        .harness
+        .process(
+            &[set_evm_validators_ix(
+                &owner.pubkey(),
+                vec![address, address],
+                1,
+            )],
+            &[&owner],
+        )
+        .await;
+    assert_chain_error(result, ChainError::DuplicateValidator);
+
+    let result = evm
+        .harness
+        .process(
+            &[set_evm_validators_ix(
+                &owner.pubkey(),
+                vec![address; MAX_VALIDATORS + 1],
+                1,
+            )],
+            &[&owner],
+        )
+        .await;
+    assert_chain_error(result, ChainError::TooManyValidators);
+
+    let stranger = Keypair::new();
+    let result = evm
+        .harness
+        .process(
+            &[set_evm_validators_ix(&stranger.pubkey(), vec![address], 1)],
+            &[&stranger],
+        )
+        .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn imports_a_block_signed_by_a_quorum() {
+    let mut evm = evm().await;
+    let header = header(7, START);
+    let signers = evm.validators[1..].to_vec();
+
+    evm.import(&header, &signers).await.unwrap();
+
+    let block: Block = evm.harness.account(block_address(7).0).await;
+    assert_eq!(block.block_id, 7);
+    assert_eq!(block.root, header.root);
+    assert_eq!(block.timestamp, START);
+    assert_eq!(block.hash_scheme, 0);
+    let status: Status = evm.harness.account(status_address()).await;
+    assert_eq!(status.last_id, 7);
+    assert_eq!(status.last_data_timestamp, START);
+    assert_eq!(status.next_block_id, 8);
+}
+
+#[tokio::test]
+async fn imports_without_earning_rewards() {
+    let mut evm = evm().await;
+    let owner = evm.harness.owner();
+    evm.harness
+        .process(&[set_rewards_enabled_ix(&owner.pubkey(), true)], &[&owner])
+        .await
+        .unwrap();
+
+    let signers = evm.validators.clone();
+
+    // anyone can import, so neither the importer nor the vault is involved
+    evm.import(&header(7, START), &signers).await.unwrap();
+
+    let vault: RewardVault = evm.harness.account(reward_vault_address()).await;
+    assert!(vault.entries.is_empty());
+}
+
+#[tokio::test]
+async fn imports_with_signatures_in_separate_instructions() {
+    let mut evm = evm().await;
+    let header = header(7, START);
+    let importer = evm.harness.funded_keypair().await;
+
+    evm.harness
+        .process(
+            &[
+                evm_signatures_ix(0, &header.encode(), &evm.validators[..1]),
+                evm_signatures_ix(1, &header.encode(), &evm.validators[2..]),
+                import_evm_block_ix(&importer.pubkey(), &header),
+            ],
+            &[&importer],
+        )
+        .await
+        .unwrap();
+
+    assert!(evm.harness.exists(block_address(7).0).await);
+}
+
+#[tokio::test]
+async fn fails_to_import_below_the_quorum() {
+    let mut evm = evm().await;
+    let signers = evm.validators[..1].to_vec();
+
+    let result = evm.import(&header(7, START), &signers).await;
+
+    assert_chain_error(result, ChainError::EvmQuorumNotReached);
+}
+
+#[tokio::test]
+async fn counts_each_validator_once() {
+    let mut evm = evm().await;
+    let signers = vec![evm.validators[0], evm.validators[0]];
+
+    let result = evm.import(&header(7, START), &signers).await;
+
+    assert_chain_error(result, ChainError::EvmQuorumNotReached);
+}
+
+#[tokio::test]
+async fn ignores_signatures_of_unregistered_keys() {
+    let mut evm = evm().await;
+    let signers = vec![evm.validators[0], secret_key(9)];
+
+    let result = evm.import(&header(7, START), &signers).await;
+
+    assert_chain_error(result, ChainError::EvmQuorumNotReached);
+}
+
+#[tokio::test]
+async fn fails_to_import_signatures_of_another_header() {
+    let mut evm = evm().await;
+    let importer = evm.harness.funded_keypair().await;
+    let signed = header(7, START);
+    let imported = EvmBlockHeader {
+        root: value(1),
+        ..signed
+    };
+
+    let result = evm
+        .harness
+        .process(
+            &[
+                evm_signatures_ix(0, &signed.encode(), &evm.validators),
+                import_evm_block_ix(&importer.pubkey(), &imported),
+            ],
+            &[&importer],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::EvmQuorumNotReached);
+}
+
+#[tokio::test]
+async fn fails_to_import_with_an_invalid_signature() {
+    let mut evm = evm().await;
+    let importer = evm.harness.funded_keypair().await;
+    let header = header(7, START);
+
+    let mut forged = evm_signatures_ix(0, &header.encode(), &evm.validators);
+    // the first signature follows the offsets of all three and the first address
+    forged.data[1 + 3 * 11 + 20] ^= 1;
+    let result = evm
+        .harness
+        .process(
+            &[forged, import_evm_block_ix(&importer.pubkey(), &header)],
+            &[&importer],
+        )
+        .await;
+
+    assert!(result.is_err());
+    assert!(!evm.harness.exists(block_address(7).0).await);
+}
+
+#[tokio::test]
+async fn fails_to_import_an_invalid_payload() {
+    let mut evm = evm().await;
+    let importer = evm.harness.funded_keypair().await;
+    let header = header(7, START);
+    let payload = header.encode();
+
+    let result = evm
+        .harness
+        .process(
+            &[
+                evm_signatures_ix(0, &payload[..64], &evm.validators),
+                import_evm_payload_ix(&importer.pubkey(), 7, payload[..64].to_vec()),
+            ],
+            &[&importer],
+        )
+        .await;
+    assert_chain_error(result, ChainError::InvalidEvmPayload);
+
+    // stored at the address of another block
+    let result = evm
+        .harness
+        .process(
+            &[
+                evm_signatures_ix(0, &payload, &evm.validators),
+                import_evm_payload_ix(&importer.pubkey(), 8, payload.to_vec()),
+            ],
+            &[&importer],
+        )
+        .await;
+    assert_chain_error(result, ChainError::InvalidEvmPayload);
+}
+
+#[tokio::test]
+async fn fails_to_import_older_data() {
+    let mut evm = evm().await;
+    let signers = evm.validators.clone();
+    evm.import(&header(7, START), &signers).await.unwrap();
+
+    let result = evm.import(&header(8, START), &signers).await;
+
+    assert_chain_error(result, ChainError::CannotSubmitOlderData);
+}
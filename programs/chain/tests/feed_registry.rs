@@ -0,0 +1,392 @@
+#![cfg(any(feature = "test-bpf", feature = "test-sbf"))]
+
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::MAX_RETURN_DATA;
+use chain::errors::ChainError;
+use chain::state::{FeedEntry, FeedMetadata, FeedRegistry, FirstClassData, FEEDS_PER_PAGE};
+use common::*;
+use solana_program_test::ProgramTest;
+use solana_sdk::signature::{Keypair, Signer};
+
+/// Adds a feed initialized before the registry existed.
+fn add_unregistered_fcd(program_test: &mut ProgramTest, key: &str) -> Pubkey {
+    let fcd = fcd_address(key).0;
+    add_program_account(
+        program_test,
+        fcd,
+        &FirstClassData {
+            key: key.to_string(),
+            value: value(1),
+            timestamp: 1651640000,
+            metadata: FeedMetadata::default(),
+            has_history: false,
+            cumulative: 0,
+            aggregated: false,
+            confidence: [0u8; 32],
+            has_confidence: false,
+            registry_page: None,
+        },
+        FirstClassData::SPACE,
+    );
+    fcd
+}
+
+fn entry(i: usize, description: &str) -> FeedEntry {
+    FeedEntry {
+        key: format!("FEED{}-USD", i),
+        fcd: Pubkey::new_unique(),
+        metadata: FeedMetadata {
+            description: description.to_string(),
+            ..FeedMetadata::umbrella("FEED-USD")
+        },
+    }
+}
+
+async fn registry(harness: &mut Harness, page: u32) -> FeedRegistry {
+    harness.account(feed_registry_address(page)).await
+}
+
+#[tokio::test]
+async fn registers_initialized_first_class_data() {
+    let mut harness = Harness::initialized().await;
+    let owner = harness.owner();
+
+    harness
+        .process(
+            &[
+                initialize_fcd_ix(&owner.pubkey(), "ETH-USD", value(1), 1651640000),
+                initialize_fcd_ix(&owner.pubkey(), "FIXED_ROOT", [7; 32], 1651640000),
+            ],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+
+    let registry = registry(&mut harness, 0).await;
+    assert_eq!(
+        registry.entries,
+        vec![
+            FeedEntry {
+                key: "ETH-USD".to_string(),
+                fcd: fcd_address("ETH-USD").0,
+                metadata: FeedMetadata::umbrella("ETH-USD"),
+            },
+            FeedEntry {
+                key: "FIXED_ROOT".to_string(),
+                fcd: fcd_address("FIXED_ROOT").0,
+                metadata: FeedMetadata::umbrella("FIXED_ROOT"),
+            },
+        ]
+    );
+
+    harness
+        .process(
+            &[instruction(
+                chain::accounts::GetRegisteredFeeds {
+                    registry: feed_registry_address(0),
+                },
+                chain::instruction::GetRegisteredFeeds {
+                    start: 0,
+                    limit: 10,
+                },
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn unregisters_closed_first_class_data() {
+    let mut harness = Harness::initialized().await;
+    let owner = harness.owner();
+    harness
+        .process(
+            &[
+                initialize_fcd_ix(&owner.pubkey(), "ETH-USD", value(1), 1651640000),
+                initialize_fcd_ix(&owner.pubkey(), "BTC-USD", value(1), 1651640000),
+            ],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+
+    harness
+        .process(
+            &[close_fcd_ix(
+                &owner.pubkey(),
+                fcd_address("ETH-USD").0,
+                "ETH-USD",
+                &owner.pubkey(),
+            )],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+
+    let registry = registry(&mut harness, 0).await;
+    assert_eq!(registry.entries.len(), 1);
+    assert_eq!(registry.entries[0].key, "BTC-USD");
+}
+
+#[tokio::test]
+async fn initializes_feed_registry_pages() {
+    let mut harness = Harness::initialized().await;
+    let owner = harness.owner();
+
+    harness
+        .process(
+            &[initialize_feed_registry_page_ix(&owner.pubkey(), 1)],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+
+    let registry = registry(&mut harness, 1).await;
+    assert_eq!(registry.page, 1);
+    assert!(registry.entries.is_empty());
+}
+
+#[tokio::test]
+async fn fails_to_initialize_feed_registry_page_by_unauthorized_user() {
+    let mut harness = Harness::initialized().await;
+    let intruder = harness.funded_keypair().await;
+
+    let result = harness
+        .process(
+            &[initialize_feed_registry_page_ix(&intruder.pubkey(), 1)],
+            &[&intruder],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::OnlyOwnerViolation);
+}
+
+#[tokio::test]
+async fn registers_first_class_data_initialized_before_the_registry() {
+    let mut fcd = Pubkey::default();
+    let mut harness = Harness::initialized_with(|program_test| {
+        fcd = add_unregistered_fcd(program_test, "ETH-USD")
+    })
+    .await;
+    let owner = harness.owner();
+
+    harness
+        .process(
+            &[register_fcd_ix(&owner.pubkey(), fcd, "ETH-USD", 0)],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+
+    let registry = registry(&mut harness, 0).await;
+    assert_eq!(
+        registry.entries,
+        vec![FeedEntry {
+            key: "ETH-USD".to_string(),
+            fcd,
+            metadata: FeedMetadata::umbrella("ETH-USD"),
+        }]
+    );
+    let stored: FirstClassData = harness.account(fcd).await;
+    assert_eq!(stored.registry_page, Some(0));
+}
+
+#[tokio::test]
+async fn fails_to_register_twice() {
+    let mut fcd = Pubkey::default();
+    let mut harness = Harness::initialized_with(|program_test| {
+        fcd = add_unregistered_fcd(program_test, "ETH-USD")
+    })
+    .await;
+    let owner = harness.owner();
+    let register = register_fcd_ix(&owner.pubkey(), fcd, "ETH-USD", 0);
+
+    let result = harness
+        .process(&[register.clone(), register], &[&owner])
+        .await;
+
+    assert_chain_error(result, ChainError::FeedAlreadyRegistered);
+}
+
+#[tokio::test]
+async fn fails_to_register_on_two_pages() {
+    let mut fcd = Pubkey::default();
+    let mut harness = Harness::initialized_with(|program_test| {
+        fcd = add_unregistered_fcd(program_test, "ETH-USD");
+        add_program_account(
+            program_test,
+            feed_registry_address(1),
+            &FeedRegistry {
+                page: 1,
+                entries: vec![],
+            },
+            FeedRegistry::SPACE,
+        );
+    })
+    .await;
+    let owner = harness.owner();
+    harness
+        .process(
+            &[register_fcd_ix(&owner.pubkey(), fcd, "ETH-USD", 0)],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+
+    let result = harness
+        .process(
+            &[register_fcd_ix(&owner.pubkey(), fcd, "ETH-USD", 1)],
+            &[&owner],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::FeedAlreadyRegistered);
+    assert!(registry(&mut harness, 1).await.entries.is_empty());
+}
+
+#[tokio::test]
+async fn fails_to_register_on_a_full_page() {
+    let mut fcd = Pubkey::default();
+    let mut harness = Harness::initialized_with(|program_test| {
+        fcd = add_unregistered_fcd(program_test, "ETH-USD");
+        add_program_account(
+            program_test,
+            feed_registry_address(1),
+            &FeedRegistry {
+                page: 1,
+                entries: (0..FEEDS_PER_PAGE).map(|i| entry(i, "")).collect(),
+            },
+            FeedRegistry::SPACE,
+        );
+    })
+    .await;
+    let owner = harness.owner();
+
+    let result = harness
+        .process(
+            &[register_fcd_ix(&owner.pubkey(), fcd, "ETH-USD", 1)],
+            &[&owner],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::FeedRegistryPageFull);
+}
+
+#[tokio::test]
+async fn fails_to_register_with_wrong_key() {
+    let mut fcd = Pubkey::default();
+    let mut harness = Harness::initialized_with(|program_test| {
+        fcd = add_unregistered_fcd(program_test, "ETH-USD")
+    })
+    .await;
+    let owner = harness.owner();
+
+    let result = harness
+        .process(
+            &[register_fcd_ix(&owner.pubkey(), fcd, "BTC-USD", 0)],
+            &[&owner],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::WrongFCDKeyForAccount);
+}
+
+#[tokio::test]
+async fn fails_to_register_by_unauthorized_user() {
+    let mut fcd = Pubkey::default();
+    let mut harness = Harness::initialized_with(|program_test| {
+        fcd = add_unregistered_fcd(program_test, "ETH-USD")
+    })
+    .await;
+    let intruder = Keypair::new();
+
+    let result = harness
+        .process(
+            &[register_fcd_ix(&intruder.pubkey(), fcd, "ETH-USD", 0)],
+            &[&intruder],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::OnlyOwnerViolation);
+}
+
+#[tokio::test]
+async fn closes_unregistered_first_class_data() {
+    let mut fcd = Pubkey::default();
+    let mut harness = Harness::initialized_with(|program_test| {
+        fcd = add_unregistered_fcd(program_test, "ETH-USD")
+    })
+    .await;
+    let owner = harness.owner();
+
+    // with no registry page recorded, that of page 0 is passed and left alone
+    harness
+        .process(
+            &[close_fcd_ix(
+                &owner.pubkey(),
+                fcd,
+                "ETH-USD",
+                &owner.pubkey(),
+            )],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+
+    assert!(!harness.exists(fcd).await);
+    assert!(registry(&mut harness, 0).await.entries.is_empty());
+}
+
+#[tokio::test]
+async fn fails_to_close_with_another_registry_page() {
+    let mut harness = Harness::initialized().await;
+    let owner = harness.owner();
+    let fcd = fcd_address("ETH-USD").0;
+    harness
+        .process(
+            &[
+                initialize_feed_registry_page_ix(&owner.pubkey(), 1),
+                initialize_fcd_ix(&owner.pubkey(), "ETH-USD", value(1), 1651640000),
+            ],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+
+    let mut close = close_fcd_ix(&owner.pubkey(), fcd, "ETH-USD", &owner.pubkey());
+    close.accounts[4].pubkey = feed_registry_address(1);
+    let result = harness.process(&[close], &[&owner]).await;
+
+    assert_eq!(
+        error_code(result),
+        u32::from(anchor_lang::error::ErrorCode::ConstraintSeeds)
+    );
+    assert!(harness.exists(fcd).await);
+}
+
+#[test]
+fn returns_as_many_entries_as_fit_in_return_data() {
+    let description = "x".repeat(64);
+    let registry = FeedRegistry {
+        page: 0,
+        entries: (0..FEEDS_PER_PAGE)
+            .map(|i| entry(i, &description))
+            .collect(),
+    };
+
+    let returned = registry.returned_entries(0, FEEDS_PER_PAGE);
+    assert!(returned.len() < FEEDS_PER_PAGE);
+    assert!(returned.try_to_vec().unwrap().len() <= MAX_RETURN_DATA);
+    let one_more = registry.returned_entries(0, returned.len() + 1);
+    assert_eq!(one_more, returned);
+    assert_eq!(returned[..], registry.entries[..returned.len()]);
+
+    assert_eq!(
+        registry.returned_entries(3, 2),
+        registry.entries[3..5].to_vec()
+    );
+    assert!(registry.returned_entries(FEEDS_PER_PAGE, 10).is_empty());
+}
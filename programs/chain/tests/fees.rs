@@ -0,0 +1,531 @@
+#![cfg(any(feature = "test-bpf", feature = "test-sbf"))]
+
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use chain::errors::ChainError;
+use chain::state::{
+    Block, FeeConfig, FeeSchedule, FeedMetadata, FirstClassData, Status, Subscription,
+    VerifyResult, MAX_FEE_EXEMPT,
+};
+use common::*;
+use solana_program_test::ProgramTest;
+use solana_sdk::signature::{Keypair, Signer};
+
+const START: i64 = 1651640000;
+const READ_FEE: u64 = 5_000;
+const VERIFY_FEE: u64 = 7_000;
+
+fn lamport_schedule() -> FeeSchedule {
+    FeeSchedule {
+        verify_fee: VERIFY_FEE,
+        read_fee: READ_FEE,
+        subscription_price: 100_000,
+        subscription_period: 3600,
+        ..FeeSchedule::default()
+    }
+}
+
+fn add_fcd(program_test: &mut ProgramTest, key: &str) -> Pubkey {
+    let fcd = fcd_address(key).0;
+    add_program_account(
+        program_test,
+        fcd,
+        &FirstClassData {
+            key: key.to_string(),
+            value: value(1000),
+            timestamp: START as u32,
+            metadata: FeedMetadata::umbrella(key),
+            has_history: false,
+            cumulative: 0,
+            aggregated: false,
+            confidence: [0u8; 32],
+            has_confidence: false,
+            registry_page: None,
+        },
+        FirstClassData::SPACE,
+    );
+    fcd
+}
+
+struct Fees {
+    harness: Harness,
+    eth: Pubkey,
+    consumer: Keypair,
+}
+
+/// Harness with an `ETH-USD` feed and a fee config, which `enable` turns on.
+async fn fees_with(setup: impl FnOnce(&mut ProgramTest, &Keypair)) -> Fees {
+    let consumer = Keypair::new();
+    let mut eth = Pubkey::default();
+    let mut harness = Harness::initialized_with(|program_test| {
+        eth = add_fcd(program_test, "ETH-USD");
+        // `set_fee_schedule` creates it with `init_if_needed`
+        add_program_account(
+            program_test,
+            fee_config_address(),
+            &FeeConfig::default(),
+            FeeConfig::SPACE,
+        );
+        setup(program_test, &consumer);
+    })
+    .await;
+    harness.set_time(START).await;
+    Fees {
+        harness,
+        eth,
+        consumer,
+    }
+}
+
+async fn fees() -> Fees {
+    fees_with(|_, _| {}).await
+}
+
+impl Fees {
+    async fn enable(&mut self, schedule: FeeSchedule, exempt: Vec<Pubkey>) {
+        let owner = self.harness.owner();
+        self.harness
+            .process(
+                &[set_fee_schedule_ix(&owner.pubkey(), schedule, exempt, true)],
+                &[&owner],
+            )
+            .await
+            .unwrap();
+    }
+
+    fn payer(&self) -> Pubkey {
+        self.harness.context.payer.pubkey()
+    }
+
+    fn read_ix(&self, operation: u8) -> Instruction {
+        get_cross_rate_ix(self.eth, self.eth, operation)
+    }
+
+    async fn collected(&mut self) -> u64 {
+        self.harness
+            .context
+            .banks_client
+            .get_balance(fee_config_address())
+            .await
+            .unwrap()
+    }
+}
+
+fn get_cross_rate_ix(base: Pubkey, quote: Pubkey, operation: u8) -> Instruction {
+    instruction(
+        chain::accounts::GetCrossRate {
+            base,
+            quote,
+            status: status_address(),
+        },
+        chain::instruction::GetCrossRate { operation },
+    )
+}
+
+fn system_program() -> AccountMeta {
+    AccountMeta::new_readonly(anchor_lang::system_program::ID, false)
+}
+
+#[tokio::test]
+async fn sets_fee_schedule() {
+    let mut fees = fees().await;
+    let exempt = vec![Pubkey::new_unique()];
+    fees.enable(lamport_schedule(), exempt.clone()).await;
+
+    let config: FeeConfig = fees.harness.account(fee_config_address()).await;
+    assert_eq!(config.schedule, lamport_schedule());
+    assert_eq!(config.exempt, exempt);
+    let status: Status = fees.harness.account(status_address()).await;
+    assert!(status.fees_enabled);
+
+    let owner = fees.harness.owner();
+    fees.harness
+        .process(
+            &[set_fee_schedule_ix(
+                &owner.pubkey(),
+                lamport_schedule(),
+                vec![],
+                false,
+            )],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+    let status: Status = fees.harness.account(status_address()).await;
+    assert!(!status.fees_enabled);
+    // reads no longer take fee accounts
+    fees.harness.process(&[fees.read_ix(2)], &[]).await.unwrap();
+}
+
+#[tokio::test]
+async fn fails_to_set_an_invalid_fee_schedule() {
+    let mut fees = fees().await;
+    let owner = fees.harness.owner();
+
+    let exempt = (0..=MAX_FEE_EXEMPT).map(|_| Pubkey::new_unique()).collect();
+    let result = fees
+        .harness
+        .process(
+            &[set_fee_schedule_ix(
+                &owner.pubkey(),
+                lamport_schedule(),
+                exempt,
+                true,
+            )],
+            &[&owner],
+        )
+        .await;
+    assert_chain_error(result, ChainError::TooManyFeeExempt);
+
+    let schedule = FeeSchedule {
+        subscription_period: 0,
+        ..lamport_schedule()
+    };
+    let result = fees
+        .harness
+        .process(
+            &[set_fee_schedule_ix(&owner.pubkey(), schedule, vec![], true)],
+            &[&owner],
+        )
+        .await;
+    assert_chain_error(result, ChainError::InvalidSubscriptionPeriod);
+}
+
+#[tokio::test]
+async fn fails_to_set_fee_schedule_by_unauthorized_user() {
+    let mut fees = fees().await;
+    let intruder = fees.harness.funded_keypair().await;
+
+    let result = fees
+        .harness
+        .process(
+            &[set_fee_schedule_ix(
+                &intruder.pubkey(),
+                lamport_schedule(),
+                vec![],
+                true,
+            )],
+            &[&intruder],
+        )
+        .await;
+    assert_chain_error(result, ChainError::OnlyOwnerViolation);
+}
+
+#[tokio::test]
+async fn charges_lamport_fees_for_reads() {
+    let mut fees = fees().await;
+    fees.enable(lamport_schedule(), vec![]).await;
+    let collected = fees.collected().await;
+
+    let ix = with_fees(fees.read_ix(2), &fees.payer(), &[system_program()]);
+    fees.harness.process(&[ix], &[]).await.unwrap();
+
+    assert_eq!(fees.collected().await, collected + READ_FEE);
+}
+
+#[tokio::test]
+async fn charges_lamport_fees_for_verification() {
+    let verify_result = Keypair::new();
+    let mut fees = fees_with(|program_test, _| {
+        add_program_account(
+            program_test,
+            block_address(1).0,
+            &Block {
+                block_id: 1,
+                root: [1; 32],
+                timestamp: START as u32,
+                hash_scheme: 0,
+            },
+            8 + 4 + 32 + 4 + 1,
+        );
+        add_program_account(
+            program_test,
+            verify_result.pubkey(),
+            &VerifyResult::default(),
+            8 + 1,
+        );
+    })
+    .await;
+    fees.enable(lamport_schedule(), vec![]).await;
+    let collected = fees.collected().await;
+
+    let verify = verify_ix(&verify_result.pubkey(), 1, vec![], [2; 32], [3; 32]);
+    let result = fees
+        .harness
+        .process(std::slice::from_ref(&verify), &[])
+        .await;
+    assert_chain_error(result, ChainError::MissingFeeAccounts);
+
+    let ix = with_fees(verify, &fees.payer(), &[system_program()]);
+    fees.harness.process(&[ix], &[]).await.unwrap();
+    assert_eq!(fees.collected().await, collected + VERIFY_FEE);
+}
+
+#[tokio::test]
+async fn fails_to_read_without_fee_accounts() {
+    let mut fees = fees().await;
+    fees.enable(lamport_schedule(), vec![]).await;
+
+    let result = fees.harness.process(&[fees.read_ix(0)], &[]).await;
+    assert_chain_error(result, ChainError::MissingFeeAccounts);
+
+    let ix = with_fees(fees.read_ix(1), &fees.payer(), &[]);
+    let result = fees.harness.process(&[ix], &[]).await;
+    assert_chain_error(result, ChainError::MissingFeeAccounts);
+
+    // the fee payer has to sign
+    let mut ix = with_fees(
+        fees.read_ix(2),
+        &fees.consumer.pubkey(),
+        &[system_program()],
+    );
+    ix.accounts[4].is_signer = false;
+    let result = fees.harness.process(&[ix], &[]).await;
+    assert_chain_error(result, ChainError::MissingFeeAccounts);
+}
+
+#[tokio::test]
+async fn lets_exempt_keys_read_for_free() {
+    let mut fees = fees().await;
+    let consumer = fees.consumer.pubkey();
+    fees.enable(lamport_schedule(), vec![consumer]).await;
+    let collected = fees.collected().await;
+
+    let ix = with_fees(fees.read_ix(2), &consumer, &[]);
+    let consumer = clone_keypair(&fees.consumer);
+    fees.harness.process(&[ix], &[&consumer]).await.unwrap();
+
+    assert_eq!(fees.collected().await, collected);
+}
+
+#[tokio::test]
+async fn charges_and_withdraws_token_fees() {
+    let (mut mint, mut source, mut vault, mut foreign_vault) = Default::default();
+    let mut fees = fees_with(|program_test, consumer| {
+        add_token_program(program_test);
+        mint = add_mint(program_test);
+        source = add_token_account(program_test, mint, consumer.pubkey(), 1_000_000);
+        vault = add_token_account(program_test, mint, fee_config_address(), 0);
+        foreign_vault = add_token_account(program_test, mint, Pubkey::new_unique(), 0);
+    })
+    .await;
+    let schedule = FeeSchedule {
+        mint,
+        ..lamport_schedule()
+    };
+    fees.enable(schedule, vec![]).await;
+    let consumer = clone_keypair(&fees.consumer);
+    let payment = |vault| {
+        [
+            AccountMeta::new(source, false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+        ]
+    };
+
+    let ix = with_fees(fees.read_ix(2), &consumer.pubkey(), &payment(vault));
+    fees.harness.process(&[ix], &[&consumer]).await.unwrap();
+    assert_eq!(fees.harness.tokens(vault).await, READ_FEE);
+    assert_eq!(fees.harness.tokens(source).await, 1_000_000 - READ_FEE);
+
+    let ix = with_fees(fees.read_ix(1), &consumer.pubkey(), &payment(foreign_vault));
+    let result = fees.harness.process(&[ix], &[&consumer]).await;
+    assert_chain_error(result, ChainError::InvalidFeeVault);
+
+    let owner = fees.harness.owner();
+    let mut ix = withdraw_fees_ix(&owner.pubkey(), &foreign_vault, READ_FEE);
+    ix.accounts.extend([
+        AccountMeta::new(vault, false),
+        AccountMeta::new_readonly(spl_token::ID, false),
+    ]);
+    fees.harness.process(&[ix], &[&owner]).await.unwrap();
+    assert_eq!(fees.harness.tokens(vault).await, 0);
+    assert_eq!(fees.harness.tokens(foreign_vault).await, READ_FEE);
+}
+
+#[tokio::test]
+async fn lets_subscribers_read_for_free() {
+    let other = Pubkey::new_unique();
+    let mut fees = fees_with(|program_test, consumer| {
+        add_program_account(
+            program_test,
+            subscription_address(&consumer.pubkey()),
+            &Subscription {
+                consumer: consumer.pubkey(),
+                paid_until: START + 60,
+            },
+            Subscription::SPACE,
+        );
+        add_program_account(
+            program_test,
+            subscription_address(&other),
+            &Subscription {
+                consumer: other,
+                paid_until: START + 60,
+            },
+            Subscription::SPACE,
+        );
+    })
+    .await;
+    fees.enable(lamport_schedule(), vec![]).await;
+    let collected = fees.collected().await;
+    let consumer = clone_keypair(&fees.consumer);
+    let subscription = |consumer: &Pubkey| {
+        [AccountMeta::new_readonly(
+            subscription_address(consumer),
+            false,
+        )]
+    };
+
+    let ix = with_fees(
+        fees.read_ix(2),
+        &consumer.pubkey(),
+        &subscription(&consumer.pubkey()),
+    );
+    fees.harness.process(&[ix], &[&consumer]).await.unwrap();
+    assert_eq!(fees.collected().await, collected);
+
+    let ix = with_fees(fees.read_ix(1), &consumer.pubkey(), &subscription(&other));
+    let result = fees.harness.process(&[ix], &[&consumer]).await;
+    assert_chain_error(result, ChainError::WrongSubscription);
+
+    fees.harness.set_time(START + 60).await;
+    let ix = with_fees(
+        fees.read_ix(0),
+        &consumer.pubkey(),
+        &subscription(&consumer.pubkey()),
+    );
+    let result = fees.harness.process(&[ix], &[&consumer]).await;
+    assert_chain_error(result, ChainError::SubscriptionExpired);
+}
+
+#[tokio::test]
+async fn subscribes() {
+    let mut fees = fees_with(|program_test, consumer| {
+        add_program_account(
+            program_test,
+            subscription_address(&consumer.pubkey()),
+            &Subscription {
+                consumer: consumer.pubkey(),
+                paid_until: START - 10,
+            },
+            Subscription::SPACE,
+        );
+    })
+    .await;
+    fees.enable(lamport_schedule(), vec![]).await;
+    let collected = fees.collected().await;
+    let consumer = fees.consumer.pubkey();
+    let payer = fees.payer();
+
+    fees.harness
+        .process(&[subscribe_ix(&payer, &consumer, 2)], &[])
+        .await
+        .unwrap();
+
+    let subscription: Subscription = fees.harness.account(subscription_address(&consumer)).await;
+    assert_eq!(subscription.consumer, consumer);
+    assert_eq!(subscription.paid_until, START + 2 * 3600);
+    assert_eq!(fees.collected().await, collected + 200_000);
+
+    // extends an active subscription from its end
+    fees.harness
+        .process(&[subscribe_ix(&payer, &consumer, 1)], &[])
+        .await
+        .unwrap();
+    let subscription: Subscription = fees.harness.account(subscription_address(&consumer)).await;
+    assert_eq!(subscription.paid_until, START + 3 * 3600);
+}
+
+#[tokio::test]
+async fn fails_to_subscribe_without_subscriptions() {
+    let mut fees = fees_with(|program_test, consumer| {
+        add_program_account(
+            program_test,
+            subscription_address(&consumer.pubkey()),
+            &Subscription::default(),
+            Subscription::SPACE,
+        );
+    })
+    .await;
+    let schedule = FeeSchedule {
+        subscription_price: 0,
+        ..lamport_schedule()
+    };
+    fees.enable(schedule, vec![]).await;
+    let consumer = fees.consumer.pubkey();
+    let payer = fees.payer();
+
+    let result = fees
+        .harness
+        .process(&[subscribe_ix(&payer, &consumer, 1)], &[])
+        .await;
+    assert_chain_error(result, ChainError::SubscriptionsUnavailable);
+}
+
+#[tokio::test]
+async fn withdraws_lamport_fees() {
+    let mut fees = fees().await;
+    fees.enable(lamport_schedule(), vec![]).await;
+    let ix = with_fees(fees.read_ix(2), &fees.payer(), &[system_program()]);
+    fees.harness.process(&[ix], &[]).await.unwrap();
+    let owner = fees.harness.owner();
+    let collected = fees.collected().await;
+
+    // the config keeps its rent exemption
+    let result = fees
+        .harness
+        .process(
+            &[withdraw_fees_ix(
+                &owner.pubkey(),
+                &owner.pubkey(),
+                READ_FEE + 1,
+            )],
+            &[&owner],
+        )
+        .await;
+    assert_chain_error(result, ChainError::InsufficientFees);
+
+    let balance = fees
+        .harness
+        .context
+        .banks_client
+        .get_balance(owner.pubkey())
+        .await
+        .unwrap();
+    fees.harness
+        .process(
+            &[withdraw_fees_ix(&owner.pubkey(), &owner.pubkey(), READ_FEE)],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(fees.collected().await, collected - READ_FEE);
+    assert_eq!(
+        fees.harness
+            .context
+            .banks_client
+            .get_balance(owner.pubkey())
+            .await
+            .unwrap(),
+        balance + READ_FEE
+    );
+}
+
+#[tokio::test]
+async fn fails_to_withdraw_fees_by_unauthorized_user() {
+    let mut fees = fees().await;
+    let intruder = fees.harness.funded_keypair().await;
+
+    let result = fees
+        .harness
+        .process(
+            &[withdraw_fees_ix(&intruder.pubkey(), &intruder.pubkey(), 1)],
+            &[&intruder],
+        )
+        .await;
+    assert_chain_error(result, ChainError::OnlyOwnerViolation);
+}
@@ -0,0 +1,309 @@
+#![cfg(any(feature = "test-bpf", feature = "test-sbf"))]
+
+mod common;
+
+use anchor_lang::prelude::*;
+use chain::errors::ChainError;
+use chain::state::{FcdHistory, FeedMetadata, FirstClassData, Observation, MAX_HISTORY_LENGTH};
+use common::*;
+use solana_program_test::ProgramTest;
+use solana_sdk::signature::{Keypair, Signer};
+
+const CAPACITY: u16 = 3;
+
+fn observation(n: u128, timestamp: u32, cumulative: u128) -> Observation {
+    Observation {
+        value: value(n),
+        timestamp,
+        cumulative,
+    }
+}
+
+fn add_fcd(program_test: &mut ProgramTest, key: &str, has_history: bool) -> Pubkey {
+    let fcd = fcd_address(key).0;
+    add_program_account(
+        program_test,
+        fcd,
+        &FirstClassData {
+            key: key.to_string(),
+            value: value(1),
+            timestamp: 1651640000,
+            metadata: FeedMetadata::umbrella(key),
+            has_history,
+            cumulative: 0,
+            aggregated: false,
+            confidence: [0u8; 32],
+            has_confidence: false,
+            registry_page: None,
+        },
+        FirstClassData::SPACE,
+    );
+    fcd
+}
+
+/// Adds `ETH-USD` with a history holding its current value, the way
+/// `initialize_fcd_history` leaves it.
+fn add_fcd_with_history(program_test: &mut ProgramTest) -> Pubkey {
+    let fcd = add_fcd(program_test, "ETH-USD", true);
+    let mut history = FcdHistory {
+        fcd,
+        capacity: CAPACITY,
+        ..FcdHistory::default()
+    };
+    history.push(observation(1, 1651640000, 0));
+    add_program_account(
+        program_test,
+        fcd_history_address(&fcd),
+        &history,
+        FcdHistory::space(CAPACITY),
+    );
+    fcd
+}
+
+async fn harness_with_history() -> (Harness, Pubkey) {
+    let mut fcd = Pubkey::default();
+    let harness =
+        Harness::initialized_with(|program_test| fcd = add_fcd_with_history(program_test)).await;
+    (harness, fcd)
+}
+
+async fn update(
+    harness: &mut Harness,
+    fcd: Pubkey,
+    n: u128,
+    timestamp: u32,
+) -> std::result::Result<(), solana_sdk::transport::TransportError> {
+    let owner = harness.owner();
+    let ix = update_fcd_ix(&owner.pubkey(), fcd, "ETH-USD", value(n), timestamp);
+    harness
+        .process(&[with_history(ix, fcd_history_address(&fcd))], &[&owner])
+        .await
+}
+
+#[tokio::test]
+async fn initializes_a_history_with_the_current_value() {
+    let mut fcd = Pubkey::default();
+    let mut harness =
+        Harness::initialized_with(|program_test| fcd = add_fcd(program_test, "ETH-USD", false))
+            .await;
+    let owner = harness.owner();
+
+    harness
+        .process(
+            &[initialize_fcd_history_ix(
+                &owner.pubkey(),
+                fcd,
+                "ETH-USD",
+                24,
+            )],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+
+    let history: FcdHistory = harness.account(fcd_history_address(&fcd)).await;
+    assert_eq!(history.fcd, fcd);
+    assert_eq!(history.capacity, 24);
+    assert_eq!(history.observations, vec![observation(1, 1651640000, 0)]);
+    let fcd: FirstClassData = harness.account(fcd).await;
+    assert!(fcd.has_history);
+}
+
+#[tokio::test]
+async fn fails_to_initialize_a_history_with_an_invalid_capacity() {
+    let mut fcd = Pubkey::default();
+    let mut harness =
+        Harness::initialized_with(|program_test| fcd = add_fcd(program_test, "ETH-USD", false))
+            .await;
+    let owner = harness.owner();
+
+    for capacity in [0, MAX_HISTORY_LENGTH + 1] {
+        let result = harness
+            .process(
+                &[initialize_fcd_history_ix(
+                    &owner.pubkey(),
+                    fcd,
+                    "ETH-USD",
+                    capacity,
+                )],
+                &[&owner],
+            )
+            .await;
+
+        assert_chain_error(result, ChainError::InvalidHistoryCapacity);
+    }
+}
+
+#[tokio::test]
+async fn fails_to_initialize_a_history_by_unauthorized_user() {
+    let mut fcd = Pubkey::default();
+    let mut harness =
+        Harness::initialized_with(|program_test| fcd = add_fcd(program_test, "ETH-USD", false))
+            .await;
+    let intruder = harness.funded_keypair().await;
+
+    let result = harness
+        .process(
+            &[initialize_fcd_history_ix(
+                &intruder.pubkey(),
+                fcd,
+                "ETH-USD",
+                CAPACITY,
+            )],
+            &[&intruder],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::OnlyOwnerViolation);
+}
+
+#[tokio::test]
+async fn appends_every_update_and_keeps_the_last_values() {
+    let (mut harness, fcd) = harness_with_history().await;
+
+    update(&mut harness, fcd, 2, 1651640100).await.unwrap();
+    let history: FcdHistory = harness.account(fcd_history_address(&fcd)).await;
+    assert_eq!(
+        history.ordered().copied().collect::<Vec<_>>(),
+        vec![
+            observation(1, 1651640000, 0),
+            observation(2, 1651640100, 100),
+        ]
+    );
+
+    update(&mut harness, fcd, 3, 1651640200).await.unwrap();
+    update(&mut harness, fcd, 4, 1651640300).await.unwrap();
+    let history: FcdHistory = harness.account(fcd_history_address(&fcd)).await;
+    assert_eq!(
+        history.ordered().copied().collect::<Vec<_>>(),
+        vec![
+            observation(2, 1651640100, 100),
+            observation(3, 1651640200, 300),
+            observation(4, 1651640300, 600),
+        ]
+    );
+    assert_eq!(history.latest(), Some(observation(4, 1651640300, 600)));
+    let fcd: FirstClassData = harness.account(fcd).await;
+    assert_eq!(fcd.cumulative, 600);
+}
+
+#[tokio::test]
+async fn fails_to_update_without_the_history() {
+    let (mut harness, fcd) = harness_with_history().await;
+    let owner = harness.owner();
+
+    let result = harness
+        .process(
+            &[update_fcd_ix(
+                &owner.pubkey(),
+                fcd,
+                "ETH-USD",
+                value(2),
+                1651640100,
+            )],
+            &[&owner],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::MissingFCDHistory);
+}
+
+#[tokio::test]
+async fn fails_to_update_with_the_history_of_another_feed() {
+    let mut fcd = Pubkey::default();
+    let mut other = Pubkey::default();
+    let mut harness = Harness::initialized_with(|program_test| {
+        fcd = add_fcd_with_history(program_test);
+        other = add_fcd(program_test, "BTC-USD", true);
+    })
+    .await;
+    let owner = harness.owner();
+
+    let ix = update_fcd_ix(&owner.pubkey(), other, "BTC-USD", value(2), 1651640100);
+    let result = harness
+        .process(&[with_history(ix, fcd_history_address(&fcd))], &[&owner])
+        .await;
+
+    assert_chain_error(result, ChainError::MissingFCDHistory);
+}
+
+#[tokio::test]
+async fn updates_feeds_without_a_history_as_before() {
+    let mut fcd = Pubkey::default();
+    let mut harness =
+        Harness::initialized_with(|program_test| fcd = add_fcd(program_test, "ETH-USD", false))
+            .await;
+
+    update(&mut harness, fcd, 2, 1651640100).await.unwrap();
+
+    let fcd: FirstClassData = harness.account(fcd).await;
+    assert_eq!(fcd.value, value(2));
+}
+
+#[tokio::test]
+async fn gets_observations_only_after_the_first_one() {
+    let (mut harness, fcd) = harness_with_history().await;
+    harness
+        .process(&[get_fcd_observation_ix(&fcd, 1651640000)], &[])
+        .await
+        .unwrap();
+    let result = harness
+        .process(&[get_fcd_observation_ix(&fcd, 1651639999)], &[])
+        .await;
+
+    assert_chain_error(result, ChainError::NoObservation);
+}
+
+#[tokio::test]
+async fn closes_the_history_with_the_feed() {
+    let (mut harness, fcd) = harness_with_history().await;
+    let owner = harness.owner();
+    let recipient = Keypair::new();
+    let history = fcd_history_address(&fcd);
+    let fcd_rent = harness.context.banks_client.get_balance(fcd).await.unwrap();
+    let history_rent = harness
+        .context
+        .banks_client
+        .get_balance(history)
+        .await
+        .unwrap();
+
+    harness
+        .process(
+            &[with_history(
+                close_fcd_ix(&owner.pubkey(), fcd, "ETH-USD", &recipient.pubkey()),
+                history,
+            )],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+
+    assert!(!harness.exists(history).await);
+    let received = harness
+        .context
+        .banks_client
+        .get_balance(recipient.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(received, fcd_rent + history_rent);
+}
+
+#[test]
+fn finds_the_observation_at_or_before_a_time() {
+    let mut history = FcdHistory {
+        capacity: CAPACITY,
+        ..FcdHistory::default()
+    };
+    assert_eq!(history.at_or_before(u32::MAX), None);
+
+    for (n, timestamp) in [(1, 100), (2, 200), (3, 300), (4, 400)] {
+        history.push(observation(n, timestamp, 0));
+    }
+
+    assert_eq!(history.at_or_before(199), None);
+    assert_eq!(history.at_or_before(200), Some(observation(2, 200, 0)));
+    assert_eq!(history.at_or_before(299), Some(observation(2, 200, 0)));
+    assert_eq!(history.at_or_before(300), Some(observation(3, 300, 0)));
+    assert_eq!(history.at_or_before(u32::MAX), Some(observation(4, 400, 0)));
+}
@@ -0,0 +1,202 @@
+use std::collections::BTreeMap;
+
+use chain::merkle::*;
+use proptest::prelude::*;
+use proptest::sample::Index;
+use sorted_merkle_tree::{Proof, SortedMerkleTree};
+
+fn on_chain_scheme(scheme: sorted_merkle_tree::HashScheme) -> HashScheme {
+    HashScheme::from_u8(scheme as u8).unwrap()
+}
+
+fn data() -> impl Strategy<Value = BTreeMap<String, [u8; 32]>> {
+    prop::collection::btree_map("[A-Z0-9]{1,10}-[A-Z]{3}", any::<[u8; 32]>(), 1..64)
+}
+
+/// A tree, the proof of one of its keys and the squashed root it verifies against.
+fn proven() -> impl Strategy<Value = (Proof, [u8; 32])> {
+    (data(), any::<Index>(), any::<u32>()).prop_map(|(data, index, timestamp)| {
+        let tree = SortedMerkleTree::new(&data).unwrap();
+        let key = index.get(&data.keys().collect::<Vec<_>>()).as_str();
+        (tree.proof(key).unwrap(), tree.squashed_root(timestamp))
+    })
+}
+
+fn flip(hash: &mut [u8; 32], bit: usize) {
+    hash[bit / 8] ^= 1 << (bit % 8);
+}
+
+proptest! {
+    #[test]
+    fn valid_proofs_verify((proof, root) in proven()) {
+        prop_assert!(verify_squashed_root(root, &proof.proof, proof.leaf()));
+        prop_assert_eq!(extract_root(compute_root(&proof.proof, proof.leaf())), extract_root(root));
+    }
+
+    #[test]
+    fn mutated_proof_elements_fail((proof, root) in proven(), element in any::<Index>(), bit in 0..256usize) {
+        prop_assume!(!proof.proof.is_empty());
+        let mut mutated = proof.proof.clone();
+        flip(&mut mutated[element.index(proof.proof.len())], bit);
+
+        prop_assert!(!verify_squashed_root(root, &mutated, proof.leaf()));
+    }
+
+    #[test]
+    fn mutated_keys_and_values_fail((proof, root) in proven(), bit in 0..256usize) {
+        let mut key = proof.key;
+        flip(&mut key, bit);
+        prop_assert!(!verify_squashed_root(root, &proof.proof, hash_leaf(key, proof.value)));
+
+        let mut value = proof.value;
+        flip(&mut value, bit);
+        prop_assert!(!verify_squashed_root(root, &proof.proof, hash_leaf(proof.key, value)));
+    }
+
+    #[test]
+    fn mutated_roots_fail_outside_the_timestamp((proof, root) in proven(), bit in 0..256usize) {
+        let mut mutated = root;
+        flip(&mut mutated, bit);
+
+        let in_timestamp = bit / 8 >= 32 - TIMESTAMP_BYTES;
+        prop_assert_eq!(verify_squashed_root(mutated, &proof.proof, proof.leaf()), in_timestamp);
+    }
+
+    #[test]
+    fn prefixed_proofs_verify_only_with_their_scheme(data in data(), index in any::<Index>(), timestamp in any::<u32>()) {
+        let tree = SortedMerkleTree::with_scheme(&data, sorted_merkle_tree::HashScheme::Prefixed).unwrap();
+        let key = index.get(&data.keys().collect::<Vec<_>>()).as_str();
+        let proof = tree.proof(key).unwrap();
+        let root = tree.squashed_root(timestamp);
+        let scheme = on_chain_scheme(proof.scheme);
+
+        prop_assert_eq!(scheme, HashScheme::Prefixed);
+        prop_assert!(scheme.verify_squashed_root(root, &proof.proof, scheme.hash_leaf(proof.key, proof.value)));
+        prop_assert!(!verify_squashed_root(root, &proof.proof, hash_leaf(proof.key, proof.value)));
+    }
+
+    #[test]
+    fn message_leaves_match_the_reference(message in prop::collection::vec(any::<u8>(), 0..512)) {
+        prop_assert_eq!(hash_message(&message), sorted_merkle_tree::hash_message(&message));
+    }
+
+    #[test]
+    fn messages_verify_in_both_schemes(
+        message in prop::collection::vec(any::<u8>(), 0..512),
+        proof in prop::collection::vec(any::<[u8; 32]>(), 0..16),
+        bit in 0..8usize
+    ) {
+        for scheme in [HashScheme::Legacy, HashScheme::Prefixed] {
+            let root = scheme.compute_root(&proof, hash_message(&message));
+            prop_assert!(scheme.verify_squashed_root(root, &proof, hash_message(&message)));
+
+            let mut extended = message.clone();
+            extended.push(0);
+            prop_assert!(!scheme.verify_squashed_root(root, &proof, hash_message(&extended)));
+
+            let mut flipped = message.clone();
+            if let Some(byte) = flipped.first_mut() {
+                *byte ^= 1 << bit;
+                prop_assert!(!scheme.verify_squashed_root(root, &proof, hash_message(&flipped)));
+            }
+        }
+    }
+
+    // a 64 byte message is what a legacy leaf or inner node hashes
+    #[test]
+    fn messages_differ_from_leaves_and_nodes(key in any::<[u8; 32]>(), value in any::<[u8; 32]>()) {
+        let message = [key, value].concat();
+        for scheme in [HashScheme::Legacy, HashScheme::Prefixed] {
+            prop_assert_ne!(hash_message(&message), scheme.hash_leaf(key, value));
+            prop_assert_ne!(hash_message(&message), scheme.hash_node(key, value));
+        }
+    }
+
+    #[test]
+    fn compare_hashes_is_lexicographic_order(a in any::<[u8; 32]>(), b in any::<[u8; 32]>()) {
+        prop_assert_eq!(compare_hashes(&a, &b), a <= b);
+        prop_assert!(compare_hashes(&a, &b) || compare_hashes(&b, &a));
+        prop_assert!(compare_hashes(&a, &a));
+    }
+
+    #[test]
+    fn extract_root_only_clears_the_timestamp(root in any::<[u8; 32]>()) {
+        let extracted = extract_root(root);
+        prop_assert_eq!(&extracted[..32 - TIMESTAMP_BYTES], &root[..32 - TIMESTAMP_BYTES]);
+        prop_assert!(extracted[32 - TIMESTAMP_BYTES..].iter().all(|byte| *byte == 0));
+        prop_assert_eq!(extract_root(extracted), extracted);
+    }
+
+    #[test]
+    fn arbitrary_proofs_match_the_reference(proof in prop::collection::vec(any::<[u8; 32]>(), 0..64), leaf in any::<[u8; 32]>(), bit in 0..256usize) {
+        let root = compute_root(&proof, leaf);
+        prop_assert_eq!(root, sorted_merkle_tree::compute_root(&proof, leaf));
+        prop_assert!(verify_squashed_root(root, &proof, leaf));
+
+        let mut flipped = leaf;
+        flipped[bit / 8] ^= 1 << (bit % 8);
+        prop_assert_ne!(compute_root(&proof, flipped), root);
+    }
+}
+
+#[test]
+fn empty_proof_verifies_the_leaf_itself() {
+    let leaf = hash_leaf([1; 32], [2; 32]);
+
+    assert_eq!(compute_root(&[], leaf), leaf);
+    assert!(verify_squashed_root(leaf, &[], leaf));
+    assert!(!verify_squashed_root([0; 32], &[], leaf));
+}
+
+#[test]
+fn huge_proofs_are_deterministic() {
+    let proof: Vec<[u8; 32]> = (0..100_000u32)
+        .map(|i| {
+            hash_leaf([(i % 251) as u8; 32], {
+                let mut value = [0; 32];
+                value[28..].copy_from_slice(&i.to_be_bytes());
+                value
+            })
+        })
+        .collect();
+    let leaf = hash_leaf([0; 32], [0; 32]);
+
+    let root = compute_root(&proof, leaf);
+    assert_eq!(root, compute_root(&proof, leaf));
+    assert_eq!(root, sorted_merkle_tree::compute_root(&proof, leaf));
+    assert!(!verify_squashed_root(root, &proof[1..], leaf));
+}
+
+// The two children of an inner node, taken as key and value, hash to the node itself.
+#[test]
+fn inner_nodes_pass_as_leaves_only_in_the_legacy_scheme() {
+    for scheme in [HashScheme::Legacy, HashScheme::Prefixed] {
+        let leaves: Vec<[u8; 32]> = (0..4u8)
+            .map(|i| scheme.hash_leaf([i; 32], [i + 100; 32]))
+            .collect();
+        let left = scheme.hash_node(leaves[0], leaves[1]);
+        let right = scheme.hash_node(leaves[2], leaves[3]);
+        let root = scheme.hash_node(left, right);
+
+        assert!(scheme.verify_squashed_root(root, &[leaves[1], right], leaves[0]));
+
+        let (key, value) = if compare_hashes(&leaves[0], &leaves[1]) {
+            (leaves[0], leaves[1])
+        } else {
+            (leaves[1], leaves[0])
+        };
+        let forged = scheme.verify_squashed_root(root, &[right], scheme.hash_leaf(key, value));
+        assert_eq!(forged, scheme == HashScheme::Legacy);
+    }
+}
+
+#[test]
+fn hash_schemes_round_trip_through_block_bytes() {
+    assert_eq!(HashScheme::from_u8(0), Some(HashScheme::Legacy));
+    assert_eq!(HashScheme::from_u8(1), Some(HashScheme::Prefixed));
+    assert_eq!(HashScheme::from_u8(2), None);
+    assert_eq!(
+        on_chain_scheme(sorted_merkle_tree::HashScheme::default()),
+        HashScheme::Legacy
+    );
+}
@@ -0,0 +1,255 @@
+#![cfg(any(feature = "test-bpf", feature = "test-sbf"))]
+
+mod common;
+
+use anchor_lang::prelude::*;
+use chain::errors::ChainError;
+use chain::state::{FeedMetadata, FeedRegistry, FirstClassData, ValueKind};
+use common::*;
+use solana_program_test::ProgramTest;
+use solana_sdk::signature::Signer;
+
+fn volume(description: &str) -> FeedMetadata {
+    FeedMetadata {
+        decimals: 6,
+        kind: ValueKind::Volume as u8,
+        description: description.to_string(),
+    }
+}
+
+/// Adds a feed initialized before metadata existed.
+fn add_legacy_fcd(program_test: &mut ProgramTest, key: &str) -> Pubkey {
+    let fcd = fcd_address(key).0;
+    add_program_account(
+        program_test,
+        fcd,
+        &FirstClassData {
+            key: key.to_string(),
+            value: value(1),
+            timestamp: 1651640000,
+            metadata: FeedMetadata::default(),
+            has_history: false,
+            cumulative: 0,
+            aggregated: false,
+            confidence: [0u8; 32],
+            has_confidence: false,
+            registry_page: None,
+        },
+        FirstClassData::SPACE,
+    );
+    fcd
+}
+
+/// Harness with a legacy `ETH-VOL` feed listed on registry page 0.
+async fn registered_legacy_fcd() -> (Harness, Pubkey) {
+    let mut fcd = Pubkey::default();
+    let mut harness =
+        Harness::initialized_with(|program_test| fcd = add_legacy_fcd(program_test, "ETH-VOL"))
+            .await;
+    let owner = harness.owner();
+    harness
+        .process(
+            &[register_fcd_ix(&owner.pubkey(), fcd, "ETH-VOL", 0)],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+    (harness, fcd)
+}
+
+#[tokio::test]
+async fn initializes_first_class_data_with_metadata() {
+    let mut harness = Harness::initialized().await;
+    let owner = harness.owner();
+    let metadata = volume("24h ETH volume in USD");
+
+    harness
+        .process(
+            &[initialize_fcd_with_metadata_ix(
+                &owner.pubkey(),
+                "ETH-VOL",
+                value(1),
+                1651640000,
+                &metadata,
+            )],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+
+    let fcd: FirstClassData = harness.account(fcd_address("ETH-VOL").0).await;
+    assert_eq!(fcd.metadata, metadata);
+    let registry: FeedRegistry = harness.account(feed_registry_address(0)).await;
+    assert_eq!(registry.entries[0].metadata, metadata);
+}
+
+#[tokio::test]
+async fn initializes_first_class_data_with_umbrella_metadata_by_default() {
+    let mut harness = Harness::initialized().await;
+    let owner = harness.owner();
+
+    harness
+        .process(
+            &[
+                initialize_fcd_ix(&owner.pubkey(), "ETH-USD", value(1), 1651640000),
+                initialize_fcd_ix(&owner.pubkey(), "FIXED_ROOT", [1; 32], 1651640000),
+            ],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+
+    for key in ["ETH-USD", "FIXED_ROOT"] {
+        let fcd: FirstClassData = harness.account(fcd_address(key).0).await;
+        assert_eq!(fcd.metadata, FeedMetadata::umbrella(key));
+    }
+    let price: FirstClassData = harness.account(fcd_address("ETH-USD").0).await;
+    assert_eq!(price.metadata.decimals, 18);
+    assert_eq!(price.metadata.kind, ValueKind::Price as u8);
+}
+
+#[tokio::test]
+async fn reads_legacy_first_class_data_with_umbrella_metadata() {
+    let (mut harness, fcd) = registered_legacy_fcd().await;
+
+    let fcd: FirstClassData = harness.account(fcd).await;
+    assert_eq!(fcd.metadata.kind, ValueKind::Unspecified as u8);
+    assert_eq!(fcd.resolved_metadata(), FeedMetadata::umbrella("ETH-VOL"));
+    let registry: FeedRegistry = harness.account(feed_registry_address(0)).await;
+    assert_eq!(
+        registry.entries[0].metadata,
+        FeedMetadata::umbrella("ETH-VOL")
+    );
+}
+
+#[tokio::test]
+async fn sets_metadata_of_the_fcd_and_its_registry_entry() {
+    let (mut harness, fcd) = registered_legacy_fcd().await;
+    let owner = harness.owner();
+    let metadata = volume("24h ETH volume in USD");
+
+    harness
+        .process(
+            &[set_fcd_metadata_ix(
+                &owner.pubkey(),
+                fcd,
+                "ETH-VOL",
+                &metadata,
+            )],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+
+    let fcd: FirstClassData = harness.account(fcd).await;
+    assert_eq!(fcd.metadata, metadata);
+    assert_eq!(fcd.value, value(1));
+    let registry: FeedRegistry = harness.account(feed_registry_address(0)).await;
+    assert_eq!(registry.entries[0].metadata, metadata);
+}
+
+#[tokio::test]
+async fn fails_to_set_metadata_from_an_unauthorized_account() {
+    let (mut harness, fcd) = registered_legacy_fcd().await;
+    let intruder = harness.funded_keypair().await;
+
+    let result = harness
+        .process(
+            &[set_fcd_metadata_ix(
+                &intruder.pubkey(),
+                fcd,
+                "ETH-VOL",
+                &volume(""),
+            )],
+            &[&intruder],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::OnlyOwnerViolation);
+}
+
+#[tokio::test]
+async fn fails_to_set_metadata_with_the_wrong_key() {
+    let (mut harness, fcd) = registered_legacy_fcd().await;
+    let owner = harness.owner();
+
+    let result = harness
+        .process(
+            &[set_fcd_metadata_ix(
+                &owner.pubkey(),
+                fcd,
+                "BTC-VOL",
+                &volume(""),
+            )],
+            &[&owner],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::WrongFCDKeyForAccount);
+}
+
+#[tokio::test]
+async fn fails_to_set_an_unknown_value_kind() {
+    let (mut harness, fcd) = registered_legacy_fcd().await;
+    let owner = harness.owner();
+
+    for kind in [ValueKind::Unspecified as u8, 4] {
+        let metadata = FeedMetadata { kind, ..volume("") };
+        let result = harness
+            .process(
+                &[set_fcd_metadata_ix(
+                    &owner.pubkey(),
+                    fcd,
+                    "ETH-VOL",
+                    &metadata,
+                )],
+                &[&owner],
+            )
+            .await;
+
+        assert_chain_error(result, ChainError::UnknownValueKind);
+    }
+}
+
+#[tokio::test]
+async fn fails_to_set_a_description_over_64_bytes() {
+    let (mut harness, fcd) = registered_legacy_fcd().await;
+    let owner = harness.owner();
+
+    let result = harness
+        .process(
+            &[set_fcd_metadata_ix(
+                &owner.pubkey(),
+                fcd,
+                "ETH-VOL",
+                &volume(&"x".repeat(65)),
+            )],
+            &[&owner],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::FeedDescriptionTooLong);
+}
+
+#[tokio::test]
+async fn fails_to_set_metadata_of_an_unregistered_feed() {
+    let mut fcd = Pubkey::default();
+    let mut harness =
+        Harness::initialized_with(|program_test| fcd = add_legacy_fcd(program_test, "ETH-VOL"))
+            .await;
+    let owner = harness.owner();
+
+    let result = harness
+        .process(
+            &[set_fcd_metadata_ix(
+                &owner.pubkey(),
+                fcd,
+                "ETH-VOL",
+                &volume(""),
+            )],
+            &[&owner],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::FeedNotRegistered);
+}
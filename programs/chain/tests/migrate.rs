@@ -0,0 +1,118 @@
+#![cfg(any(feature = "test-bpf", feature = "test-sbf"))]
+
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use chain::errors::ChainError;
+use chain::state::{Authority, Status, ACCOUNT_VERSION, PAUSE_SUBMIT};
+use common::*;
+use solana_program_test::ProgramTest;
+use solana_sdk::account::Account;
+use solana_sdk::signature::{Keypair, Signer};
+
+/// Adds an account with the layout written by version 0.2.0 of `initialize`.
+fn add_legacy_account(program_test: &mut ProgramTest, address: Pubkey, data: Vec<u8>) {
+    program_test.add_account(
+        address,
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: chain::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+}
+
+/// Starts a bank with the `Authority` and `Status` accounts of a 0.2.0 deployment.
+async fn legacy_deployment(owner: Keypair) -> Harness {
+    let mut program_test = program_test();
+
+    // 8 discriminator + 32 owner + 1 unused
+    let mut authority = Authority::discriminator().to_vec();
+    authority.extend_from_slice(owner.pubkey().as_ref());
+    authority.push(0);
+    add_legacy_account(&mut program_test, authority_address(), authority);
+
+    // 8 discriminator + 4 padding + 4 last_id + 4 last_data_timestamp + 4 next_block_id + 1 unused
+    let mut status = Status::discriminator().to_vec();
+    for field in [PADDING, 7, 1651641200, 8] {
+        status.extend_from_slice(&field.to_le_bytes());
+    }
+    status.push(0);
+    add_legacy_account(&mut program_test, status_address(), status);
+
+    Harness::start(program_test, owner).await
+}
+
+#[tokio::test]
+async fn migrates_legacy_accounts_in_place() {
+    let owner = Keypair::new();
+    let mut harness = legacy_deployment(clone_keypair(&owner)).await;
+    assert!(harness.submit(8, [1; 32], 1651642200).await.is_err());
+
+    harness
+        .process(&[migrate_ix(&owner.pubkey())], &[&owner])
+        .await
+        .unwrap();
+
+    let authority: Authority = harness.account(authority_address()).await;
+    assert_eq!(authority.owner, owner.pubkey());
+    assert_eq!(authority.guardian, Pubkey::default());
+    assert_eq!(authority.version, ACCOUNT_VERSION);
+
+    let status: Status = harness.account(status_address()).await;
+    assert_eq!(status.padding, PADDING);
+    assert_eq!(status.last_id, 7);
+    assert_eq!(status.last_data_timestamp, 1651641200);
+    assert_eq!(status.next_block_id, 8);
+    assert_eq!(status.max_proof_length(), 32);
+    assert_eq!(status.paused, 0);
+    assert_eq!(status.version, ACCOUNT_VERSION);
+
+    for (address, space) in [
+        (authority_address(), Authority::SPACE),
+        (status_address(), Status::SPACE),
+    ] {
+        let account = harness
+            .context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(account.data.len(), space);
+        assert!(account.lamports >= Rent::default().minimum_balance(space));
+    }
+
+    harness.submit(8, [1; 32], 1651642200).await.unwrap();
+    harness
+        .process(&[pause_ix(&owner.pubkey(), PAUSE_SUBMIT)], &[&owner])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn fails_to_migrate_by_unauthorized_user() {
+    let mut harness = legacy_deployment(Keypair::new()).await;
+    let intruder = harness.funded_keypair().await;
+
+    let result = harness
+        .process(&[migrate_ix(&intruder.pubkey())], &[&intruder])
+        .await;
+
+    assert_chain_error(result, ChainError::OnlyOwnerViolation);
+}
+
+#[tokio::test]
+async fn fails_to_migrate_current_accounts() {
+    let mut harness = Harness::initialized().await;
+    let owner = harness.owner();
+
+    let result = harness
+        .process(&[migrate_ix(&owner.pubkey())], &[&owner])
+        .await;
+
+    assert_chain_error(result, ChainError::AlreadyMigrated);
+}
@@ -0,0 +1,192 @@
+#![cfg(any(feature = "test-bpf", feature = "test-sbf"))]
+
+mod common;
+
+use chain::errors::ChainError;
+use chain::state::{Authority, Status, PAUSE_ALL, PAUSE_FCD_UPDATE, PAUSE_SUBMIT, PAUSE_VERIFY};
+use common::*;
+use solana_sdk::signature::{Keypair, Signer};
+
+async fn with_guardian() -> (Harness, Keypair) {
+    let mut harness = Harness::initialized().await;
+    let owner = harness.owner();
+    let guardian = Keypair::new();
+    harness
+        .process(
+            &[set_guardian_ix(&owner.pubkey(), guardian.pubkey())],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+    (harness, guardian)
+}
+
+#[tokio::test]
+async fn sets_guardian() {
+    let (mut harness, guardian) = with_guardian().await;
+
+    let authority: Authority = harness.account(authority_address()).await;
+    assert_eq!(authority.guardian, guardian.pubkey());
+}
+
+#[tokio::test]
+async fn fails_to_set_guardian_by_unauthorized_user() {
+    let mut harness = Harness::initialized().await;
+    let intruder = Keypair::new();
+
+    let result = harness
+        .process(
+            &[set_guardian_ix(&intruder.pubkey(), intruder.pubkey())],
+            &[&intruder],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::OnlyOwnerViolation);
+}
+
+#[tokio::test]
+async fn pauses_and_unpauses_flags_independently() {
+    let mut harness = Harness::initialized().await;
+    let owner = harness.owner();
+
+    harness
+        .process(
+            &[pause_ix(&owner.pubkey(), PAUSE_SUBMIT | PAUSE_VERIFY)],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+    let status: Status = harness.account(status_address()).await;
+    assert_eq!(status.paused, PAUSE_SUBMIT | PAUSE_VERIFY);
+
+    harness
+        .process(&[unpause_ix(&owner.pubkey(), PAUSE_SUBMIT)], &[&owner])
+        .await
+        .unwrap();
+    let status: Status = harness.account(status_address()).await;
+    assert_eq!(status.paused, PAUSE_VERIFY);
+}
+
+#[tokio::test]
+async fn guardian_pauses_and_unpauses() {
+    let (mut harness, guardian) = with_guardian().await;
+
+    harness
+        .process(&[pause_ix(&guardian.pubkey(), PAUSE_ALL)], &[&guardian])
+        .await
+        .unwrap();
+    let status: Status = harness.account(status_address()).await;
+    assert_eq!(status.paused, PAUSE_ALL);
+
+    harness
+        .process(&[unpause_ix(&guardian.pubkey(), PAUSE_ALL)], &[&guardian])
+        .await
+        .unwrap();
+    let status: Status = harness.account(status_address()).await;
+    assert_eq!(status.paused, 0);
+}
+
+#[tokio::test]
+async fn fails_to_pause_by_unauthorized_user() {
+    let (mut harness, _guardian) = with_guardian().await;
+    let intruder = Keypair::new();
+
+    let result = harness
+        .process(&[pause_ix(&intruder.pubkey(), PAUSE_ALL)], &[&intruder])
+        .await;
+    assert_chain_error(result, ChainError::NotOwnerOrGuardian);
+
+    let result = harness
+        .process(&[unpause_ix(&intruder.pubkey(), PAUSE_ALL)], &[&intruder])
+        .await;
+    assert_chain_error(result, ChainError::NotOwnerOrGuardian);
+}
+
+#[tokio::test]
+async fn fails_to_pause_unknown_flags() {
+    let mut harness = Harness::initialized().await;
+    let owner = harness.owner();
+
+    let result = harness
+        .process(&[pause_ix(&owner.pubkey(), 1 << 7)], &[&owner])
+        .await;
+
+    assert_chain_error(result, ChainError::UnknownPauseFlags);
+}
+
+#[tokio::test]
+async fn fails_to_submit_while_paused() {
+    let mut harness = Harness::initialized().await;
+    let owner = harness.owner();
+    harness
+        .process(&[pause_ix(&owner.pubkey(), PAUSE_SUBMIT)], &[&owner])
+        .await
+        .unwrap();
+
+    let result = harness.submit(1, [1; 32], 1651641200).await;
+    assert_chain_error(result, ChainError::Paused);
+
+    harness
+        .process(&[unpause_ix(&owner.pubkey(), PAUSE_SUBMIT)], &[&owner])
+        .await
+        .unwrap();
+    harness.submit(1, [1; 32], 1651641200).await.unwrap();
+}
+
+#[tokio::test]
+async fn fails_to_update_first_class_data_while_paused() {
+    let mut harness = Harness::initialized().await;
+    let owner = harness.owner();
+    let (fcd, _) = fcd_address("ETH-USD");
+    harness
+        .process(
+            &[
+                initialize_fcd_ix(&owner.pubkey(), "ETH-USD", value(1), 1651641200),
+                pause_ix(&owner.pubkey(), PAUSE_FCD_UPDATE),
+            ],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+
+    let result = harness
+        .process(
+            &[update_fcd_ix(
+                &owner.pubkey(),
+                fcd,
+                "ETH-USD",
+                value(2),
+                1651642200,
+            )],
+            &[&owner],
+        )
+        .await;
+    assert_chain_error(result, ChainError::Paused);
+
+    harness.submit(1, [1; 32], 1651641200).await.unwrap();
+}
+
+#[tokio::test]
+async fn fails_to_verify_while_paused() {
+    let mut harness = Harness::initialized().await;
+    let owner = harness.owner();
+    harness.submit(1, [1; 32], 1651641200).await.unwrap();
+    harness
+        .process(&[pause_ix(&owner.pubkey(), PAUSE_VERIFY)], &[&owner])
+        .await
+        .unwrap();
+    let verify_result = Keypair::new();
+    let payer = harness.context.payer.pubkey();
+
+    let result = harness
+        .process(
+            &[
+                initialize_verify_result_ix(&payer, &verify_result.pubkey()),
+                verify_ix(&verify_result.pubkey(), 1, vec![], [1; 32], value(1)),
+            ],
+            &[&verify_result],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::Paused);
+}
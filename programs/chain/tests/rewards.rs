@@ -0,0 +1,454 @@
+#![cfg(any(feature = "test-bpf", feature = "test-sbf"))]
+
+mod common;
+
+use anchor_lang::prelude::*;
+use chain::errors::ChainError;
+use chain::state::{
+    FcdRound, FeeConfig, FeedMetadata, FirstClassData, RewardEntry, RewardVault, Status,
+    ValidatorSet,
+};
+use common::*;
+use solana_program_test::ProgramTest;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_instruction;
+
+const START: u32 = 1651640000;
+
+struct Rewards {
+    harness: Harness,
+    eth: Pubkey,
+    validators: Vec<Keypair>,
+}
+
+fn add_fcd(program_test: &mut ProgramTest, key: &str, aggregated: bool) -> Pubkey {
+    let fcd = fcd_address(key).0;
+    add_program_account(
+        program_test,
+        fcd,
+        &FirstClassData {
+            key: key.to_string(),
+            value: value(1),
+            timestamp: START,
+            metadata: FeedMetadata::umbrella(key),
+            has_history: false,
+            cumulative: 0,
+            aggregated,
+            confidence: [0u8; 32],
+            has_confidence: false,
+            registry_page: None,
+        },
+        FirstClassData::SPACE,
+    );
+    fcd
+}
+
+/// Harness with a `RewardVault` holding `vault`, a fee config, an `ETH-USD` feed
+/// updated by the owner and a `BTC-USD` feed aggregated from three validators
+/// with a quorum of two. `enable` turns rewards on.
+async fn rewards_with(vault: RewardVault) -> Rewards {
+    let validators: Vec<Keypair> = (0..3).map(|_| Keypair::new()).collect();
+    let keys = validators
+        .iter()
+        .map(|validator| validator.pubkey())
+        .collect();
+    let mut eth = Pubkey::default();
+    let mut harness = Harness::initialized_with(|program_test| {
+        // `set_rewards_enabled` and `set_fee_schedule` create them with `init_if_needed`
+        add_program_account(
+            program_test,
+            reward_vault_address(),
+            &vault,
+            RewardVault::SPACE,
+        );
+        add_program_account(
+            program_test,
+            fee_config_address(),
+            &FeeConfig::default(),
+            FeeConfig::SPACE,
+        );
+        add_program_account(
+            program_test,
+            validator_set_address(),
+            &ValidatorSet {
+                validators: keys,
+                quorum: 2,
+                window: 60,
+            },
+            ValidatorSet::SPACE,
+        );
+        eth = add_fcd(program_test, "ETH-USD", false);
+        let btc = add_fcd(program_test, "BTC-USD", true);
+        add_program_account(
+            program_test,
+            fcd_round_address(&btc),
+            &FcdRound {
+                fcd: btc,
+                ..FcdRound::default()
+            },
+            FcdRound::SPACE,
+        );
+    })
+    .await;
+    harness.set_time((START + 600).into()).await;
+    Rewards {
+        harness,
+        eth,
+        validators,
+    }
+}
+
+async fn rewards() -> Rewards {
+    rewards_with(RewardVault::default()).await
+}
+
+fn entry(key: Pubkey, points: u64, owed: u64) -> RewardEntry {
+    RewardEntry { key, points, owed }
+}
+
+impl Rewards {
+    async fn enable(&mut self) {
+        let owner = self.harness.owner();
+        self.harness
+            .process(&[set_rewards_enabled_ix(&owner.pubkey(), true)], &[&owner])
+            .await
+            .unwrap();
+    }
+
+    async fn vault(&mut self) -> RewardVault {
+        self.harness.account(reward_vault_address()).await
+    }
+
+    async fn balance(&mut self, address: Pubkey) -> u64 {
+        self.harness
+            .context
+            .banks_client
+            .get_balance(address)
+            .await
+            .unwrap()
+    }
+
+    /// Sends `lamports` from the payer, standing in for collected fees.
+    async fn fund(&mut self, address: Pubkey, lamports: u64) {
+        let payer = self.harness.context.payer.pubkey();
+        self.harness
+            .process(
+                &[system_instruction::transfer(&payer, &address, lamports)],
+                &[],
+            )
+            .await
+            .unwrap();
+    }
+
+    async fn update_eth(&mut self, timestamp: u32) {
+        let owner = self.harness.owner();
+        let ix = update_fcd_ix(&owner.pubkey(), self.eth, "ETH-USD", value(2), timestamp);
+        self.harness
+            .process(&[with_reward_vault(ix)], &[&owner])
+            .await
+            .unwrap();
+    }
+
+    async fn observe_btc(&mut self, validator: usize, timestamp: u32) {
+        let validator = clone_keypair(&self.validators[validator]);
+        let ix = submit_fcd_observation_ix(
+            &validator.pubkey(),
+            fcd_address("BTC-USD").0,
+            "BTC-USD",
+            value(3),
+            timestamp,
+        );
+        self.harness
+            .process(&[with_reward_vault(ix)], &[&validator])
+            .await
+            .unwrap();
+    }
+
+    async fn distribute(&mut self, amount: u64) {
+        let owner = self.harness.owner();
+        self.harness
+            .process(&[distribute_rewards_ix(&owner.pubkey(), amount)], &[&owner])
+            .await
+            .unwrap();
+    }
+
+    async fn claim(&mut self, claimant: &Keypair) -> u64 {
+        let balance = self.balance(claimant.pubkey()).await;
+        self.harness
+            .process(&[claim_rewards_ix(&claimant.pubkey())], &[claimant])
+            .await
+            .unwrap();
+        self.balance(claimant.pubkey()).await - balance
+    }
+
+    /// Lamports of the vault above its rent exemption.
+    async fn vault_balance(&mut self) -> u64 {
+        self.balance(reward_vault_address()).await
+            - Rent::default().minimum_balance(RewardVault::SPACE)
+    }
+}
+
+#[tokio::test]
+async fn sets_rewards_enabled() {
+    let mut rewards = rewards().await;
+    rewards.enable().await;
+    let status: Status = rewards.harness.account(status_address()).await;
+    assert!(status.rewards_enabled);
+
+    let owner = rewards.harness.owner();
+    rewards
+        .harness
+        .process(&[set_rewards_enabled_ix(&owner.pubkey(), false)], &[&owner])
+        .await
+        .unwrap();
+    let status: Status = rewards.harness.account(status_address()).await;
+    assert!(!status.rewards_enabled);
+    // submissions no longer take the vault
+    rewards.harness.submit(1, [1; 32], START).await.unwrap();
+    assert_eq!(rewards.vault().await.total_points, 0);
+}
+
+#[tokio::test]
+async fn fails_to_set_rewards_enabled_by_unauthorized_user() {
+    let mut rewards = rewards().await;
+    let intruder = rewards.harness.funded_keypair().await;
+
+    let result = rewards
+        .harness
+        .process(
+            &[set_rewards_enabled_ix(&intruder.pubkey(), true)],
+            &[&intruder],
+        )
+        .await;
+    assert_chain_error(result, ChainError::OnlyOwnerViolation);
+}
+
+#[tokio::test]
+async fn accrues_a_point_per_submitted_block() {
+    let mut rewards = rewards().await;
+    rewards.enable().await;
+    let owner = rewards.harness.owner();
+
+    for (block_id, timestamp) in [(1, START), (2, START + 100)] {
+        let ix = submit_ix(&owner.pubkey(), block_id, [1; 32], timestamp);
+        rewards
+            .harness
+            .process(&[with_reward_vault(ix)], &[&owner])
+            .await
+            .unwrap();
+    }
+
+    let vault = rewards.vault().await;
+    assert_eq!(vault.entries, vec![entry(owner.pubkey(), 2, 0)]);
+    assert_eq!(vault.total_points, 2);
+}
+
+#[tokio::test]
+async fn fails_to_submit_without_the_reward_vault() {
+    let mut rewards = rewards().await;
+    rewards.enable().await;
+
+    let result = rewards.harness.submit(1, [1; 32], START).await;
+    assert_chain_error(result, ChainError::MissingRewardVault);
+
+    // nor with another account in its place
+    let owner = rewards.harness.owner();
+    let ix = with_history(
+        submit_ix(&owner.pubkey(), 1, [1; 32], START),
+        fee_config_address(),
+    );
+    let result = rewards.harness.process(&[ix], &[&owner]).await;
+    assert_chain_error(result, ChainError::MissingRewardVault);
+}
+
+#[tokio::test]
+async fn accrues_to_the_owner_on_fcd_updates() {
+    let mut rewards = rewards().await;
+    rewards.enable().await;
+
+    rewards.update_eth(START + 100).await;
+
+    let vault = rewards.vault().await;
+    assert_eq!(
+        vault.entries,
+        vec![entry(rewards.harness.owner.pubkey(), 1, 0)]
+    );
+}
+
+#[tokio::test]
+async fn accrues_to_every_validator_of_a_finalized_round() {
+    let mut rewards = rewards().await;
+    rewards.enable().await;
+
+    rewards.observe_btc(0, START + 100).await;
+    assert_eq!(rewards.vault().await.total_points, 0);
+    rewards.observe_btc(2, START + 100).await;
+
+    let vault = rewards.vault().await;
+    assert_eq!(
+        vault.entries,
+        vec![
+            entry(rewards.validators[0].pubkey(), 1, 0),
+            entry(rewards.validators[2].pubkey(), 1, 0),
+        ]
+    );
+    assert_eq!(vault.total_points, 2);
+}
+
+#[tokio::test]
+async fn distributes_fees_in_proportion_to_points() {
+    let (a, b, c) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let mut rewards = rewards_with(RewardVault {
+        total_points: 4,
+        undistributed: 0,
+        entries: vec![entry(a, 3, 0), entry(b, 1, 10), entry(c, 0, 0)],
+    })
+    .await;
+    rewards.fund(fee_config_address(), 1_001).await;
+    let collected = rewards.balance(fee_config_address()).await;
+
+    rewards.distribute(1_001).await;
+
+    let vault = rewards.vault().await;
+    // 1001 * 3 / 4 and 1001 / 4 rounded down, the remaining lamport carried over
+    assert_eq!(
+        vault.entries,
+        vec![entry(a, 0, 750), entry(b, 0, 260), entry(c, 0, 0)]
+    );
+    assert_eq!(vault.undistributed, 1);
+    assert_eq!(vault.total_points, 0);
+    assert_eq!(
+        rewards.balance(fee_config_address()).await,
+        collected - 1_001
+    );
+    assert_eq!(rewards.vault_balance().await, 1_001);
+}
+
+#[tokio::test]
+async fn keeps_rewards_undistributed_without_points() {
+    let mut rewards = rewards().await;
+    rewards.fund(fee_config_address(), 500).await;
+
+    rewards.distribute(500).await;
+    let vault = rewards.vault().await;
+    assert_eq!(vault.undistributed, 500);
+    assert!(vault.entries.is_empty());
+
+    // carried into the next distribution once points accrued
+    rewards.enable().await;
+    rewards.update_eth(START + 100).await;
+    rewards.distribute(0).await;
+    let vault = rewards.vault().await;
+    assert_eq!(
+        vault.entries,
+        vec![entry(rewards.harness.owner.pubkey(), 0, 500)]
+    );
+    assert_eq!(vault.undistributed, 0);
+}
+
+#[tokio::test]
+async fn fails_to_distribute_more_than_the_collected_fees() {
+    let mut rewards = rewards().await;
+    rewards.fund(fee_config_address(), 500).await;
+    let owner = rewards.harness.owner();
+
+    // the config keeps its rent exemption
+    let result = rewards
+        .harness
+        .process(&[distribute_rewards_ix(&owner.pubkey(), 501)], &[&owner])
+        .await;
+    assert_chain_error(result, ChainError::InsufficientFees);
+}
+
+#[tokio::test]
+async fn fails_to_distribute_by_unauthorized_user() {
+    let mut rewards = rewards().await;
+    let intruder = rewards.harness.funded_keypair().await;
+
+    let result = rewards
+        .harness
+        .process(
+            &[distribute_rewards_ix(&intruder.pubkey(), 0)],
+            &[&intruder],
+        )
+        .await;
+    assert_chain_error(result, ChainError::OnlyOwnerViolation);
+}
+
+#[tokio::test]
+async fn claims_rewards() {
+    let claimant = Keypair::new();
+    let other = Pubkey::new_unique();
+    let mut rewards = rewards_with(RewardVault {
+        total_points: 2,
+        undistributed: 0,
+        entries: vec![entry(claimant.pubkey(), 0, 700), entry(other, 2, 300)],
+    })
+    .await;
+    rewards.fund(reward_vault_address(), 1_000).await;
+    rewards.fund(claimant.pubkey(), 1_000_000).await;
+
+    assert_eq!(rewards.claim(&claimant).await, 700);
+
+    // the claimant's entry had no points left and makes room for other keys
+    let vault = rewards.vault().await;
+    assert_eq!(vault.entries, vec![entry(other, 2, 300)]);
+    assert_eq!(rewards.vault_balance().await, 300);
+
+    let stranger = rewards.harness.funded_keypair().await;
+    let result = rewards
+        .harness
+        .process(&[claim_rewards_ix(&stranger.pubkey())], &[&stranger])
+        .await;
+    assert_chain_error(result, ChainError::NoRewards);
+}
+
+#[tokio::test]
+async fn accounts_for_every_lamport_from_fees_to_claims() {
+    let mut rewards = rewards().await;
+    rewards.enable().await;
+    let owner = rewards.harness.owner();
+
+    // the owner submits a block and updates a feed, two validators finalize a round
+    let ix = submit_ix(&owner.pubkey(), 1, [1; 32], START);
+    rewards
+        .harness
+        .process(&[with_reward_vault(ix)], &[&owner])
+        .await
+        .unwrap();
+    rewards.update_eth(START + 100).await;
+    rewards.observe_btc(0, START + 100).await;
+    rewards.observe_btc(1, START + 100).await;
+
+    rewards.fund(fee_config_address(), 10_001).await;
+    rewards.distribute(10_001).await;
+
+    let vault = rewards.vault().await;
+    let owed: u64 = vault.entries.iter().map(|entry| entry.owed).sum();
+    assert_eq!(owed + vault.undistributed, 10_001);
+    assert_eq!(rewards.vault_balance().await, 10_001);
+    assert_eq!(vault.owed(&owner.pubkey()), 5_000);
+
+    let validators: Vec<Keypair> = rewards.validators.iter().map(clone_keypair).collect();
+    for validator in &validators {
+        rewards.fund(validator.pubkey(), 1_000_000).await;
+    }
+    assert_eq!(rewards.claim(&validators[0]).await, 2_500);
+    assert_eq!(rewards.claim(&validators[1]).await, 2_500);
+    let result = rewards
+        .harness
+        .process(
+            &[claim_rewards_ix(&validators[2].pubkey())],
+            &[&validators[2]],
+        )
+        .await;
+    assert_chain_error(result, ChainError::NoRewards);
+
+    // the owner's share and the rounding remainder stay in the vault
+    assert_eq!(rewards.vault_balance().await, 5_001);
+    assert_eq!(rewards.vault().await.undistributed, 1);
+}
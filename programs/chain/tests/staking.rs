@@ -0,0 +1,603 @@
+#![cfg(any(feature = "test-bpf", feature = "test-sbf"))]
+
+mod common;
+
+use anchor_lang::prelude::*;
+use chain::errors::ChainError;
+use chain::state::{Block, SlashedBlock, StakeConfig, ValidatorSet, ValidatorStake};
+use common::*;
+use solana_sdk::signature::{Keypair, Signer};
+
+const START: i64 = 1651640000;
+const UNBONDING_PERIOD: u32 = 3600;
+const STAKED: u64 = 1_000;
+// latest submitted block, after blocks 7 and 8, all with root `value(1)`
+const LAST_BLOCK_ID: u32 = 10;
+
+struct Staking {
+    harness: Harness,
+    mint: Pubkey,
+    vault: Pubkey,
+    treasury: Pubkey,
+    // token account of the first validator, holding 500 tokens
+    source: Pubkey,
+    validators: Vec<Keypair>,
+}
+
+/// Harness with a `StakeConfig` slashing half the stake and three validators with
+/// stake accounts, where the first staked `STAKED` tokens. The last one was removed
+/// from the `ValidatorSet`. Blocks 7, 8 and `LAST_BLOCK_ID` have been submitted with
+/// root `1`.
+async fn staking() -> Staking {
+    let validators: Vec<Keypair> = (0..3).map(|_| Keypair::new()).collect();
+    let keys: Vec<Pubkey> = validators
+        .iter()
+        .map(|validator| validator.pubkey())
+        .collect();
+    let (mut mint, mut vault, mut treasury, mut source) = Default::default();
+    let mut harness = Harness::initialized_with(|program_test| {
+        add_token_program(program_test);
+        mint = add_mint(program_test);
+        vault = add_token_account(program_test, mint, stake_config_address(), STAKED);
+        treasury = add_token_account(program_test, mint, Pubkey::new_unique(), 0);
+        source = add_token_account(program_test, mint, keys[0], 500);
+        // `set_stake_config`, `stake` and `set_validators` create them with `init_if_needed`
+        add_program_account(
+            program_test,
+            stake_config_address(),
+            &StakeConfig {
+                mint,
+                vault,
+                treasury,
+                slash_bps: 5_000,
+                unbonding_period: UNBONDING_PERIOD,
+            },
+            StakeConfig::SPACE,
+        );
+        for (index, key) in keys.iter().enumerate() {
+            add_program_account(
+                program_test,
+                validator_stake_address(key),
+                &ValidatorStake {
+                    validator: *key,
+                    amount: if index == 0 { STAKED } else { 0 },
+                    ..ValidatorStake::default()
+                },
+                ValidatorStake::SPACE,
+            );
+        }
+        add_program_account(
+            program_test,
+            validator_set_address(),
+            &ValidatorSet {
+                validators: keys[..2].to_vec(),
+                quorum: 2,
+                window: 60,
+            },
+            ValidatorSet::SPACE,
+        );
+        for block_id in [7, 8] {
+            add_program_account(
+                program_test,
+                block_address(block_id).0,
+                &Block {
+                    block_id,
+                    root: value(1),
+                    timestamp: START as u32 - 100,
+                    hash_scheme: 0,
+                },
+                8 + 4 + 32 + 4 + 1,
+            );
+        }
+    })
+    .await;
+    harness.set_time(START).await;
+    harness
+        .submit(LAST_BLOCK_ID, value(1), START as u32)
+        .await
+        .unwrap();
+    for validator in &validators {
+        let payer = harness.context.payer.pubkey();
+        harness
+            .process(
+                &[solana_sdk::system_instruction::transfer(
+                    &payer,
+                    &validator.pubkey(),
+                    1_000_000_000,
+                )],
+                &[],
+            )
+            .await
+            .unwrap();
+    }
+    Staking {
+        harness,
+        mint,
+        vault,
+        treasury,
+        source,
+        validators,
+    }
+}
+
+impl Staking {
+    async fn stake_of(&mut self, validator: &Pubkey) -> ValidatorStake {
+        self.harness
+            .account(validator_stake_address(validator))
+            .await
+    }
+
+    /// Reports the first validator for attesting roots `1` and `second` of `block_id`,
+    /// with the attestations signed by `signer`.
+    async fn report(
+        &mut self,
+        reporter: &Keypair,
+        signer: &Keypair,
+        block_id: u32,
+        second: u128,
+    ) -> std::result::Result<(), solana_sdk::transport::TransportError> {
+        let validator = self.validators[0].pubkey();
+        self.harness
+            .process(
+                &[
+                    attestation_ix(signer, block_id, value(1)),
+                    attestation_ix(signer, block_id, value(second)),
+                    report_equivocation_ix(
+                        &reporter.pubkey(),
+                        validator,
+                        self.vault,
+                        self.treasury,
+                        block_id,
+                        (value(1), value(second)),
+                    ),
+                ],
+                &[reporter],
+            )
+            .await
+    }
+}
+
+#[tokio::test]
+async fn sets_stake_config() {
+    let mut staking = staking().await;
+    let owner = staking.harness.owner();
+
+    staking
+        .harness
+        .process(
+            &[set_stake_config_ix(
+                &owner.pubkey(),
+                staking.vault,
+                staking.treasury,
+                1_000,
+                60,
+            )],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+
+    let config: StakeConfig = staking.harness.account(stake_config_address()).await;
+    assert_eq!(config.mint, staking.mint);
+    assert_eq!(config.vault, staking.vault);
+    assert_eq!(config.treasury, staking.treasury);
+    assert_eq!(config.slash_bps, 1_000);
+    assert_eq!(config.unbonding_period, 60);
+}
+
+#[tokio::test]
+async fn fails_to_set_stake_config_with_invalid_values() {
+    let mut staking = staking().await;
+    let owner = staking.harness.owner();
+
+    let result = staking
+        .harness
+        .process(
+            &[set_stake_config_ix(
+                &owner.pubkey(),
+                staking.vault,
+                staking.treasury,
+                10_001,
+                60,
+            )],
+            &[&owner],
+        )
+        .await;
+    assert_chain_error(result, ChainError::InvalidSlashRate);
+
+    // the source isn't owned by the stake config
+    let result = staking
+        .harness
+        .process(
+            &[set_stake_config_ix(
+                &owner.pubkey(),
+                staking.source,
+                staking.treasury,
+                1_000,
+                60,
+            )],
+            &[&owner],
+        )
+        .await;
+    assert_chain_error(result, ChainError::InvalidStakeVault);
+
+    let stranger = staking.harness.funded_keypair().await;
+    let result = staking
+        .harness
+        .process(
+            &[set_stake_config_ix(
+                &stranger.pubkey(),
+                staking.vault,
+                staking.treasury,
+                1_000,
+                60,
+            )],
+            &[&stranger],
+        )
+        .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn stakes() {
+    let mut staking = staking().await;
+    let validator = clone_keypair(&staking.validators[0]);
+
+    staking
+        .harness
+        .process(
+            &[stake_ix(
+                &validator.pubkey(),
+                staking.source,
+                staking.vault,
+                200,
+            )],
+            &[&validator],
+        )
+        .await
+        .unwrap();
+
+    let stake = staking.stake_of(&validator.pubkey()).await;
+    assert_eq!(stake.amount, STAKED + 200);
+    assert_eq!(staking.harness.tokens(staking.vault).await, STAKED + 200);
+    assert_eq!(staking.harness.tokens(staking.source).await, 300);
+}
+
+#[tokio::test]
+async fn fails_to_stake_outside_of_the_validator_set() {
+    let mut staking = staking().await;
+    let removed = clone_keypair(&staking.validators[2]);
+
+    let result = staking
+        .harness
+        .process(
+            &[stake_ix(
+                &removed.pubkey(),
+                staking.source,
+                staking.vault,
+                200,
+            )],
+            &[&removed],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::NotValidator);
+}
+
+#[tokio::test]
+async fn withdraws_stake_after_the_unbonding_period() {
+    let mut staking = staking().await;
+    let validator = clone_keypair(&staking.validators[0]);
+
+    let result = staking
+        .harness
+        .process(
+            &[
+                request_unstake_ix(&validator.pubkey(), 400),
+                withdraw_stake_ix(&validator.pubkey(), staking.vault, staking.source),
+            ],
+            &[&validator],
+        )
+        .await;
+    assert_chain_error(result, ChainError::StakeUnbonding);
+
+    staking
+        .harness
+        .process(
+            &[request_unstake_ix(&validator.pubkey(), 400)],
+            &[&validator],
+        )
+        .await
+        .unwrap();
+    let stake = staking.stake_of(&validator.pubkey()).await;
+    assert_eq!(stake.amount, STAKED - 400);
+    assert_eq!(stake.unbonding, 400);
+    assert_eq!(stake.unbonding_until, START + i64::from(UNBONDING_PERIOD));
+
+    staking
+        .harness
+        .set_time(START + i64::from(UNBONDING_PERIOD))
+        .await;
+    staking
+        .harness
+        .process(
+            &[withdraw_stake_ix(
+                &validator.pubkey(),
+                staking.vault,
+                staking.source,
+            )],
+            &[&validator],
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(staking.harness.tokens(staking.source).await, 900);
+    assert_eq!(staking.harness.tokens(staking.vault).await, STAKED - 400);
+    assert_eq!(staking.stake_of(&validator.pubkey()).await.unbonding, 0);
+}
+
+#[tokio::test]
+async fn fails_to_unstake_more_than_staked() {
+    let mut staking = staking().await;
+    let validator = clone_keypair(&staking.validators[0]);
+
+    let result = staking
+        .harness
+        .process(
+            &[request_unstake_ix(&validator.pubkey(), STAKED + 1)],
+            &[&validator],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::InsufficientStake);
+}
+
+#[tokio::test]
+async fn slashes_an_equivocating_validator() {
+    let mut staking = staking().await;
+    let validator = clone_keypair(&staking.validators[0]);
+    let reporter = staking.harness.funded_keypair().await;
+
+    staking.report(&reporter, &validator, 7, 2).await.unwrap();
+
+    let stake = staking.stake_of(&validator.pubkey()).await;
+    assert_eq!(stake.amount, STAKED / 2);
+    assert_eq!(staking.harness.tokens(staking.treasury).await, STAKED / 2);
+    assert_eq!(staking.harness.tokens(staking.vault).await, STAKED / 2);
+    let slashed_block: SlashedBlock = staking
+        .harness
+        .account(slashed_block_address(&validator.pubkey(), 7))
+        .await;
+    assert_eq!(slashed_block.reporter, reporter.pubkey());
+    assert_eq!(slashed_block.slashed, STAKED / 2);
+
+    // the same evidence can't be reported again
+    let other_reporter = staking.harness.funded_keypair().await;
+    let result = staking.report(&other_reporter, &validator, 7, 3).await;
+    assert!(result.is_err());
+    assert_eq!(
+        staking.stake_of(&validator.pubkey()).await.amount,
+        STAKED / 2
+    );
+
+    staking.report(&reporter, &validator, 8, 3).await.unwrap();
+    assert_eq!(
+        staking.stake_of(&validator.pubkey()).await.amount,
+        STAKED / 4
+    );
+}
+
+#[tokio::test]
+async fn slashes_equivocations_reported_out_of_order() {
+    let mut staking = staking().await;
+    let validator = clone_keypair(&staking.validators[0]);
+    let reporter = staking.harness.funded_keypair().await;
+
+    staking.report(&reporter, &validator, 8, 2).await.unwrap();
+    staking.report(&reporter, &validator, 7, 2).await.unwrap();
+
+    assert_eq!(
+        staking.stake_of(&validator.pubkey()).await.amount,
+        STAKED / 4
+    );
+    assert_eq!(
+        staking.harness.tokens(staking.treasury).await,
+        STAKED * 3 / 4
+    );
+}
+
+#[tokio::test]
+async fn fails_to_report_a_block_not_submitted() {
+    let mut staking = staking().await;
+    let validator = clone_keypair(&staking.validators[0]);
+
+    // a validator reporting itself far ahead can't shield earlier equivocations
+    let result = staking.report(&validator, &validator, u32::MAX, 2).await;
+    assert_eq!(
+        error_code(result),
+        u32::from(anchor_lang::error::ErrorCode::AccountNotInitialized)
+    );
+    let result = staking
+        .report(&validator, &validator, LAST_BLOCK_ID + 1, 2)
+        .await;
+    assert_eq!(
+        error_code(result),
+        u32::from(anchor_lang::error::ErrorCode::AccountNotInitialized)
+    );
+
+    let reporter = staking.harness.funded_keypair().await;
+    staking
+        .report(&reporter, &validator, LAST_BLOCK_ID, 2)
+        .await
+        .unwrap();
+    assert_eq!(
+        staking.stake_of(&validator.pubkey()).await.amount,
+        STAKED / 2
+    );
+}
+
+#[tokio::test]
+async fn fails_to_report_roots_other_than_the_submitted_one() {
+    let mut staking = staking().await;
+    let validator = clone_keypair(&staking.validators[0]);
+    let reporter = staking.harness.funded_keypair().await;
+
+    // block 7 was submitted with root 1, so neither root was ever in use
+    let result = staking
+        .harness
+        .process(
+            &[
+                attestation_ix(&validator, 7, value(2)),
+                attestation_ix(&validator, 7, value(3)),
+                report_equivocation_ix(
+                    &reporter.pubkey(),
+                    validator.pubkey(),
+                    staking.vault,
+                    staking.treasury,
+                    7,
+                    (value(2), value(3)),
+                ),
+            ],
+            &[&reporter],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::RootNotSubmitted);
+    assert_eq!(staking.stake_of(&validator.pubkey()).await.amount, STAKED);
+}
+
+#[tokio::test]
+async fn fails_to_report_a_validator_without_stake() {
+    let mut staking = staking().await;
+    let validator = clone_keypair(&staking.validators[1]);
+
+    let result = staking
+        .harness
+        .process(
+            &[
+                attestation_ix(&validator, 7, value(1)),
+                attestation_ix(&validator, 7, value(2)),
+                report_equivocation_ix(
+                    &validator.pubkey(),
+                    validator.pubkey(),
+                    staking.vault,
+                    staking.treasury,
+                    7,
+                    (value(1), value(2)),
+                ),
+            ],
+            &[&validator],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::NothingToSlash);
+    assert!(
+        !staking
+            .harness
+            .exists(slashed_block_address(&validator.pubkey(), 7))
+            .await
+    );
+}
+
+#[tokio::test]
+async fn slashes_unbonding_stake() {
+    let mut staking = staking().await;
+    let validator = clone_keypair(&staking.validators[0]);
+    let reporter = staking.harness.funded_keypair().await;
+
+    staking
+        .harness
+        .process(
+            &[request_unstake_ix(&validator.pubkey(), 800)],
+            &[&validator],
+        )
+        .await
+        .unwrap();
+    staking.report(&reporter, &validator, 7, 2).await.unwrap();
+
+    let stake = staking.stake_of(&validator.pubkey()).await;
+    assert_eq!(stake.amount, 0);
+    assert_eq!(stake.unbonding, 500);
+    assert_eq!(staking.harness.tokens(staking.treasury).await, STAKED / 2);
+}
+
+#[tokio::test]
+async fn fails_to_report_without_equivocation() {
+    let mut staking = staking().await;
+    let validator = clone_keypair(&staking.validators[0]);
+    let reporter = staking.harness.funded_keypair().await;
+
+    let result = staking.report(&reporter, &validator, 7, 1).await;
+
+    assert_chain_error(result, ChainError::NotEquivocation);
+}
+
+#[tokio::test]
+async fn fails_to_report_attestations_by_another_key() {
+    let mut staking = staking().await;
+    let other = clone_keypair(&staking.validators[1]);
+    let reporter = staking.harness.funded_keypair().await;
+
+    let result = staking.report(&reporter, &other, 7, 2).await;
+
+    assert_chain_error(result, ChainError::MissingAttestationSignature);
+}
+
+#[tokio::test]
+async fn fails_to_report_without_both_attestations() {
+    let mut staking = staking().await;
+    let validator = clone_keypair(&staking.validators[0]);
+    let reporter = staking.harness.funded_keypair().await;
+
+    let result = staking
+        .harness
+        .process(
+            &[
+                attestation_ix(&validator, 7, value(1)),
+                report_equivocation_ix(
+                    &reporter.pubkey(),
+                    validator.pubkey(),
+                    staking.vault,
+                    staking.treasury,
+                    7,
+                    (value(1), value(2)),
+                ),
+            ],
+            &[&reporter],
+        )
+        .await;
+
+    assert_chain_error(result, ChainError::MissingAttestationSignature);
+}
+
+#[tokio::test]
+async fn fails_to_report_with_an_invalid_signature() {
+    let mut staking = staking().await;
+    let validator = clone_keypair(&staking.validators[0]);
+    let reporter = staking.harness.funded_keypair().await;
+
+    let mut forged = attestation_ix(&validator, 7, value(2));
+    // the message is the last part of the instruction data
+    *forged.data.last_mut().unwrap() ^= 1;
+    let result = staking
+        .harness
+        .process(
+            &[
+                attestation_ix(&validator, 7, value(1)),
+                forged,
+                report_equivocation_ix(
+                    &reporter.pubkey(),
+                    validator.pubkey(),
+                    staking.vault,
+                    staking.treasury,
+                    7,
+                    (value(1), value(2)),
+                ),
+            ],
+            &[&reporter],
+        )
+        .await;
+
+    assert!(result.is_err());
+    assert_eq!(staking.stake_of(&validator.pubkey()).await.amount, STAKED);
+}
@@ -0,0 +1,263 @@
+#![cfg(any(feature = "test-bpf", feature = "test-sbf"))]
+
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::*;
+use chain::errors::ChainError;
+use chain::state::{
+    numeric_value, FcdHistory, FeedMetadata, FirstClassData, Observation, MAX_HISTORY_LENGTH,
+};
+use common::*;
+use solana_program_test::ProgramTest;
+use solana_sdk::signature::Signer;
+use solana_sdk::transport::TransportError;
+
+const START: u32 = 1651640000;
+
+/// Adds `key` with `n` set at `START`, a history holding that value and the
+/// given accumulator, the way `initialize_fcd_history` leaves them.
+fn add_fcd_with_history(
+    program_test: &mut ProgramTest,
+    key: &str,
+    n: u128,
+    cumulative: u128,
+) -> Pubkey {
+    let fcd = fcd_address(key).0;
+    let value = if key.starts_with("FIXED_") {
+        [7; 32]
+    } else {
+        value(n)
+    };
+    add_program_account(
+        program_test,
+        fcd,
+        &FirstClassData {
+            key: key.to_string(),
+            value,
+            timestamp: START,
+            metadata: FeedMetadata::umbrella(key),
+            has_history: true,
+            cumulative,
+            aggregated: false,
+            confidence: [0u8; 32],
+            has_confidence: false,
+            registry_page: None,
+        },
+        FirstClassData::SPACE,
+    );
+    let mut history = FcdHistory {
+        fcd,
+        capacity: MAX_HISTORY_LENGTH,
+        ..FcdHistory::default()
+    };
+    history.push(Observation {
+        value,
+        timestamp: START,
+        cumulative,
+    });
+    add_program_account(
+        program_test,
+        fcd_history_address(&fcd),
+        &history,
+        FcdHistory::space(MAX_HISTORY_LENGTH),
+    );
+    fcd
+}
+
+async fn harness(n: u128, cumulative: u128) -> (Harness, Pubkey) {
+    let mut fcd = Pubkey::default();
+    let harness = Harness::initialized_with(|program_test| {
+        fcd = add_fcd_with_history(program_test, "ETH-USD", n, cumulative)
+    })
+    .await;
+    (harness, fcd)
+}
+
+/// Sets the feed to each `(n, seconds after START)` in turn.
+async fn update(harness: &mut Harness, fcd: Pubkey, values: &[(u128, u32)]) {
+    let owner = harness.owner();
+    for (n, offset) in values {
+        let ix = update_fcd_ix(&owner.pubkey(), fcd, "ETH-USD", value(*n), START + offset);
+        harness
+            .process(&[with_history(ix, fcd_history_address(&fcd))], &[&owner])
+            .await
+            .unwrap();
+    }
+}
+
+/// TWAP over `window` seconds up to `START + offset`, from the stored accounts.
+async fn twap(harness: &mut Harness, fcd: Pubkey, offset: u32, window: u32) -> Option<u128> {
+    let history: FcdHistory = harness.account(fcd_history_address(&fcd)).await;
+    let fcd: FirstClassData = harness.account(fcd).await;
+    history.twap(&fcd, START + offset, window)
+}
+
+fn get_twap_ix(fcd: Pubkey, key: &str, window_secs: u32) -> solana_sdk::instruction::Instruction {
+    instruction(
+        chain::accounts::GetTwap {
+            fcd,
+            history: fcd_history_address(&fcd),
+            status: status_address(),
+        },
+        chain::instruction::GetTwap {
+            key: key.to_string(),
+            window_secs,
+        },
+    )
+}
+
+async fn get_twap(
+    harness: &mut Harness,
+    fcd: Pubkey,
+    key: &str,
+    window_secs: u32,
+) -> std::result::Result<(), TransportError> {
+    harness
+        .process(&[get_twap_ix(fcd, key, window_secs)], &[])
+        .await
+}
+
+#[tokio::test]
+async fn averages_values_by_the_time_they_were_held() {
+    let (mut harness, fcd) = harness(100, 0).await;
+
+    update(&mut harness, fcd, &[(200, 100), (400, 300)]).await;
+
+    // 100 for 100s, 200 for 200s, 400 for 100s
+    assert_eq!(twap(&mut harness, fcd, 400, 400).await, Some(225));
+    // the window starts between observations: 200 for 100s, 400 for 100s
+    assert_eq!(twap(&mut harness, fcd, 400, 200).await, Some(300));
+    // the latest value carries on until now
+    assert_eq!(twap(&mut harness, fcd, 1000, 500).await, Some(400));
+    assert_eq!(twap(&mut harness, fcd, 400, 401).await, None);
+}
+
+#[tokio::test]
+async fn dampens_a_short_lived_outlier() {
+    let price = 1_000 * 10u128.pow(18);
+    let (mut harness, fcd) = harness(price, 0).await;
+
+    // steady updates every 10 minutes, with a 100x outlier held for 15 seconds
+    update(
+        &mut harness,
+        fcd,
+        &[
+            (price, 600),
+            (price, 1200),
+            (price, 1800),
+            (100 * price, 2400),
+            (price, 2415),
+            (price, 3000),
+        ],
+    )
+    .await;
+
+    let twap = twap(&mut harness, fcd, 3600, 3600).await.unwrap();
+    // the outlier moves the hourly average by 15/3600 of its deviation, 1.41x
+    // instead of 100x
+    assert_eq!(twap, price + 99 * price * 15 / 3600);
+    assert!(twap < price * 3 / 2);
+}
+
+#[tokio::test]
+async fn ignores_a_fresh_outlier_until_time_passes() {
+    let price = 1_000 * 10u128.pow(18);
+    let (mut harness, fcd) = harness(price, 0).await;
+
+    update(&mut harness, fcd, &[(100 * price, 3600)]).await;
+
+    // the spot value is 100x, but it hasn't been held for any time yet
+    assert_eq!(twap(&mut harness, fcd, 3600, 3600).await, Some(price));
+    assert_eq!(
+        twap(&mut harness, fcd, 3636, 3600).await,
+        Some(price + 99 * price / 100)
+    );
+}
+
+#[tokio::test]
+async fn stays_exact_when_the_accumulator_wraps() {
+    let (mut harness, fcd) = harness(3, u128::MAX - 50).await;
+
+    update(&mut harness, fcd, &[(5, 100)]).await;
+
+    let stored: FirstClassData = harness.account(fcd).await;
+    assert!(stored.cumulative < 300);
+    assert_eq!(twap(&mut harness, fcd, 200, 200).await, Some(4));
+}
+
+#[tokio::test]
+async fn rounds_down() {
+    let (mut harness, fcd) = harness(1, 0).await;
+
+    update(&mut harness, fcd, &[(2, 100)]).await;
+
+    assert_eq!(twap(&mut harness, fcd, 200, 200).await, Some(1));
+}
+
+#[tokio::test]
+async fn gets_the_twap_of_a_covered_window() {
+    let (mut harness, fcd) = harness(100, 0).await;
+    update(&mut harness, fcd, &[(200, 100)]).await;
+    harness.set_time((START + 200).into()).await;
+
+    get_twap(&mut harness, fcd, "ETH-USD", 200).await.unwrap();
+    let result = get_twap(&mut harness, fcd, "ETH-USD", 201).await;
+
+    assert_chain_error(result, ChainError::TwapWindowNotCovered);
+}
+
+#[tokio::test]
+async fn fails_to_get_the_twap_of_an_empty_window() {
+    let (mut harness, fcd) = harness(100, 0).await;
+
+    let result = get_twap(&mut harness, fcd, "ETH-USD", 0).await;
+
+    assert_chain_error(result, ChainError::InvalidTwapWindow);
+}
+
+#[tokio::test]
+async fn fails_to_get_the_twap_of_a_raw_feed() {
+    let mut fcd = Pubkey::default();
+    let mut harness = Harness::initialized_with(|program_test| {
+        fcd = add_fcd_with_history(program_test, "FIXED_ROOT", 0, 0)
+    })
+    .await;
+    harness.set_time((START + 100).into()).await;
+
+    let result = get_twap(&mut harness, fcd, "FIXED_ROOT", 100).await;
+
+    assert_chain_error(result, ChainError::TwapUnavailable);
+}
+
+#[tokio::test]
+async fn fails_to_get_the_twap_with_the_wrong_key() {
+    let (mut harness, fcd) = harness(100, 0).await;
+
+    let result = get_twap(&mut harness, fcd, "BTC-USD", 100).await;
+
+    assert_chain_error(result, ChainError::WrongFCDKeyForAccount);
+}
+
+#[tokio::test]
+async fn fails_to_get_the_twap_with_the_history_of_another_feed() {
+    let mut fcd = Pubkey::default();
+    let mut other = Pubkey::default();
+    let mut harness = Harness::initialized_with(|program_test| {
+        fcd = add_fcd_with_history(program_test, "ETH-USD", 100, 0);
+        other = add_fcd_with_history(program_test, "BTC-USD", 100, 0);
+    })
+    .await;
+    let mut ix = get_twap_ix(fcd, "ETH-USD", 100);
+    ix.accounts[1].pubkey = fcd_history_address(&other);
+
+    let result = harness.process(&[ix], &[]).await;
+
+    assert_eq!(error_code(result), u32::from(ErrorCode::ConstraintSeeds));
+}
+
+#[test]
+fn saturates_values_beyond_u128() {
+    assert_eq!(numeric_value(&value(42)), 42);
+    assert_eq!(numeric_value(&[0xff; 32]), u128::MAX);
+}
@@ -0,0 +1,391 @@
+#![cfg(any(feature = "test-bpf", feature = "test-sbf"))]
+
+mod common;
+
+use anchor_lang::InstructionData;
+use chain::errors::ChainError;
+use chain::merkle::{hash_message, HashScheme, DEFAULT_MAX_PROOF_LENGTH};
+use chain::state::VerifyResult;
+use common::*;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transport::TransportError;
+
+const ROOT: &str = "ff3a1d60902efa015c36f653c5d28e0b4a13bc5bdb8944b218fe2f6f6272b87a";
+const KEY: &str = "000000000000000000000000000000000000000000000031494e43482d444149";
+const VALUE: &str = "000000000000000000000000000000000000000000000000259ae7ce85275000";
+const PROOF: [&str; 12] = [
+    "8aa4e4134178289504b4b6c7c85527b41905cf3d51ad95eaec44a87fbe773b82",
+    "2555c92539183bfa28387c6e98403aeb44f8b7602d0580e4679f2432405b62b1",
+    "6bb2d161e2d374a8aa779e0c61ecef7e82b7a6ba6543bf997212ea164c7ec540",
+    "e3cd6c525d52487eb7439d1042dbd917a9b421fd2656a98a6f8af593fd4f4453",
+    "39afef9403f6ccd794a1bf6c48a55a0d4164d8ab9f32992410f62629bd57a6b7",
+    "72d0fddd950ac6ce7f54a48d4003843d526ee02fc21d8c305012bdd17f7058af",
+    "fb1199eb1639a574b06bd4f2fc619a9004fb55dd9016c6b24c4c79498a24099f",
+    "fa9e1fb3aa77f7249c18bd4dbd99bd9c3766a6bf6ab00eac7d5380732059566a",
+    "81b18433beaada4ee9a058a3eb1580498a61789809abb60517ec0ca5e0bcf948",
+    "a8440a4bf999006045d796a91e23fec4b23eee861ba9735d41dc804a76ae0643",
+    "dcec74631415edf80085bdb0907dfb4dd6928db21ebe31b201b1c61cd5a6b412",
+    "e1c181e05f242407fcce79feb83cad315d8d86e5d668f8fa8586d92f7eab082e",
+];
+
+fn hash(hex: &str) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+    for (i, byte) in hash.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap();
+    }
+    hash
+}
+
+fn proof() -> Vec<[u8; 32]> {
+    PROOF.iter().map(|element| hash(element)).collect()
+}
+
+async fn try_verify(
+    harness: &mut Harness,
+    block_id: u32,
+    proof: Vec<[u8; 32]>,
+    key: [u8; 32],
+    value: [u8; 32],
+) -> Result<bool, TransportError> {
+    let verify_result = Keypair::new();
+    let payer = harness.context.payer.pubkey();
+    harness
+        .process(
+            &[
+                initialize_verify_result_ix(&payer, &verify_result.pubkey()),
+                verify_ix(&verify_result.pubkey(), block_id, proof, key, value),
+            ],
+            &[&verify_result],
+        )
+        .await?;
+    let result: VerifyResult = harness.account(verify_result.pubkey()).await;
+    Ok(result.result)
+}
+
+async fn verify(
+    harness: &mut Harness,
+    block_id: u32,
+    proof: Vec<[u8; 32]>,
+    key: [u8; 32],
+    value: [u8; 32],
+) -> bool {
+    try_verify(harness, block_id, proof, key, value)
+        .await
+        .unwrap()
+}
+
+async fn try_verify_against_latest(
+    harness: &mut Harness,
+    block_id: u32,
+    proof: Vec<[u8; 32]>,
+    value: [u8; 32],
+    max_age: u32,
+) -> Result<bool, TransportError> {
+    let verify_result = Keypair::new();
+    let payer = harness.context.payer.pubkey();
+    harness
+        .process(
+            &[
+                initialize_verify_result_ix(&payer, &verify_result.pubkey()),
+                verify_against_latest_ix(
+                    &verify_result.pubkey(),
+                    block_id,
+                    proof,
+                    hash(KEY),
+                    value,
+                    max_age,
+                ),
+            ],
+            &[&verify_result],
+        )
+        .await?;
+    let result: VerifyResult = harness.account(verify_result.pubkey()).await;
+    Ok(result.result)
+}
+
+async fn try_verify_message(
+    harness: &mut Harness,
+    block_id: u32,
+    message: &[u8],
+    proof: Vec<[u8; 32]>,
+) -> Result<bool, TransportError> {
+    let verify_result = Keypair::new();
+    let payer = harness.context.payer.pubkey();
+    harness
+        .process(
+            &[
+                initialize_verify_result_ix(&payer, &verify_result.pubkey()),
+                verify_message_ix(&verify_result.pubkey(), block_id, message.to_vec(), proof),
+            ],
+            &[&verify_result],
+        )
+        .await?;
+    let result: VerifyResult = harness.account(verify_result.pubkey()).await;
+    Ok(result.result)
+}
+
+async fn verify_message(
+    harness: &mut Harness,
+    block_id: u32,
+    message: &[u8],
+    proof: Vec<[u8; 32]>,
+) -> bool {
+    try_verify_message(harness, block_id, message, proof)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn verifies_the_proof_of_a_submitted_block() {
+    let mut harness = Harness::initialized().await;
+    harness.submit(1335, hash(ROOT), 1651641200).await.unwrap();
+
+    assert!(verify(&mut harness, 1335, proof(), hash(KEY), hash(VALUE)).await);
+}
+
+#[tokio::test]
+async fn fails_for_false_proofs() {
+    let mut harness = Harness::initialized().await;
+    harness.submit(1336, hash(ROOT), 1651642200).await.unwrap();
+
+    let mut false_proof = proof();
+    false_proof[11] = hash("deadbeaf5f242407fcce79feb83cad315d8d86e5d668f8fa8586d92f7eab082e");
+    assert!(!verify(&mut harness, 1336, false_proof, hash(KEY), hash(VALUE)).await);
+    assert!(!verify(&mut harness, 1336, proof(), hash(KEY), value(1)).await);
+}
+
+#[tokio::test]
+async fn fails_for_a_tampered_block() {
+    let mut harness = Harness::initialized().await;
+    let mut root = hash(ROOT);
+    root[..4].copy_from_slice(&[0xde, 0xad, 0xbe, 0xaf]);
+    harness.submit(1337, root, 1651643200).await.unwrap();
+
+    assert!(!verify(&mut harness, 1337, proof(), hash(KEY), hash(VALUE)).await);
+}
+
+#[tokio::test]
+async fn verifies_with_the_hash_scheme_of_the_block() {
+    let mut harness = Harness::initialized().await;
+    let owner = harness.owner();
+    let scheme = HashScheme::Prefixed;
+    let leaves: Vec<[u8; 32]> = (0..3u8)
+        .map(|i| scheme.hash_leaf([i; 32], value(i.into())))
+        .collect();
+    let root = scheme.hash_node(scheme.hash_node(leaves[0], leaves[1]), leaves[2]);
+    harness
+        .process(
+            &[submit_with_hash_scheme_ix(
+                &owner.pubkey(),
+                1338,
+                root,
+                1651644200,
+                scheme as u8,
+            )],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+
+    assert!(
+        verify(
+            &mut harness,
+            1338,
+            vec![leaves[1], leaves[2]],
+            [0; 32],
+            value(0)
+        )
+        .await
+    );
+    assert!(verify(&mut harness, 1338, vec![leaves[2]], [2; 32], value(2)).await);
+    assert!(
+        !verify(
+            &mut harness,
+            1338,
+            vec![leaves[2]],
+            leaves[0].min(leaves[1]),
+            leaves[0].max(leaves[1])
+        )
+        .await
+    );
+}
+
+#[tokio::test]
+async fn fails_for_a_block_account_with_other_seeds() {
+    let mut harness = Harness::initialized().await;
+    harness.submit(1335, hash(ROOT), 1651641200).await.unwrap();
+    let verify_result = Keypair::new();
+    let payer = harness.context.payer.pubkey();
+
+    let mut ix = verify_ix(
+        &verify_result.pubkey(),
+        1335,
+        proof(),
+        hash(KEY),
+        hash(VALUE),
+    );
+    ix.data = chain::instruction::VerifyProofForBlock {
+        seed: block_seed(1336),
+        proof: proof(),
+        key: hash(KEY),
+        value: hash(VALUE),
+    }
+    .data();
+    let result = harness
+        .process(
+            &[
+                initialize_verify_result_ix(&payer, &verify_result.pubkey()),
+                ix,
+            ],
+            &[&verify_result],
+        )
+        .await;
+
+    assert_eq!(
+        error_code(result),
+        u32::from(anchor_lang::error::ErrorCode::ConstraintSeeds)
+    );
+}
+
+#[tokio::test]
+async fn fails_for_proofs_longer_than_the_default_maximum() {
+    let mut harness = Harness::initialized().await;
+    harness.submit(1335, hash(ROOT), 1651641200).await.unwrap();
+    let long_proof = vec![[0; 32]; usize::from(DEFAULT_MAX_PROOF_LENGTH) + 1];
+
+    let result = try_verify(&mut harness, 1335, long_proof, hash(KEY), hash(VALUE)).await;
+
+    assert_chain_error(result.map(|_| ()), ChainError::ProofTooLong);
+    let max_proof = vec![[0; 32]; usize::from(DEFAULT_MAX_PROOF_LENGTH)];
+    assert!(!verify(&mut harness, 1335, max_proof, hash(KEY), hash(VALUE)).await);
+}
+
+#[tokio::test]
+async fn fails_for_proofs_longer_than_the_configured_maximum() {
+    let mut harness = Harness::initialized().await;
+    let owner = harness.owner();
+    harness.submit(1335, hash(ROOT), 1651641200).await.unwrap();
+
+    harness
+        .process(&[set_max_proof_length_ix(&owner.pubkey(), 12)], &[&owner])
+        .await
+        .unwrap();
+    assert!(verify(&mut harness, 1335, proof(), hash(KEY), hash(VALUE)).await);
+
+    harness
+        .process(&[set_max_proof_length_ix(&owner.pubkey(), 11)], &[&owner])
+        .await
+        .unwrap();
+    let result = try_verify(&mut harness, 1335, proof(), hash(KEY), hash(VALUE)).await;
+    assert_chain_error(result.map(|_| ()), ChainError::ProofTooLong);
+}
+
+#[tokio::test]
+async fn verifies_against_the_latest_block() {
+    let mut harness = Harness::initialized().await;
+    harness.submit(1335, hash(ROOT), 1651641200).await.unwrap();
+    harness.set_time(1651641260).await;
+
+    assert!(
+        try_verify_against_latest(&mut harness, 1335, proof(), hash(VALUE), 60)
+            .await
+            .unwrap()
+    );
+    assert!(
+        !try_verify_against_latest(&mut harness, 1335, proof(), value(1), 60)
+            .await
+            .unwrap()
+    );
+}
+
+#[tokio::test]
+async fn fails_to_verify_against_a_stale_latest_block() {
+    let mut harness = Harness::initialized().await;
+    harness.submit(1335, hash(ROOT), 1651641200).await.unwrap();
+    harness.set_time(1651641261).await;
+
+    let result = try_verify_against_latest(&mut harness, 1335, proof(), hash(VALUE), 60).await;
+
+    assert_chain_error(result.map(|_| ()), ChainError::StaleBlock);
+}
+
+#[tokio::test]
+async fn fails_to_verify_against_a_block_other_than_the_latest() {
+    let mut harness = Harness::initialized().await;
+    harness.submit(1335, hash(ROOT), 1651641200).await.unwrap();
+    harness.submit(1336, [0; 32], 1651642200).await.unwrap();
+    harness.set_time(1651642200).await;
+
+    let result = try_verify_against_latest(&mut harness, 1335, proof(), hash(VALUE), 3600).await;
+
+    assert_eq!(
+        error_code(result.map(|_| ())),
+        u32::from(anchor_lang::error::ErrorCode::ConstraintSeeds)
+    );
+}
+
+#[tokio::test]
+async fn verifies_messages_of_a_submitted_block() {
+    let mut harness = Harness::initialized().await;
+    let messages: Vec<Vec<u8>> = vec![b"transfer 100 UMB".to_vec(), vec![7; 300], vec![]];
+    let leaves: Vec<[u8; 32]> = messages.iter().map(|m| hash_message(m)).collect();
+    let scheme = HashScheme::Legacy;
+    let left = scheme.hash_node(leaves[0], leaves[1]);
+    harness
+        .submit(1335, scheme.hash_node(left, leaves[2]), 1651641200)
+        .await
+        .unwrap();
+
+    assert!(verify_message(&mut harness, 1335, &messages[0], vec![leaves[1], leaves[2]]).await);
+    assert!(verify_message(&mut harness, 1335, &messages[1], vec![leaves[0], leaves[2]]).await);
+    assert!(verify_message(&mut harness, 1335, &messages[2], vec![left]).await);
+    assert!(
+        !verify_message(
+            &mut harness,
+            1335,
+            b"transfer 900 UMB",
+            vec![leaves[1], leaves[2]]
+        )
+        .await
+    );
+    assert!(!verify_message(&mut harness, 1335, &messages[0], vec![leaves[2]]).await);
+}
+
+#[tokio::test]
+async fn verifies_messages_next_to_key_value_leaves() {
+    let mut harness = Harness::initialized().await;
+    let owner = harness.owner();
+    let scheme = HashScheme::Prefixed;
+    let message = [[1; 32], value(1)].concat();
+    let leaves = [hash_message(&message), scheme.hash_leaf([1; 32], value(1))];
+    harness
+        .process(
+            &[submit_with_hash_scheme_ix(
+                &owner.pubkey(),
+                1338,
+                scheme.hash_node(leaves[0], leaves[1]),
+                1651644200,
+                scheme as u8,
+            )],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+
+    assert!(verify_message(&mut harness, 1338, &message, vec![leaves[1]]).await);
+    assert!(verify(&mut harness, 1338, vec![leaves[0]], [1; 32], value(1)).await);
+    // the same bytes as a key/value leaf, or as a message in place of one
+    assert!(!verify(&mut harness, 1338, vec![leaves[1]], [1; 32], value(1)).await);
+    assert!(!verify_message(&mut harness, 1338, &message, vec![leaves[0]]).await);
+}
+
+#[tokio::test]
+async fn fails_for_message_proofs_longer_than_the_maximum() {
+    let mut harness = Harness::initialized().await;
+    harness.submit(1335, hash(ROOT), 1651641200).await.unwrap();
+    let long_proof = vec![[0; 32]; usize::from(DEFAULT_MAX_PROOF_LENGTH) + 1];
+
+    let result = try_verify_message(&mut harness, 1335, b"message", long_proof).await;
+
+    assert_chain_error(result.map(|_| ()), ChainError::ProofTooLong);
+}
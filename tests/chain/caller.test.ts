@@ -1,6 +1,6 @@
 import * as anchor from '@project-serum/anchor';
-import {Program} from '@project-serum/anchor';
-import {PublicKey, SystemProgram} from '@solana/web3.js';
+import {Program, BN} from '@project-serum/anchor';
+import {PublicKey, SystemProgram, Keypair, LAMPORTS_PER_SOL} from '@solana/web3.js';
 import {Chain} from '../../target/types/chain';
 import {Caller} from '../../target/types/caller';
 import {expect} from 'chai';
@@ -9,28 +9,51 @@ import {
   getPublicKeyForSeed,
   getAddressFromToml,
   derivePDAFromBlockId,
+  derivePDAFromFCDKey,
+  deriveClosedFCDPDA,
+  deriveFeedRegistryPDA,
   encodeBlockRoot,
+  encodeDataValue,
 } from '../utils';
 
 const provider: anchor.AnchorProvider = anchor.AnchorProvider.env();
 anchor.setProvider(provider);
 
+// block root, key, value and proof of 1INCH-DAI = 2.709733 on the Umbrella sidechain
+const ROOT = '0xff3a1d60902efa015c36f653c5d28e0b4a13bc5bdb8944b218fe2f6f6272b87a';
+const KEY = '1INCH-DAI';
+const VALUE = '0x000000000000000000000000000000000000000000000000259ae7ce85275000';
+const PROOF = [
+  '0x8aa4e4134178289504b4b6c7c85527b41905cf3d51ad95eaec44a87fbe773b82',
+  '0x2555c92539183bfa28387c6e98403aeb44f8b7602d0580e4679f2432405b62b1',
+  '0x6bb2d161e2d374a8aa779e0c61ecef7e82b7a6ba6543bf997212ea164c7ec540',
+  '0xe3cd6c525d52487eb7439d1042dbd917a9b421fd2656a98a6f8af593fd4f4453',
+  '0x39afef9403f6ccd794a1bf6c48a55a0d4164d8ab9f32992410f62629bd57a6b7',
+  '0x72d0fddd950ac6ce7f54a48d4003843d526ee02fc21d8c305012bdd17f7058af',
+  '0xfb1199eb1639a574b06bd4f2fc619a9004fb55dd9016c6b24c4c79498a24099f',
+  '0xfa9e1fb3aa77f7249c18bd4dbd99bd9c3766a6bf6ab00eac7d5380732059566a',
+  '0x81b18433beaada4ee9a058a3eb1580498a61789809abb60517ec0ca5e0bcf948',
+  '0xa8440a4bf999006045d796a91e23fec4b23eee861ba9735d41dc804a76ae0643',
+  '0xdcec74631415edf80085bdb0907dfb4dd6928db21ebe31b201b1c61cd5a6b412',
+  '0xe1c181e05f242407fcce79feb83cad315d8d86e5d668f8fa8586d92f7eab082e',
+];
+
+// prices of feeds without a prefix have 18 decimals, `cents(150)` is 1.50
+const cents = (value: number): BN => new BN(value).mul(new BN(10).pow(new BN(16)));
+
 describe('caller', async () => {
 
   let callerProgram = anchor.workspace.Caller as Program<Caller>;
   let chainProgram = anchor.workspace.Chain as Program<Chain>;
 
+  const now = Math.floor(Date.now() / 1000);
+
   const createBlock = async (blockId: number, blockRoot: string, timestamp: number): Promise<[PublicKey, Buffer]> => {
     const [blockPda, seed] = await derivePDAFromBlockId(
       blockId,
       chainProgram.programId
     );
 
-    const [
-      authorityPda,
-      statusPda,
-    ] = await getStateStructPDAs(chainProgram.programId);
-
     await chainProgram.methods.submit(
       seed,
       blockId,
@@ -38,9 +61,9 @@ describe('caller', async () => {
       timestamp)
       .accounts({
         owner: provider.wallet.publicKey,
-        authority: authorityPda,
+        authority: await getPublicKeyForSeed('authority', chainProgram.programId),
         block: blockPda,
-        status: statusPda,
+        status: await getPublicKeyForSeed('status', chainProgram.programId),
         systemProgram: SystemProgram.programId,
       })
       .rpc({ commitment: "confirmed" })
@@ -51,43 +74,91 @@ describe('caller', async () => {
     ]
   }
 
-  const getStateStructPDAs = async (programIdArg) => {
-    const authorityPda = await getPublicKeyForSeed(
-      'authority',
-      programIdArg
+  const createFCD = async (key: string, value: number, timestamp: number): Promise<PublicKey> => {
+    const [fcdPda, seed] = await derivePDAFromFCDKey(
+      key,
+      chainProgram.programId
     );
 
-    const statusPda = await getPublicKeyForSeed(
-      'status',
-      programIdArg
-    );
+    await chainProgram.methods.initializeFirstClassData(
+      seed,
+      key,
+      encodeDataValue(value, key),
+      timestamp)
+      .accounts({
+        owner: provider.wallet.publicKey,
+        authority: await getPublicKeyForSeed('authority', chainProgram.programId),
+        fcd: fcdPda,
+        closed: await deriveClosedFCDPDA(fcdPda, chainProgram.programId),
+        registry: await deriveFeedRegistryPDA(0, chainProgram.programId),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc({ commitment: "confirmed" })
 
-    return [
-      authorityPda,
-      statusPda,
-    ];
+    return fcdPda;
   }
 
-  const getReturnLog = (confirmedTransaction) => {
-    const prefix = "Program return: ";
+  const createVault = async (key: string, minPrice: BN, maxPrice: BN, maxAge: number): Promise<Keypair> => {
+    const vault = Keypair.generate();
 
-    let log = confirmedTransaction.meta.logMessages.find((log) =>
-      log.startsWith(prefix)
-    );
+    await callerProgram.methods.initializeVault(key, minPrice, maxPrice, maxAge)
+      .accounts({
+        authority: provider.wallet.publicKey,
+        vault: vault.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([vault])
+      .rpc({ commitment: "confirmed" })
+
+    return vault;
+  }
 
-    log = log.slice(prefix.length);
-    const [key, data] = log.split(" ", 2);
-    const buffer = Buffer.from(data, "base64");
-    return [key, data, buffer];
-  };
+  const deposit = async (vault: Keypair, key: string, amount: number) => {
+    const [fcdPda, _] = await derivePDAFromFCDKey(key, chainProgram.programId);
 
-  let provider;
-  before(async () => {
-    provider = anchor.AnchorProvider.env();
-    anchor.setProvider(provider);
-  });
+    await callerProgram.methods.deposit(new BN(amount))
+      .accounts({
+        depositor: provider.wallet.publicKey,
+        vault: vault.publicKey,
+        fcd: fcdPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc({ commitment: "confirmed" })
+  }
 
-  const verifyResultAccount = anchor.web3.Keypair.generate();
+  const depositWithProof = async (vault: Keypair, value: string, proof: string[], amount: number) => {
+    await callerProgram.methods.depositWithProof(
+      blockSeed,
+      encodeBlockRoot(value),
+      proof.map(encodeBlockRoot),
+      new BN(amount))
+      .accounts({
+        depositor: provider.wallet.publicKey,
+        vault: vault.publicKey,
+        block: blockPda,
+        status: await getPublicKeyForSeed('status', chainProgram.programId),
+        verifyResult: verifyResultAccount.publicKey,
+        chainProgram: chainProgram.programId,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc({ commitment: "confirmed" })
+  }
+
+  const expectError = async (call: Promise<void>, error: string) => {
+    let failed = false;
+    try {
+      await call;
+    } catch(err) {
+      failed = true;
+      // the error message will include the custom program error
+      expect(err.toString().includes(error)).to.equal(true);
+    }
+    expect(failed).to.equal(true);
+  }
+
+  const verifyResultAccount = Keypair.generate();
+  let blockPda: PublicKey;
+  let blockSeed: Buffer;
 
   it('deploys new program `caller`', async() => {
     let programId = callerProgram.programId;
@@ -95,7 +166,12 @@ describe('caller', async () => {
     expect(programId.toBase58()).to.equal(getAddressFromToml('caller'));
   });
 
-  it('initialize `VerifyResult` account', async () => {
+  it('prepares a fresh feed, a block and a `VerifyResult` account', async () => {
+    await createFCD('VAULT-USD', 1.5, now - 60);
+
+    // two minutes old, with the 1INCH-DAI proof
+    [blockPda, blockSeed] = await createBlock(1343, ROOT, now - 120);
+
     await chainProgram.methods.initializeVerifyResult()
       .accounts({
         verifyResult: verifyResultAccount.publicKey,
@@ -106,124 +182,140 @@ describe('caller', async () => {
       .rpc({ commitment: "confirmed" })
   });
 
-  it('verifies off-chain the proof of a submitted block', async () => {
-    const [blockPda, seed] = await createBlock(
-      1338,
-      '0xb54bfd1e031ee84e0e78b2a41d388df4ae165d4fa968a53a97ce39a4f33ec4a1',
-      1651644200
+  it('initializes a vault', async () => {
+    const vault = await createVault('VAULT-USD', cents(100), cents(200), 3600);
+
+    const account = await callerProgram.account.vault.fetch(vault.publicKey);
+    expect(account.authority.toBase58()).to.equal(provider.wallet.publicKey.toBase58());
+    expect(account.minPrice.eq(cents(100))).to.equal(true);
+    expect(account.maxPrice.eq(cents(200))).to.equal(true);
+    expect(account.maxAge).to.equal(3600);
+    expect(account.deposits.toNumber()).to.equal(0);
+  });
+
+  it('fails to initialize a vault with inverted bounds', async () => {
+    await expectError(
+      createVault('VAULT-USD', cents(200), cents(100), 3600).then(() => {}),
+      'InvalidBounds'
     );
+  });
 
-    const proofs = [
-      Buffer.from('0x55747576547286b610e889628b275a282f0ee916319ef219a5cf51ff94ef9179'.slice(2), "hex"),
-      Buffer.from('0xe2ea0a050e929e24840f8d2f358b4811fc57830b37f825e2804cfe1d8739e68d'.slice(2), "hex"),
-      Buffer.from('0x4fc70ae8789647370c93beb224cbf9f61f38618ea38be23087fc2f070c0efaf3'.slice(2), "hex"),
-    ]
+  it('accepts a deposit at a fresh first class data price within bounds', async () => {
+    const vault = await createVault('VAULT-USD', cents(100), cents(200), 3600);
+    const before = await provider.connection.getBalance(vault.publicKey);
 
-    const key   = Buffer.from('0x4900000000000000000000000000000000000000000000000000000000000000'.slice(2), "hex");
-    const value = Buffer.from('0x5800000000000000000000000000000000000000000000000000000000000000'.slice(2), "hex");
+    await deposit(vault, 'VAULT-USD', LAMPORTS_PER_SOL / 10);
 
-    const tx = await chainProgram.methods
-      .verifyProofForBlock(seed, proofs, key, value)
-      .accounts({
-          verifyResult: verifyResultAccount.publicKey,
-          block: blockPda,
-        })
-      .rpc({commitment: "confirmed"})
+    const account = await callerProgram.account.vault.fetch(vault.publicKey);
+    expect(account.deposits.toNumber()).to.equal(LAMPORTS_PER_SOL / 10);
+    expect(await provider.connection.getBalance(vault.publicKey)).to.equal(before + LAMPORTS_PER_SOL / 10);
+  });
 
-    let result = await chainProgram.account.verifyResult.fetch(verifyResultAccount.publicKey);
-    expect(result.result == true)
+  it('rejects a deposit at a first class data price out of bounds', async () => {
+    const vault = await createVault('VAULT-USD', cents(160), cents(200), 3600);
+
+    await expectError(deposit(vault, 'VAULT-USD', LAMPORTS_PER_SOL / 10), 'PriceOutOfBounds');
   });
 
-  it("verifies on-chain through CPI calls the proof of a submitted block", async () => {
-    const [blockPda, seed] = await createBlock(
-      1339,
-      '0xb54bfd1e031ee84e0e78b2a41d388df4ae165d4fa968a53a97ce39a4f33ec4a1',
-      1651645200
+  it('rejects a deposit at a stale first class data price', async () => {
+    const vault = await createVault('VAULT-USD', cents(100), cents(200), 30);
+
+    await expectError(deposit(vault, 'VAULT-USD', LAMPORTS_PER_SOL / 10), 'StalePrice');
+  });
+
+  it('rejects the first class data of another feed', async () => {
+    const vault = await createVault('VAULT-USD', cents(100), cents(200), 3600);
+    const [otherFcd, _] = await derivePDAFromFCDKey('ETH-USD', chainProgram.programId);
+
+    await expectError(
+      callerProgram.methods.deposit(new BN(LAMPORTS_PER_SOL / 10))
+        .accounts({
+          depositor: provider.wallet.publicKey,
+          vault: vault.publicKey,
+          fcd: otherFcd,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc({ commitment: "confirmed" })
+        .then(() => {}),
+      'ConstraintSeeds'
     );
+  });
 
-    let result = await chainProgram.account.verifyResult.fetch(verifyResultAccount.publicKey);
-    expect(result.result == false)
+  it('accepts a deposit at a price proven against a block', async () => {
+    const vault = await createVault(KEY, cents(200), cents(300), 3600);
 
-    let tx = await callerProgram.methods
-      .cpiCallVerifyTrue(seed)
-      .accounts({
-        cpiReturn: verifyResultAccount.publicKey,
-        cpiReturnProgram: chainProgram.programId,
-        block: blockPda,
-      })
-      .rpc({commitment: "confirmed"});
-  
-    result = await chainProgram.account.verifyResult.fetch(verifyResultAccount.publicKey);
-    expect(result.result == true)
+    await depositWithProof(vault, VALUE, PROOF, LAMPORTS_PER_SOL / 10);
+
+    const account = await callerProgram.account.vault.fetch(vault.publicKey);
+    expect(account.deposits.toNumber()).to.equal(LAMPORTS_PER_SOL / 10);
+    const result = await chainProgram.account.verifyResult.fetch(verifyResultAccount.publicKey);
+    expect(result.result).to.equal(true);
   });
 
-  it("fails to verify on-chain through CPI calls with tempered proofs", async () => {
-    const [blockPda, seed] = await createBlock(
-      1340,
-      '0xb54bfd1e031ee84e0e78b2a41d388df4ae165d4fa968a53a97ce39a4f33ec4a1',
-      1651646200
-    );
+  it('rejects a deposit with a tampered proof', async () => {
+    const vault = await createVault(KEY, cents(200), cents(300), 3600);
+    const proof = [...PROOF];
+    proof[11] = '0xdeadbeaf5f242407fcce79feb83cad315d8d86e5d668f8fa8586d92f7eab082e';
 
-    let result = await chainProgram.account.verifyResult.fetch(verifyResultAccount.publicKey);
-    expect(result.result == false)
+    await expectError(depositWithProof(vault, VALUE, proof, LAMPORTS_PER_SOL / 10), 'UnverifiedPrice');
+  });
 
-    let tx = await callerProgram.methods
-      .cpiCallVerifyFalseTemperedProofs(seed)
-      .accounts({
-        cpiReturn: verifyResultAccount.publicKey,
-        cpiReturnProgram: chainProgram.programId,
-        block: blockPda,
-      })
-      .rpc({commitment: "confirmed"});
-  
-    result = await chainProgram.account.verifyResult.fetch(verifyResultAccount.publicKey);
-    expect(result.result == false)
+  it('rejects a deposit at a tampered price', async () => {
+    const vault = await createVault(KEY, cents(200), cents(300), 3600);
+    const value = '0x000000000000000000000000000000000000000000000000259ae7cedeadbeaf';
+
+    await expectError(depositWithProof(vault, value, PROOF, LAMPORTS_PER_SOL / 10), 'UnverifiedPrice');
   });
 
-  it("fails to verify on-chain through CPI calls with tempered key", async () => {
-    const [blockPda, seed] = await createBlock(
-      1341,
-      '0xb54bfd1e031ee84e0e78b2a41d388df4ae165d4fa968a53a97ce39a4f33ec4a1',
-      1651647200
-    );
+  it('rejects a deposit at a proven price out of bounds', async () => {
+    const vault = await createVault(KEY, cents(100), cents(200), 3600);
 
-    let result = await chainProgram.account.verifyResult.fetch(verifyResultAccount.publicKey);
-    expect(result.result == false)
+    await expectError(depositWithProof(vault, VALUE, PROOF, LAMPORTS_PER_SOL / 10), 'PriceOutOfBounds');
+  });
 
-    let tx = await callerProgram.methods
-      .cpiCallVerifyFalseTemperedKey(seed)
-      .accounts({
-        cpiReturn: verifyResultAccount.publicKey,
-        cpiReturnProgram: chainProgram.programId,
-        block: blockPda,
-      })
-      .rpc({commitment: "confirmed"});
-  
-    result = await chainProgram.account.verifyResult.fetch(verifyResultAccount.publicKey);
-    expect(result.result == false)
+  it('rejects a deposit at a price proven against a stale block', async () => {
+    const vault = await createVault(KEY, cents(200), cents(300), 60);
+
+    await expectError(depositWithProof(vault, VALUE, PROOF, LAMPORTS_PER_SOL / 10), 'StalePrice');
   });
 
-  it("fails to verify on-chain through CPI calls with tempered value", async () => {
-    const [blockPda, seed] = await createBlock(
-      1342,
-      '0xb54bfd1e031ee84e0e78b2a41d388df4ae165d4fa968a53a97ce39a4f33ec4a1',
-      1651648200
+  it('withdraws deposits to the vault authority only', async () => {
+    const vault = await createVault('VAULT-USD', cents(100), cents(200), 3600);
+    await deposit(vault, 'VAULT-USD', LAMPORTS_PER_SOL / 10);
+
+    const stranger = Keypair.generate();
+    await expectError(
+      callerProgram.methods.withdraw(new BN(LAMPORTS_PER_SOL / 10))
+        .accounts({
+          authority: stranger.publicKey,
+          vault: vault.publicKey,
+        })
+        .signers([stranger])
+        .rpc({ commitment: "confirmed" })
+        .then(() => {}),
+      'ConstraintHasOne'
     );
 
-    let result = await chainProgram.account.verifyResult.fetch(verifyResultAccount.publicKey);
-    expect(result.result == false)
+    await expectError(
+      callerProgram.methods.withdraw(new BN(LAMPORTS_PER_SOL))
+        .accounts({
+          authority: provider.wallet.publicKey,
+          vault: vault.publicKey,
+        })
+        .rpc({ commitment: "confirmed" })
+        .then(() => {}),
+      'InsufficientFunds'
+    );
 
-    let tx = await callerProgram.methods
-      .cpiCallVerifyFalseTemperedValue(seed)
+    await callerProgram.methods.withdraw(new BN(LAMPORTS_PER_SOL / 10))
       .accounts({
-        cpiReturn: verifyResultAccount.publicKey,
-        cpiReturnProgram: chainProgram.programId,
-        block: blockPda,
+        authority: provider.wallet.publicKey,
+        vault: vault.publicKey,
       })
-      .rpc({commitment: "confirmed"});
-  
-    result = await chainProgram.account.verifyResult.fetch(verifyResultAccount.publicKey);
-    expect(result.result == false)
+      .rpc({ commitment: "confirmed" })
+
+    const rent = await provider.connection.getMinimumBalanceForRentExemption(8 + 32 + 32 + 16 + 16 + 4 + 8);
+    expect(await provider.connection.getBalance(vault.publicKey)).to.equal(rent);
   });
 
 });
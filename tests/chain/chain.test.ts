@@ -9,6 +9,8 @@ import {
   getAddressFromToml,
   derivePDAFromBlockId,
   derivePDAFromFCDKey,
+  deriveClosedFCDPDA,
+  deriveFeedRegistryPDA,
   encodeBlockRoot,
   decodeBlockRoot,
   encodeDataValue,
@@ -103,6 +105,22 @@ describe('chain', async () => {
       authorityPda
     ] = await getStateStructPDAs(programId);
 
+    // the few feeds of these tests all fit on the first registry page
+    const registryPda = await deriveFeedRegistryPDA(0, program.programId);
+    if (await provider.connection.getAccountInfo(registryPda) === null) {
+      await program.rpc.initializeFeedRegistryPage(
+        0,
+        {
+          accounts: {
+            owner: provider.wallet.publicKey,
+            authority: authorityPda,
+            registry: registryPda,
+            systemProgram: SystemProgram.programId,
+          },
+        },
+      );
+    }
+
     await program.rpc.initializeFirstClassData(
       seed,
       key,
@@ -113,6 +131,8 @@ describe('chain', async () => {
           owner: provider.wallet.publicKey,
           authority: authorityPda,
           fcd: fcdPda,
+          closed: await deriveClosedFCDPDA(fcdPda, program.programId),
+          registry: registryPda,
           systemProgram: SystemProgram.programId,
         },
       },
@@ -145,6 +145,7 @@ describe('verify', async () => {
       .accounts({
           verifyResult: verifyResultAccount.publicKey,
           block: blockPda,
+          status: await getPublicKeyForSeed('status', program.programId),
         })
       .rpc({commitment: "confirmed"})
 
@@ -182,6 +183,7 @@ describe('verify', async () => {
       .accounts({
           verifyResult: verifyResultAccount.publicKey,
           block: blockPda,
+          status: await getPublicKeyForSeed('status', program.programId),
         })
       .rpc({commitment: "confirmed"})
 
@@ -219,6 +221,7 @@ describe('verify', async () => {
       .accounts({
           verifyResult: verifyResultAccount.publicKey,
           block: blockPda,
+          status: await getPublicKeyForSeed('status', program.programId),
         })
       .rpc({commitment: "confirmed"})
 
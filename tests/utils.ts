@@ -48,6 +48,33 @@ export async function derivePDAFromFCDKey(
   return [publicKey, seed];
 }
 
+export async function deriveClosedFCDPDA(
+  fcdPda: PublicKey,
+  programId: PublicKey
+): Promise<PublicKey> {
+  const [publicKey] = await PublicKey.findProgramAddress(
+    [utils.bytes.utf8.encode('closed'), fcdPda.toBuffer()],
+    programId
+  );
+
+  return publicKey;
+}
+
+export async function deriveFeedRegistryPDA(
+  page: number,
+  programId: PublicKey
+): Promise<PublicKey> {
+  const pageSeed = Buffer.alloc(4);
+  pageSeed.writeUInt32LE(page);
+
+  const [publicKey] = await PublicKey.findProgramAddress(
+    [utils.bytes.utf8.encode('feed_registry'), pageSeed],
+    programId
+  );
+
+  return publicKey;
+}
+
 export function derivePDAFromSeed(
   seed: Buffer,
   programId: PublicKey